@@ -0,0 +1,33 @@
+/*
+ * C bindings for huff-snark-verifier, built with `cargo build --release --features ffi`.
+ *
+ * Every function returns 0 on success and writes its result to `out`. On failure,
+ * it returns 1 and writes the error message to `out` instead. Either way, the
+ * string written to `out` must be released with `huffv_free`.
+ */
+
+#ifndef HUFFV_H
+#define HUFFV_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Generates a Huff verifier contract from a snarkjs verification key JSON string. */
+int huffv_generate(const char *vkey_json, char **out);
+
+/* Packs a snarkjs verification key JSON string as the verifier's `VKEY` table. */
+int huffv_pack(const char *vkey_json, char **out);
+
+/* Encodes the calldata for verifying a snarkjs proof with the generated verifier.
+ * Public inputs are passed in reverse if `reverse_inputs` is non-zero. */
+int huffv_calldata(const char *proof_json, const char *public_json, int reverse_inputs, char **out);
+
+/* Releases a string written by one of the functions above. */
+void huffv_free(char *s);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* HUFFV_H */
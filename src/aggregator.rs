@@ -0,0 +1,75 @@
+use crate::codegen::{Abi, InputCommitment, VerifierOptions, VkeyStorage};
+use crate::error::{HuffvError, Result};
+use crate::utils::render_template;
+use crate::vkey::VerificationKey;
+use serde::Serialize;
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The ERC-4337 aggregator template contract
+pub static AGGREGATOR_CONTRACT: &str = include_str!("contracts/AggregatorTemplate.sol");
+
+////////////////////////////////////////////////////////////////
+//                         AGGREGATOR                         //
+////////////////////////////////////////////////////////////////
+
+/// Generates an ERC-4337 `IAggregator` for the verifier generated for `key` with
+/// `options`. Its aggregated signature is a proof whose public inputs are the hashes
+/// of the bundle's user operations, and `validateSignatures` checks it with the
+/// deployed verifier.
+///
+/// The aggregator calls the verifier from `view` functions and passes it a dynamic
+/// array of hashes, so the verifier must take `--abi huffv` and can't emit an event.
+pub fn generate_aggregator(key: &VerificationKey, options: &VerifierOptions) -> Result<String> {
+    if options.abi != Abi::Huffv {
+        return Err(HuffvError::InvalidOptions(
+            "The aggregator needs the dynamic public inputs array of `--abi huffv`.".to_string(),
+        ));
+    }
+    if options.vkey_storage == VkeyStorage::Calldata {
+        return Err(HuffvError::InvalidOptions(
+            "The aggregator checks the verifier's `VKEY_HASH()`, so the verifier can't take its key in calldata."
+                .to_string(),
+        ));
+    }
+    if options.event_signature.is_some() {
+        return Err(HuffvError::InvalidOptions(
+            "The aggregator calls the verifier from `view` functions, so it can't emit an event."
+                .to_string(),
+        ));
+    }
+
+    let n_inputs = key.ic.len().saturating_sub(1);
+    let context = AggregatorContext {
+        vkey_hash: hex::encode(key.hash()?),
+        signature: options.verify_signature(n_inputs),
+        selector: hex::encode(options.verify_selector(n_inputs)),
+        n_inputs,
+        n_proof_words: if options.compressed_proof { 4 } else { 8 },
+        compressed_proof: options.compressed_proof,
+        keccak_inputs: options.input_commitment == InputCommitment::Keccak,
+    };
+    render_template(AGGREGATOR_CONTRACT, &context)
+}
+
+/// The values the aggregator template is rendered with.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct AggregatorContext {
+    /// The keccak256 hash of the packed verification key, as hex without a `0x` prefix.
+    vkey_hash: String,
+    /// The signature of the verification function.
+    signature: String,
+    /// The selector of the verification function, as hex without a `0x` prefix.
+    selector: String,
+    /// The number of public inputs of the circuit.
+    n_inputs: usize,
+    /// The number of words in the aggregated signature's proof.
+    n_proof_words: usize,
+    /// Whether the proof is compressed.
+    compressed_proof: bool,
+    /// Whether the verifier commits to the hashes with keccak256.
+    keccak_inputs: bool,
+}
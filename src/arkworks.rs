@@ -0,0 +1,174 @@
+//! A reader for Groth16 verifying keys in the canonical serialization of
+//! [arkworks](https://github.com/arkworks-rs/groth16), for Rust provers that never
+//! export a snarkjs JSON key.
+//!
+//! An `ark_groth16::VerifyingKey<Bn254>` is serialized as its fields in order:
+//! `alpha_g1`, `beta_g2`, `gamma_g2`, `delta_g2`, and `gamma_abc_g1`, the ICs, as a
+//! little endian `u64` length and the points.
+
+use crate::error::{HuffvError, Result};
+use crate::vkey::{Curve, Protocol, VerificationKey};
+use ark_bn254::{Fq, G1Affine, G2Affine};
+use ark_ec::AffineRepr;
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, Compress, SerializationError, Validate};
+
+////////////////////////////////////////////////////////////////
+//                           READER                           //
+////////////////////////////////////////////////////////////////
+
+/// Reads an arkworks Groth16 BN254 verifying key, serialized with
+/// `serialize_compressed` or `serialize_uncompressed`.
+///
+/// The points are checked to be on their curves and in their prime order subgroups,
+/// and the key is converted to the coordinates snarkjs would export.
+pub fn read_arkworks_vkey(data: &[u8]) -> Result<VerificationKey> {
+    let (alpha, beta, gamma, delta, ic) = deserialize(data, Compress::Yes)
+        .or_else(|_| deserialize(data, Compress::No))
+        .map_err(|e| {
+            HuffvError::InvalidKey(format!(
+                "Not an arkworks Groth16 BN254 verifying key: {}",
+                e
+            ))
+        })?;
+    if ic.is_empty() {
+        return Err(HuffvError::InvalidKey("IC must not be empty.".to_string()));
+    }
+
+    Ok(VerificationKey {
+        protocol: Protocol::Groth16,
+        curve: Curve::Bn128,
+        n_public: ic.len() as u64 - 1,
+        vk_alpha_1: g1("alpha_g1", &alpha)?,
+        vk_beta_2: g2("beta_g2", &beta)?,
+        vk_gamma_2: g2("gamma_g2", &gamma)?,
+        vk_delta_2: g2("delta_g2", &delta)?,
+        vk_alphabeta_12: None,
+        ic: ic
+            .iter()
+            .enumerate()
+            .map(|(i, point)| g1(&format!("gamma_abc_g1[{}]", i), point))
+            .collect::<Result<Vec<_>>>()?,
+    })
+}
+
+/// The points of a verifying key
+type Points = (G1Affine, G2Affine, G2Affine, G2Affine, Vec<G1Affine>);
+
+/// Deserializes the points of a verifying key serialized with `compress`, failing
+/// unless they take up all of `data`.
+fn deserialize(
+    mut data: &[u8],
+    compress: Compress,
+) -> std::result::Result<Points, SerializationError> {
+    let reader = &mut data;
+    let alpha = G1Affine::deserialize_with_mode(&mut *reader, compress, Validate::Yes)?;
+    let beta = G2Affine::deserialize_with_mode(&mut *reader, compress, Validate::Yes)?;
+    let gamma = G2Affine::deserialize_with_mode(&mut *reader, compress, Validate::Yes)?;
+    let delta = G2Affine::deserialize_with_mode(&mut *reader, compress, Validate::Yes)?;
+    let ic = Vec::<G1Affine>::deserialize_with_mode(&mut *reader, compress, Validate::Yes)?;
+    if !reader.is_empty() {
+        return Err(SerializationError::InvalidData);
+    }
+    Ok((alpha, beta, gamma, delta, ic))
+}
+
+////////////////////////////////////////////////////////////////
+//                      HELPER FUNCTIONS                      //
+////////////////////////////////////////////////////////////////
+
+/// The snarkjs coordinates `[x, y, 1]` of the G1 point `name`.
+fn g1(name: &str, point: &G1Affine) -> Result<Vec<String>> {
+    let (x, y) = point.xy().ok_or_else(|| infinity(name))?;
+    Ok(vec![fq(x), fq(y), "1".to_string()])
+}
+
+/// The snarkjs coordinates `[[x.c0, x.c1], [y.c0, y.c1], [1, 0]]` of the G2 point
+/// `name`.
+fn g2(name: &str, point: &G2Affine) -> Result<Vec<Vec<String>>> {
+    let (x, y) = point.xy().ok_or_else(|| infinity(name))?;
+    Ok(vec![
+        vec![fq(&x.c0), fq(&x.c1)],
+        vec![fq(&y.c0), fq(&y.c1)],
+        vec!["1".to_string(), "0".to_string()],
+    ])
+}
+
+/// The error of the point `name` being the point at infinity, which no key has.
+fn infinity(name: &str) -> HuffvError {
+    HuffvError::InvalidKey(format!("{} is the point at infinity.", name))
+}
+
+/// `x` in decimal.
+fn fq(x: &Fq) -> String {
+    x.into_bigint().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::synthetic_circuit;
+    use ark_bn254::Fq2;
+    use ark_serialize::CanonicalSerialize;
+    use std::str::FromStr;
+
+    /// The G1 point of snarkjs coordinates `point`.
+    fn g1_point(point: &[String]) -> G1Affine {
+        G1Affine::new(
+            Fq::from_str(&point[0]).unwrap(),
+            Fq::from_str(&point[1]).unwrap(),
+        )
+    }
+
+    /// The G2 point of snarkjs coordinates `point`.
+    fn g2_point(point: &[Vec<String>]) -> G2Affine {
+        let fq2 =
+            |c: &[String]| Fq2::new(Fq::from_str(&c[0]).unwrap(), Fq::from_str(&c[1]).unwrap());
+        G2Affine::new(fq2(&point[0]), fq2(&point[1]))
+    }
+
+    /// Serializes the points of `key` as an arkworks verifying key, compressed or not.
+    fn serialize(key: &VerificationKey, compress: Compress) -> Vec<u8> {
+        let ic = key
+            .ic
+            .iter()
+            .map(|point| g1_point(point))
+            .collect::<Vec<_>>();
+        let mut data = Vec::new();
+        g1_point(&key.vk_alpha_1)
+            .serialize_with_mode(&mut data, compress)
+            .unwrap();
+        for point in [&key.vk_beta_2, &key.vk_gamma_2, &key.vk_delta_2] {
+            g2_point(point)
+                .serialize_with_mode(&mut data, compress)
+                .unwrap();
+        }
+        ic.serialize_with_mode(&mut data, compress).unwrap();
+        data
+    }
+
+    #[test]
+    fn serialized_keys_pack_to_the_same_key_as_their_json() {
+        let (key, _, _) = synthetic_circuit(3);
+        for compress in [Compress::Yes, Compress::No] {
+            let data = serialize(&key, compress);
+            let read = read_arkworks_vkey(&data).unwrap();
+            assert_eq!(read.n_public, key.n_public);
+            assert_eq!(read.ic, key.ic);
+            assert_eq!(read.to_packed().unwrap(), key.to_packed().unwrap());
+        }
+    }
+
+    #[test]
+    fn trailing_bytes_are_rejected() {
+        let (key, _, _) = synthetic_circuit(3);
+        for compress in [Compress::Yes, Compress::No] {
+            let mut data = serialize(&key, compress);
+            data.push(0);
+            assert!(matches!(
+                read_arkworks_vkey(&data),
+                Err(HuffvError::InvalidKey(_))
+            ));
+        }
+    }
+}
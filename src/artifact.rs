@@ -0,0 +1,263 @@
+use crate::codegen::{Abi, EvmVersion, OnFailure, Target, VerifierOptions, VkeyStorage};
+use crate::error::Result;
+use crate::vkey::{Curve, Protocol, VerificationKey};
+use serde::Serialize;
+use serde_json::{json, Value};
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The name of the generated verifier contract
+pub static CONTRACT_NAME: &str = "Verifier";
+
+/// The format of the artifacts, as recognized by Hardhat
+pub static ARTIFACT_FORMAT: &str = "hh-sol-artifact-1";
+
+////////////////////////////////////////////////////////////////
+//                          ARTIFACT                          //
+////////////////////////////////////////////////////////////////
+
+/// A Hardhat-style artifact describing a generated verifier.
+///
+/// `huffv` does not compile the verifier, so the bytecode fields are left as
+/// empty placeholders (`0x`) to be filled once the contract is compiled.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Artifact {
+    #[serde(rename = "_format")]
+    pub format: &'static str,
+    pub contract_name: &'static str,
+    pub source_name: String,
+    pub abi: Value,
+    pub bytecode: &'static str,
+    pub deployed_bytecode: &'static str,
+    pub link_references: Value,
+    pub deployed_link_references: Value,
+    pub metadata: ArtifactMetadata,
+}
+
+/// What the verifier was generated from, and how.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactMetadata {
+    /// The number of public inputs the verifier expects.
+    pub n_public: usize,
+    /// The proof system the verifier checks.
+    pub protocol: Protocol,
+    /// The curve the verifier operates on.
+    pub curve: Curve,
+    /// The keccak256 hash of the packed verification key.
+    pub vkey_hash: String,
+    /// The language of the generated verifier.
+    pub target: Target,
+    /// The EVM version the verifier targets.
+    pub evm_version: EvmVersion,
+    /// The generator, `huffv`, and its version.
+    pub generator: String,
+}
+
+/// Artifact implementation
+impl Artifact {
+    /// Builds the artifact of the verifier generated for `key` with `options`,
+    /// whose source is saved at `source_name`.
+    pub fn new(
+        key: &VerificationKey,
+        options: &VerifierOptions,
+        source_name: &str,
+    ) -> Result<Self> {
+        Ok(Self {
+            format: ARTIFACT_FORMAT,
+            contract_name: CONTRACT_NAME,
+            source_name: source_name.to_string(),
+            abi: verifier_abi(options, key.ic.len().saturating_sub(1)),
+            bytecode: "0x",
+            deployed_bytecode: "0x",
+            link_references: json!({}),
+            deployed_link_references: json!({}),
+            metadata: ArtifactMetadata::new(key, options)?,
+        })
+    }
+}
+
+/// ArtifactMetadata implementation
+impl ArtifactMetadata {
+    /// The metadata of the verifier generated for `key` with `options`.
+    pub fn new(key: &VerificationKey, options: &VerifierOptions) -> Result<Self> {
+        Ok(Self {
+            n_public: key.ic.len().saturating_sub(1),
+            protocol: key.protocol,
+            curve: key.curve,
+            vkey_hash: format!("0x{}", hex::encode(key.hash()?)),
+            target: options.target,
+            evm_version: options.evm_version,
+            generator: format!("huffv {}", env!("CARGO_PKG_VERSION")),
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////
+//                      HELPER FUNCTIONS                      //
+////////////////////////////////////////////////////////////////
+
+/// The ABI of the verifier with `n_public` public inputs: the verification function
+/// of `options.abi`, `VKEY_HASH` unless the key is passed with every call,
+/// `supportsInterface(bytes4)` if it answers ERC-165, `SELF_TEST()` if it has a
+/// self-test proof, the constructor taking the packed key or its data contract if the
+/// verifier is deployed with them, the owner's functions if the key is in storage, the
+/// event emitted on successful verification if there is one, and the errors reverted
+/// with on failure if there are any.
+fn verifier_abi(options: &VerifierOptions, n_public: usize) -> Value {
+    let param = |name: &str, ty: &str| json!({ "name": name, "type": ty, "internalType": ty });
+    let mut verify_inputs = match options.abi {
+        Abi::Huffv if options.compressed_proof => {
+            vec![param("proof", "uint256[4]"), param("input", "uint256[]")]
+        }
+        Abi::Huffv => vec![
+            param("a", "uint256[2]"),
+            param("b", "uint256[2][2]"),
+            param("c", "uint256[2]"),
+            param("input", "uint256[]"),
+        ],
+        Abi::Snarkjs => vec![
+            param("_pA", "uint256[2]"),
+            param("_pB", "uint256[2][2]"),
+            param("_pC", "uint256[2]"),
+            param("_pubSignals", &format!("uint256[{}]", n_public)),
+        ],
+    };
+    let mut verify_outputs = vec![param("", "bool")];
+    // The packed key comes first, and its hash is returned for the caller to check
+    let keyed = options.vkey_storage == VkeyStorage::Calldata;
+    if keyed {
+        verify_inputs.insert(0, param("packedVkey", "bytes"));
+        verify_outputs.push(param("vkeyHash", "bytes32"));
+    }
+    let mut abi = vec![json!({
+        "type": "function",
+        "name": options.verify_name(),
+        "inputs": verify_inputs,
+        "outputs": verify_outputs,
+        // Emitting an event makes verification non-`view`
+        "stateMutability": if options.event_signature.is_some() { "nonpayable" } else { "view" },
+    })];
+    if !keyed {
+        abi.push(json!({
+            "type": "function",
+            "name": "VKEY_HASH",
+            "inputs": [],
+            "outputs": [param("", "bytes32")],
+            "stateMutability": "view",
+        }));
+    }
+    if options.erc165 {
+        abi.push(json!({
+            "type": "function",
+            "name": "supportsInterface",
+            "inputs": [param("interfaceId", "bytes4")],
+            "outputs": [param("", "bool")],
+            "stateMutability": "pure",
+        }));
+    }
+    if options.self_test.is_some() {
+        abi.push(json!({
+            "type": "function",
+            "name": "SELF_TEST",
+            "inputs": [],
+            "outputs": [param("", "bool")],
+            "stateMutability": if options.event_signature.is_some() { "nonpayable" } else { "view" },
+        }));
+    }
+
+    // The packed key is all words, so its ABI encoding as a static array is the key itself
+    let constructor_inputs = match options.vkey_storage {
+        VkeyStorage::Code | VkeyStorage::Calldata => None,
+        VkeyStorage::Constructor | VkeyStorage::Storage => {
+            let ty = format!("uint256[{}]", 0x1E0 / 0x20 + (n_public + 1) * 2);
+            Some(param("packedVkey", &ty))
+        }
+        VkeyStorage::External => Some(param("vkeyData", "address")),
+    };
+    if let Some(input) = constructor_inputs {
+        abi.push(json!({
+            "type": "constructor",
+            "inputs": [input],
+            "stateMutability": "nonpayable",
+        }));
+    }
+
+    if options.vkey_storage == VkeyStorage::Storage {
+        abi.extend([
+            json!({
+                "type": "function",
+                "name": "setVerificationKey",
+                "inputs": [param("packedVkey", "bytes")],
+                "outputs": [],
+                "stateMutability": "nonpayable",
+            }),
+            json!({
+                "type": "function",
+                "name": "freeze",
+                "inputs": [],
+                "outputs": [],
+                "stateMutability": "nonpayable",
+            }),
+            json!({
+                "type": "function",
+                "name": "owner",
+                "inputs": [],
+                "outputs": [param("", "address")],
+                "stateMutability": "view",
+            }),
+            json!({
+                "type": "function",
+                "name": "initialize",
+                "inputs": [param("owner", "address"), param("packedVkey", "bytes")],
+                "outputs": [],
+                "stateMutability": "nonpayable",
+            }),
+            json!({
+                "type": "event",
+                "name": "VerificationKeySet",
+                "inputs": [{ "name": "vkeyHash", "type": "bytes32", "internalType": "bytes32", "indexed": true }],
+                "anonymous": false,
+            }),
+        ]);
+    }
+
+    // The vkey hash is the event's indexed parameter, followed by the inputs hash as
+    // its data with `--event-inputs-hash`
+    if let Some((name, types)) = options
+        .event_signature
+        .as_deref()
+        .and_then(|sig| sig.strip_suffix(')'))
+        .and_then(|sig| sig.split_once('('))
+    {
+        let inputs = types
+            .split(',')
+            .zip([("vkeyHash", true), ("publicInputsHash", false)])
+            .map(|(ty, (name, indexed))| {
+                json!({ "name": name, "type": ty, "internalType": ty, "indexed": indexed })
+            })
+            .collect::<Vec<_>>();
+        abi.push(json!({
+            "type": "event",
+            "name": name,
+            "inputs": inputs,
+            "anonymous": false,
+        }));
+    }
+
+    if options.on_failure == OnFailure::Revert {
+        abi.extend([
+            json!({ "type": "error", "name": "InvalidProof", "inputs": [] }),
+            json!({ "type": "error", "name": "InvalidPublicInput", "inputs": [param("index", "uint256")] }),
+            json!({ "type": "error", "name": "InvalidCalldata", "inputs": [] }),
+        ]);
+        if options.check_proof_points {
+            abi.push(json!({ "type": "error", "name": "InvalidProofPoint", "inputs": [] }));
+        }
+    }
+
+    Value::Array(abi)
+}
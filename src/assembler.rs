@@ -0,0 +1,811 @@
+use crate::codegen::EvmVersion;
+use crate::error::{HuffvError, Result};
+use ibig::UBig;
+use std::collections::HashMap;
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The mnemonics of the opcodes that Huff macros are written with.
+const OPCODES: &[(&str, u8)] = &[
+    ("stop", 0x00),
+    ("add", 0x01),
+    ("mul", 0x02),
+    ("sub", 0x03),
+    ("div", 0x04),
+    ("sdiv", 0x05),
+    ("mod", 0x06),
+    ("smod", 0x07),
+    ("addmod", 0x08),
+    ("mulmod", 0x09),
+    ("exp", 0x0a),
+    ("signextend", 0x0b),
+    ("lt", 0x10),
+    ("gt", 0x11),
+    ("slt", 0x12),
+    ("sgt", 0x13),
+    ("eq", 0x14),
+    ("iszero", 0x15),
+    ("and", 0x16),
+    ("or", 0x17),
+    ("xor", 0x18),
+    ("not", 0x19),
+    ("byte", 0x1a),
+    ("shl", 0x1b),
+    ("shr", 0x1c),
+    ("sar", 0x1d),
+    ("sha3", 0x20),
+    ("keccak256", 0x20),
+    ("address", 0x30),
+    ("balance", 0x31),
+    ("origin", 0x32),
+    ("caller", 0x33),
+    ("callvalue", 0x34),
+    ("calldataload", 0x35),
+    ("calldatasize", 0x36),
+    ("calldatacopy", 0x37),
+    ("codesize", 0x38),
+    ("codecopy", 0x39),
+    ("gasprice", 0x3a),
+    ("extcodesize", 0x3b),
+    ("extcodecopy", 0x3c),
+    ("returndatasize", 0x3d),
+    ("returndatacopy", 0x3e),
+    ("extcodehash", 0x3f),
+    ("blockhash", 0x40),
+    ("coinbase", 0x41),
+    ("timestamp", 0x42),
+    ("number", 0x43),
+    ("difficulty", 0x44),
+    ("prevrandao", 0x44),
+    ("gaslimit", 0x45),
+    ("chainid", 0x46),
+    ("selfbalance", 0x47),
+    ("basefee", 0x48),
+    ("blobhash", 0x49),
+    ("blobbasefee", 0x4a),
+    ("pop", 0x50),
+    ("mload", 0x51),
+    ("mstore", 0x52),
+    ("mstore8", 0x53),
+    ("sload", 0x54),
+    ("sstore", 0x55),
+    ("jump", 0x56),
+    ("jumpi", 0x57),
+    ("pc", 0x58),
+    ("msize", 0x59),
+    ("gas", 0x5a),
+    ("jumpdest", 0x5b),
+    ("tload", 0x5c),
+    ("tstore", 0x5d),
+    ("mcopy", 0x5e),
+    ("push0", 0x5f),
+    ("dup1", 0x80),
+    ("dup2", 0x81),
+    ("dup3", 0x82),
+    ("dup4", 0x83),
+    ("dup5", 0x84),
+    ("dup6", 0x85),
+    ("dup7", 0x86),
+    ("dup8", 0x87),
+    ("dup9", 0x88),
+    ("dup10", 0x89),
+    ("dup11", 0x8a),
+    ("dup12", 0x8b),
+    ("dup13", 0x8c),
+    ("dup14", 0x8d),
+    ("dup15", 0x8e),
+    ("dup16", 0x8f),
+    ("swap1", 0x90),
+    ("swap2", 0x91),
+    ("swap3", 0x92),
+    ("swap4", 0x93),
+    ("swap5", 0x94),
+    ("swap6", 0x95),
+    ("swap7", 0x96),
+    ("swap8", 0x97),
+    ("swap9", 0x98),
+    ("swap10", 0x99),
+    ("swap11", 0x9a),
+    ("swap12", 0x9b),
+    ("swap13", 0x9c),
+    ("swap14", 0x9d),
+    ("swap15", 0x9e),
+    ("swap16", 0x9f),
+    ("log0", 0xa0),
+    ("log1", 0xa1),
+    ("log2", 0xa2),
+    ("log3", 0xa3),
+    ("log4", 0xa4),
+    ("create", 0xf0),
+    ("call", 0xf1),
+    ("callcode", 0xf2),
+    ("return", 0xf3),
+    ("delegatecall", 0xf4),
+    ("create2", 0xf5),
+    ("staticcall", 0xfa),
+    ("revert", 0xfd),
+    ("invalid", 0xfe),
+    ("selfdestruct", 0xff),
+];
+
+/// `PUSH1`, the first of the push opcodes.
+const PUSH1: u8 = 0x60;
+
+/// `PUSH2`, which every jump label and table offset is pushed with.
+const PUSH2: u8 = 0x61;
+
+/// `RETURN`, which a `CONSTRUCTOR` that deploys its own runtime code ends with.
+const RETURN: u8 = 0xf3;
+
+////////////////////////////////////////////////////////////////
+//                         ASSEMBLER                          //
+////////////////////////////////////////////////////////////////
+
+/// Assembles the runtime code of a Huff contract: its `MAIN` macro, followed by the
+/// tables it uses.
+///
+/// `huffv` assembles the Huff it generates itself, so that verifiers can be deployed
+/// without `huffc`. It supports the subset of Huff its templates are written in:
+/// constants with literal values, macros without parameters (whose invocations may
+/// still pass literal arguments, which go unused), code tables, jump labels,
+/// and the `__codesize`, `__tablestart`, and `__tablesize` builtins. Zero literals
+/// are pushed with `PUSH0` on EVM versions that have it.
+pub fn assemble_runtime_code(source: &str, evm_version: EvmVersion) -> Result<Vec<u8>> {
+    Program::parse(source, evm_version)?.assemble("MAIN", true)
+}
+
+/// Assembles the runtime code of a Huff contract, see [`assemble_runtime_code`], with
+/// the name and code offset of each table in it, in order.
+pub(crate) fn assemble_runtime_tables(
+    source: &str,
+    evm_version: EvmVersion,
+) -> Result<(Vec<u8>, TableOffsets<'_>)> {
+    Program::parse(source, evm_version)?.assemble_with_tables("MAIN", true)
+}
+
+/// Assembles the creation code of a Huff contract, as `huffc -b` compiles it: its
+/// `CONSTRUCTOR` macro, then the runtime code, see [`assemble_runtime_code`]. Unless
+/// the `CONSTRUCTOR` returns the runtime code itself, it is followed by code that
+/// copies the runtime code to memory and returns it.
+///
+/// Constructor arguments are appended to the creation code when it is deployed.
+pub fn assemble_creation_code(source: &str, evm_version: EvmVersion) -> Result<Vec<u8>> {
+    let program = Program::parse(source, evm_version)?;
+    let runtime = program.assemble("MAIN", true)?;
+    let (mut creation, returns) = match program.macros.contains_key("CONSTRUCTOR") {
+        true => (
+            program.assemble("CONSTRUCTOR", false)?,
+            program.returns("CONSTRUCTOR", &mut Vec::new())?,
+        ),
+        false => (Vec::new(), false),
+    };
+    if returns {
+        creation.extend(runtime);
+        return Ok(creation);
+    }
+
+    // runtime_size dup1 runtime_offset 0x00 codecopy 0x00 return
+    let zero = program.push(&[]);
+    let runtime_offset = creation.len() + 3 + 1 + 3 + zero.len() + 1 + zero.len() + 1;
+    creation.extend(push2(runtime.len())?);
+    creation.push(opcode("dup1"));
+    creation.extend(push2(runtime_offset)?);
+    creation.extend(&zero);
+    creation.push(opcode("codecopy"));
+    creation.extend(&zero);
+    creation.push(RETURN);
+    creation.extend(runtime);
+    Ok(creation)
+}
+
+/// The name and code offset of each table in assembled code, in order.
+pub(crate) type TableOffsets<'a> = Vec<(&'a str, usize)>;
+
+/// The definitions of a Huff contract.
+struct Program<'a> {
+    /// The values of the constants, as big-endian bytes without leading zeros.
+    constants: HashMap<&'a str, Vec<u8>>,
+    /// The body of each macro.
+    macros: HashMap<&'a str, Vec<Token<'a>>>,
+    /// The bytes of each table.
+    tables: HashMap<&'a str, Vec<u8>>,
+    /// The EVM version the code is assembled for.
+    evm_version: EvmVersion,
+}
+
+/// A reference to a jump label or a table, filled in once the code is laid out.
+struct Fixup<'a> {
+    /// The position of the two byte placeholder in the code.
+    position: usize,
+    /// The label or table.
+    name: &'a str,
+    /// The macro invocation the label is referenced from, or `None` for a table.
+    invocation: Option<usize>,
+}
+
+/// A macro invocation, in which jump labels are defined.
+struct Invocation<'a> {
+    /// The invocation of the macro this invocation is in.
+    parent: Option<usize>,
+    /// The code offset of each label defined by the invocation.
+    labels: HashMap<&'a str, usize>,
+}
+
+/// The code being assembled from a macro.
+#[derive(Default)]
+struct Assembly<'a> {
+    code: Vec<u8>,
+    fixups: Vec<Fixup<'a>>,
+    invocations: Vec<Invocation<'a>>,
+    /// The tables used, in the order they were first used in.
+    tables: Vec<&'a str>,
+}
+
+/// Program implementation
+impl<'a> Program<'a> {
+    /// Parses the definitions of the Huff `source`.
+    fn parse(source: &'a str, evm_version: EvmVersion) -> Result<Self> {
+        let tokens = tokenize(source)?;
+        let mut program = Self {
+            constants: HashMap::new(),
+            macros: HashMap::new(),
+            tables: HashMap::new(),
+            evm_version,
+        };
+
+        let mut tokens = tokens.into_iter().peekable();
+        while let Some(token) = tokens.next() {
+            if token != Token::Directive("#define") {
+                return Err(unexpected(&token, "a `#define`"));
+            }
+            let kind = match tokens.next() {
+                Some(Token::Ident(kind)) => kind,
+                other => return Err(unexpected_opt(other, "a definition")),
+            };
+            let name = match tokens.next() {
+                Some(Token::Ident(name)) => name,
+                other => return Err(unexpected_opt(other, "a name")),
+            };
+            match kind {
+                "constant" => {
+                    expect(tokens.next(), Token::Punct('='))?;
+                    let value = match tokens.next() {
+                        Some(Token::Literal(digits)) => literal_bytes(digits)?,
+                        Some(Token::Decimal(digits)) => decimal_bytes(digits)?,
+                        other => {
+                            return Err(assembly_error(format!(
+                                "The constant `{}` must have a literal value, not {}.",
+                                name,
+                                describe(other.as_ref())
+                            )))
+                        }
+                    };
+                    program.constants.insert(name, value);
+                }
+                "macro" => {
+                    expect(tokens.next(), Token::Punct('('))?;
+                    if tokens.peek() != Some(&Token::Punct(')')) {
+                        return Err(assembly_error(format!(
+                            "The macro `{}` takes arguments, which aren't supported.",
+                            name
+                        )));
+                    }
+                    tokens.next();
+                    expect(tokens.next(), Token::Punct('='))?;
+                    // Skip `takes (n) returns (m)`
+                    while !matches!(tokens.next(), Some(Token::Punct('{')) | None) {}
+                    let mut body = Vec::new();
+                    let mut depth = 1;
+                    for token in tokens.by_ref() {
+                        depth += match token {
+                            Token::Punct('{') => 1,
+                            Token::Punct('}') => -1,
+                            _ => 0,
+                        };
+                        if depth == 0 {
+                            break;
+                        }
+                        body.push(token);
+                    }
+                    if depth != 0 {
+                        return Err(assembly_error(format!(
+                            "The macro `{}` is never closed.",
+                            name
+                        )));
+                    }
+                    program.macros.insert(name, body);
+                }
+                "table" => {
+                    expect(tokens.next(), Token::Punct('{'))?;
+                    let mut bytes = Vec::new();
+                    loop {
+                        match tokens.next() {
+                            Some(Token::Punct('}')) => break,
+                            Some(Token::Literal(digits)) => {
+                                bytes.extend(hex::decode(digits).map_err(|_| {
+                                    assembly_error(format!(
+                                        "The table `{}` has an odd number of hex digits.",
+                                        name
+                                    ))
+                                })?)
+                            }
+                            other => return Err(unexpected_opt(other, "a table literal")),
+                        }
+                    }
+                    program.tables.insert(name, bytes);
+                }
+                _ => {
+                    return Err(assembly_error(format!(
+                        "`#define {}` isn't supported.",
+                        kind
+                    )))
+                }
+            }
+        }
+        Ok(program)
+    }
+
+    /// Assembles the macro `name`, followed by the tables it uses if `with_tables`.
+    fn assemble(&self, name: &'a str, with_tables: bool) -> Result<Vec<u8>> {
+        Ok(self.assemble_with_tables(name, with_tables)?.0)
+    }
+
+    /// Assembles the macro `name` as [`Program::assemble`] does, with the name and code
+    /// offset of each table it uses.
+    fn assemble_with_tables(
+        &self,
+        name: &'a str,
+        with_tables: bool,
+    ) -> Result<(Vec<u8>, TableOffsets<'a>)> {
+        let mut assembly = Assembly::default();
+        self.expand(name, None, &mut assembly, &mut Vec::new())?;
+
+        let mut offsets = HashMap::new();
+        let mut end = assembly.code.len();
+        for table in &assembly.tables {
+            offsets.insert(*table, end);
+            end += self.tables[table].len();
+        }
+        if !with_tables && !assembly.tables.is_empty() {
+            return Err(assembly_error(format!(
+                "The `{}` macro can't use tables.",
+                name
+            )));
+        }
+        if end > 0xFFFF {
+            return Err(assembly_error(format!(
+                "The `{}` macro is 0x{:x} bytes, past what two byte jumps reach.",
+                name, end
+            )));
+        }
+
+        for fixup in &assembly.fixups {
+            let target = match fixup.invocation {
+                None => offsets[fixup.name],
+                Some(invocation) => {
+                    // Labels are visible in the macros invoked after their definition's macro
+                    let mut scope = Some(invocation);
+                    loop {
+                        let Some(index) = scope else {
+                            return Err(assembly_error(format!(
+                                "The jump label `{}` is never defined.",
+                                fixup.name
+                            )));
+                        };
+                        if let Some(offset) = assembly.invocations[index].labels.get(fixup.name) {
+                            break *offset;
+                        }
+                        scope = assembly.invocations[index].parent;
+                    }
+                }
+            };
+            assembly.code[fixup.position..fixup.position + 2]
+                .copy_from_slice(&(target as u16).to_be_bytes());
+        }
+
+        let mut code = assembly.code;
+        for table in &assembly.tables {
+            code.extend(&self.tables[table]);
+        }
+        let tables = assembly
+            .tables
+            .iter()
+            .map(|table| (*table, offsets[table]))
+            .collect();
+        Ok((code, tables))
+    }
+
+    /// Appends the code of the macro `name` to `assembly`, as invoked from `parent`.
+    /// `stack` holds the macros being expanded, to reject recursive macros.
+    fn expand(
+        &self,
+        name: &'a str,
+        parent: Option<usize>,
+        assembly: &mut Assembly<'a>,
+        stack: &mut Vec<&'a str>,
+    ) -> Result<()> {
+        let body = self.body(name, stack)?;
+        stack.push(name);
+        let invocation = assembly.invocations.len();
+        assembly.invocations.push(Invocation {
+            parent,
+            labels: HashMap::new(),
+        });
+
+        let mut tokens = body.iter().peekable();
+        while let Some(token) = tokens.next() {
+            match *token {
+                Token::Literal(digits) => {
+                    let push = self.push(&literal_bytes(digits)?);
+                    assembly.code.extend(push);
+                }
+                Token::Decimal(digits) => {
+                    let push = self.push(&decimal_bytes(digits)?);
+                    assembly.code.extend(push);
+                }
+                Token::Punct('[') => {
+                    let constant = match (tokens.next(), tokens.next()) {
+                        (Some(Token::Ident(constant)), Some(Token::Punct(']'))) => *constant,
+                        (other, _) => return Err(unexpected_opt(other.copied(), "a constant")),
+                    };
+                    let value = self.constants.get(constant).ok_or_else(|| {
+                        assembly_error(format!("The constant `{}` is never defined.", constant))
+                    })?;
+                    assembly.code.extend(self.push(value));
+                }
+                Token::Ident(ident) if tokens.peek() == Some(&&Token::Punct(':')) => {
+                    tokens.next();
+                    let offset = assembly.code.len();
+                    if assembly.invocations[invocation]
+                        .labels
+                        .insert(ident, offset)
+                        .is_some()
+                    {
+                        return Err(assembly_error(format!(
+                            "The jump label `{}` is defined twice in `{}`.",
+                            ident, name
+                        )));
+                    }
+                    assembly.code.push(opcode("jumpdest"));
+                }
+                Token::Ident(ident) if tokens.peek() == Some(&&Token::Punct('(')) => {
+                    tokens.next();
+                    let mut arguments = Vec::new();
+                    loop {
+                        match tokens.next() {
+                            Some(Token::Punct(')')) if arguments.is_empty() => break,
+                            Some(
+                                argument
+                                @ (Token::Ident(_) | Token::Literal(_) | Token::Decimal(_)),
+                            ) => {
+                                arguments.push(*argument);
+                                match tokens.next() {
+                                    Some(Token::Punct(')')) => break,
+                                    Some(Token::Punct(',')) => {}
+                                    other => return Err(unexpected_opt(other.copied(), "`)`")),
+                                }
+                            }
+                            other => return Err(unexpected_opt(other.copied(), "an argument")),
+                        }
+                    }
+                    let argument = match arguments.as_slice() {
+                        [Token::Ident(argument)] => Some(*argument),
+                        _ => None,
+                    };
+                    match (ident, argument) {
+                        ("__tablestart", Some(table)) => {
+                            self.table(table)?;
+                            if !assembly.tables.contains(&table) {
+                                assembly.tables.push(table);
+                            }
+                            assembly.fixups.push(Fixup {
+                                position: assembly.code.len() + 1,
+                                name: table,
+                                invocation: None,
+                            });
+                            assembly.code.extend([PUSH2, 0x00, 0x00]);
+                        }
+                        ("__tablesize", Some(table)) => {
+                            let size = self.table(table)?.len();
+                            assembly.code.extend(self.push(&minimal_bytes(size)));
+                        }
+                        ("__codesize", Some(macro_name)) => {
+                            // The size of the macro's code alone, without its tables
+                            let mut code = Assembly::default();
+                            self.expand(macro_name, None, &mut code, stack)?;
+                            assembly
+                                .code
+                                .extend(self.push(&minimal_bytes(code.code.len())));
+                        }
+                        // Macros take no parameters, so their arguments go unused, as in `huffc`
+                        _ if !ident.starts_with("__") => {
+                            self.expand(ident, Some(invocation), assembly, stack)?;
+                        }
+                        _ => {
+                            return Err(assembly_error(format!(
+                                "`{}` with {} argument(s) isn't supported.",
+                                ident,
+                                arguments.len()
+                            )))
+                        }
+                    }
+                }
+                Token::Ident(ident) => match OPCODES.iter().find(|(name, _)| *name == ident) {
+                    Some((_, byte)) => assembly.code.push(*byte),
+                    None => {
+                        assembly.fixups.push(Fixup {
+                            position: assembly.code.len() + 1,
+                            name: ident,
+                            invocation: Some(invocation),
+                        });
+                        assembly.code.extend([PUSH2, 0x00, 0x00]);
+                    }
+                },
+                other => return Err(unexpected(&other, "an instruction")),
+            }
+        }
+
+        stack.pop();
+        Ok(())
+    }
+
+    /// Whether the macro `name`, or a macro it invokes, ends the execution with
+    /// `RETURN`.
+    fn returns(&self, name: &'a str, stack: &mut Vec<&'a str>) -> Result<bool> {
+        let body = self.body(name, stack)?;
+        stack.push(name);
+        let mut returns = false;
+        for (i, token) in body.iter().enumerate() {
+            returns |= match *token {
+                Token::Ident("return") => true,
+                Token::Ident(ident)
+                    if body.get(i + 1) == Some(&Token::Punct('(')) && !ident.starts_with("__") =>
+                {
+                    self.returns(ident, stack)?
+                }
+                _ => false,
+            };
+        }
+        stack.pop();
+        Ok(returns)
+    }
+
+    /// The body of the macro `name`, unless it is missing or already being expanded.
+    fn body(&self, name: &'a str, stack: &[&'a str]) -> Result<&Vec<Token<'a>>> {
+        if stack.contains(&name) {
+            return Err(assembly_error(format!(
+                "The macro `{}` invokes itself.",
+                name
+            )));
+        }
+        self.macros
+            .get(name)
+            .ok_or_else(|| assembly_error(format!("The macro `{}` is never defined.", name)))
+    }
+
+    /// The bytes of the table `name`.
+    fn table(&self, name: &str) -> Result<&Vec<u8>> {
+        self.tables
+            .get(name)
+            .ok_or_else(|| assembly_error(format!("The table `{}` is never defined.", name)))
+    }
+
+    /// The shortest push of the big-endian `value`, without leading zeros.
+    fn push(&self, value: &[u8]) -> Vec<u8> {
+        match value.len() {
+            0 if self.evm_version.has_push0() => vec![opcode("push0")],
+            0 => vec![PUSH1, 0x00],
+            len => [&[PUSH1 + len as u8 - 1], value].concat(),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////
+//                         TOKENIZER                          //
+////////////////////////////////////////////////////////////////
+
+/// A token of Huff source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token<'a> {
+    /// A directive, e.g. `#define`.
+    Directive(&'a str),
+    /// A name or an opcode.
+    Ident(&'a str),
+    /// The hex digits of a `0x` prefixed literal.
+    Literal(&'a str),
+    /// The digits of a decimal literal.
+    Decimal(&'a str),
+    /// A single punctuation character.
+    Punct(char),
+}
+
+/// Splits the Huff `source` into tokens, without its comments.
+fn tokenize(source: &str) -> Result<Vec<Token<'_>>> {
+    let mut tokens = Vec::new();
+    let mut rest = source;
+    loop {
+        rest = rest.trim_start();
+        if let Some(comment) = rest.strip_prefix("//") {
+            rest = comment.find('\n').map_or("", |end| &comment[end..]);
+            continue;
+        }
+        if let Some(comment) = rest.strip_prefix("/*") {
+            let end = comment
+                .find("*/")
+                .ok_or_else(|| assembly_error("A block comment is never closed.".to_string()))?;
+            rest = &comment[end + 2..];
+            continue;
+        }
+        let Some(first) = rest.chars().next() else {
+            return Ok(tokens);
+        };
+
+        let word_end = |s: &str, from: usize| {
+            s[from..]
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                .map_or(s.len(), |end| from + end)
+        };
+        let (token, len) = if first == '#' {
+            let end = word_end(rest, 1);
+            (Token::Directive(&rest[..end]), end)
+        } else if let Some(digits) = rest.strip_prefix("0x") {
+            let end = digits
+                .find(|c: char| !c.is_ascii_hexdigit())
+                .unwrap_or(digits.len());
+            (Token::Literal(&digits[..end]), 2 + end)
+        } else if first.is_ascii_digit() {
+            let end = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            (Token::Decimal(&rest[..end]), end)
+        } else if first.is_ascii_alphabetic() || first == '_' {
+            let end = word_end(rest, 0);
+            (Token::Ident(&rest[..end]), end)
+        } else if "(){}[]=:,".contains(first) {
+            (Token::Punct(first), 1)
+        } else {
+            return Err(assembly_error(format!("Unexpected character `{}`.", first)));
+        };
+        tokens.push(token);
+        rest = &rest[len..];
+    }
+}
+
+////////////////////////////////////////////////////////////////
+//                      HELPER FUNCTIONS                      //
+////////////////////////////////////////////////////////////////
+
+/// The byte of the opcode `name`.
+fn opcode(name: &str) -> u8 {
+    OPCODES
+        .iter()
+        .find(|(mnemonic, _)| *mnemonic == name)
+        .map(|(_, byte)| *byte)
+        .expect("The assembler only emits known opcodes.")
+}
+
+/// A `PUSH2` of `value`, which every jump label and table offset is pushed with.
+fn push2(value: usize) -> Result<[u8; 3]> {
+    let value = u16::try_from(value)
+        .map_err(|_| assembly_error(format!("0x{:x} doesn't fit in two bytes.", value)))?;
+    let [high, low] = value.to_be_bytes();
+    Ok([PUSH2, high, low])
+}
+
+/// The value of the hex literal `digits`, as big-endian bytes without leading zeros.
+fn literal_bytes(digits: &str) -> Result<Vec<u8>> {
+    let digits = digits.trim_start_matches('0');
+    if digits.len() > 64 {
+        return Err(assembly_error(format!(
+            "The literal 0x{} doesn't fit in a word.",
+            digits
+        )));
+    }
+    Ok(
+        hex::decode(format!("{:0>1$}", digits, digits.len() + digits.len() % 2))
+            .expect("The tokenizer only reads hex digits."),
+    )
+}
+
+/// The value of the decimal literal `digits`, as big-endian bytes without leading
+/// zeros.
+fn decimal_bytes(digits: &str) -> Result<Vec<u8>> {
+    let value = UBig::from_str_radix(digits, 10).expect("The tokenizer only reads decimal digits.");
+    let bytes = value.to_be_bytes();
+    let start = bytes
+        .iter()
+        .position(|byte| *byte != 0)
+        .unwrap_or(bytes.len());
+    if bytes.len() - start > 32 {
+        return Err(assembly_error(format!(
+            "The literal {} doesn't fit in a word.",
+            digits
+        )));
+    }
+    Ok(bytes[start..].to_vec())
+}
+
+/// `value` as big-endian bytes without leading zeros.
+fn minimal_bytes(value: usize) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let start = bytes
+        .iter()
+        .position(|byte| *byte != 0)
+        .unwrap_or(bytes.len());
+    bytes[start..].to_vec()
+}
+
+/// Fails unless `token` is `expected`.
+fn expect(token: Option<Token>, expected: Token) -> Result<()> {
+    match token {
+        Some(token) if token == expected => Ok(()),
+        other => Err(unexpected_opt(other, &describe(Some(&expected)))),
+    }
+}
+
+/// The error for a `token` where `expected` was expected.
+fn unexpected(token: &Token, expected: &str) -> HuffvError {
+    unexpected_opt(Some(*token), expected)
+}
+
+/// The error for a `token`, or the end of the source, where `expected` was expected.
+fn unexpected_opt(token: Option<Token>, expected: &str) -> HuffvError {
+    assembly_error(format!(
+        "Expected {}, found {}.",
+        expected,
+        describe(token.as_ref())
+    ))
+}
+
+/// Describes `token` for error messages.
+fn describe(token: Option<&Token>) -> String {
+    match token {
+        None => "the end of the source".to_string(),
+        Some(Token::Directive(directive)) => format!("`{}`", directive),
+        Some(Token::Ident(ident)) => format!("`{}`", ident),
+        Some(Token::Literal(digits)) => format!("`0x{}`", digits),
+        Some(Token::Decimal(digits)) => format!("`{}`", digits),
+        Some(Token::Punct(c)) => format!("`{}`", c),
+    }
+}
+
+/// An assembly error with `message`.
+fn assembly_error(message: String) -> HuffvError {
+    HuffvError::Assembly(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decimal_literals_are_pushed_as_decimal() {
+        let source = "#define constant WORD = 64\n\
+                      #define macro MAIN() = takes (0) returns (0) { 32 [WORD] 0x32 0 }";
+        assert_eq!(
+            assemble_runtime_code(source, EvmVersion::Shanghai).unwrap(),
+            [0x60, 0x20, 0x60, 0x40, 0x60, 0x32, 0x5f]
+        );
+    }
+
+    #[test]
+    fn decimal_literals_past_a_word_are_rejected() {
+        let source = format!(
+            "#define macro MAIN() = takes (0) returns (0) {{ {} }}",
+            UBig::from(1u8) << 256
+        );
+        assert!(assemble_runtime_code(&source, EvmVersion::Shanghai).is_err());
+    }
+
+    #[test]
+    fn arguments_of_macros_without_parameters_go_unused() {
+        let source = "#define macro ONE() = { 0x01 }\n\
+                      #define macro MAIN() = { ONE(0x480) ONE(1, WORD) ONE() }";
+        assert_eq!(
+            assemble_runtime_code(source, EvmVersion::Shanghai).unwrap(),
+            [0x60, 0x01, 0x60, 0x01, 0x60, 0x01]
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use crate::assembler::assemble_creation_code;
+use crate::codegen::{
+    Emit, InputCommitment, OnFailure, PrecompileAddresses, PublicInputOrder, TemplateContext,
+    VerifierOptions,
+};
+use crate::error::{HuffvError, Result};
+use crate::proof::{Proof, PublicSignals};
+use crate::utils::{encode_num, render_template, signature_selector};
+use crate::verify::validate_verification_key;
+use crate::vkey::VerificationKey;
+use serde::Serialize;
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The batch verifier template contract
+pub static BATCH_VERIFIER_CONTRACT: &str = include_str!("contracts/BatchVerifierTemplate.huff");
+
+/// The signature of the batch verifier's verification function: the proofs, as the
+/// eight words of [`Proof::encode`] each, then the public inputs of every proof, one
+/// proof after another
+pub static VERIFY_BATCH_SIGNATURE: &str = "verifyBatch(uint256[8][],uint256[])";
+
+/// The memory location of the weighted sums of the ICs' scalars, which the copy of
+/// the calldata follows.
+const SCALARS_PTR: usize = 0xC0;
+
+////////////////////////////////////////////////////////////////
+//                       BATCH VERIFIER                       //
+////////////////////////////////////////////////////////////////
+
+/// Generates a Huff verifier of batches of proofs for `key`, with `options`. A batch
+/// is verified with a single `ecPairing` call of N + 3 pairs, its proofs weighted by
+/// scalars derived from the keccak256 hash of the calldata, and is encoded with
+/// [`encode_batch_calldata`]. `VKEY_HASH()` returns the hash of the key.
+///
+/// The batch verifier keeps the key in its code, and only the EVM version and the
+/// precompile addresses of `options` apply to it. The options that change how a proof
+/// or its public inputs are taken (`--compressed-proof`, `--input-commitment`, and
+/// `--public-input-order reverse`) or what a rejected proof does (`--on-failure
+/// revert`) aren't supported.
+///
+/// With `--emit initcode`, the batch verifier's creation code is returned as `0x`
+/// prefixed hex.
+pub fn generate_batch_verifier(key: &VerificationKey, options: &VerifierOptions) -> Result<String> {
+    validate_verification_key(key)?;
+    options.check_batch()?;
+
+    let template = TemplateContext::new(key, options)?;
+    let n_inputs = key.ic.len() - 1;
+    let context = BatchVerifierContext {
+        signature: VERIFY_BATCH_SIGNATURE,
+        selector: hex::encode(signature_selector(VERIFY_BATCH_SIGNATURE)),
+        n_inputs,
+        n_inputs_hex: format!("0x{:02x}", n_inputs),
+        calldata_ptr: format!("0x{:02x}", SCALARS_PTR + key.ic.len() * 0x20),
+        snark_scalar: template.snark_scalar,
+        vkey_hash: template.vkey_hash,
+        packed_vkey: template.packed_vkey,
+        evm_version: template.evm_version,
+        push0: template.push0,
+        precompiles: template.precompiles,
+    };
+    let verifier = render_template(BATCH_VERIFIER_CONTRACT, &context)?;
+    if options.emit == Emit::Initcode {
+        let initcode = assemble_creation_code(&verifier, options.evm_version)?;
+        return Ok(format!("0x{}", hex::encode(initcode)));
+    }
+    Ok(verifier)
+}
+
+/// ABI-encodes a call to `verifyBatch(uint256[8][],uint256[])` of a batch verifier, for
+/// `proofs` and their public signals.
+pub fn encode_batch_calldata(proofs: &[(Proof, PublicSignals)]) -> Result<String> {
+    let Some((_, first)) = proofs.first() else {
+        return Err(HuffvError::InvalidOptions(
+            "A batch needs at least one proof.".to_string(),
+        ));
+    };
+    if proofs
+        .iter()
+        .any(|(_, public)| public.0.len() != first.0.len())
+    {
+        return Err(HuffvError::InvalidOptions(
+            "Every proof of a batch needs as many public signals.".to_string(),
+        ));
+    }
+
+    // Head: the offsets of the proofs and the public inputs, then the proofs in place
+    let mut calldata = hex::encode(signature_selector(VERIFY_BATCH_SIGNATURE));
+    calldata.push_str(&encode_num("64")?);
+    calldata.push_str(&encode_num(&(0x60 + proofs.len() * 0x100).to_string())?);
+    calldata.push_str(&encode_num(&proofs.len().to_string())?);
+    for (proof, _) in proofs {
+        calldata.push_str(&proof.encode()?);
+    }
+
+    // Then the public inputs of every proof, in one array
+    calldata.push_str(&encode_num(&(proofs.len() * first.0.len()).to_string())?);
+    for (_, public) in proofs {
+        calldata.push_str(&public.encode(PublicInputOrder::Forward)?);
+    }
+    Ok(format!("0x{}", calldata))
+}
+
+/// VerifierOptions implementation for batch verifiers
+impl VerifierOptions {
+    /// Checks that the options apply to a batch verifier.
+    fn check_batch(&self) -> Result<()> {
+        let problem = if self.compressed_proof {
+            "A batch verifier takes the proofs uncompressed, so it doesn't support `--compressed-proof`."
+        } else if self.input_commitment != InputCommitment::None {
+            "A batch verifier takes the circuit's public inputs as is, so it doesn't support `--input-commitment`."
+        } else if self.public_input_order != PublicInputOrder::Forward {
+            "A batch verifier takes the public inputs in the order of the ICs, so it doesn't support `--public-input-order reverse`."
+        } else if self.on_failure != OnFailure::Return {
+            "A batch verifier returns whether all of its proofs are valid, so it doesn't support `--on-failure revert`."
+        } else {
+            return Ok(());
+        };
+        Err(HuffvError::InvalidOptions(problem.to_string()))
+    }
+}
+
+////////////////////////////////////////////////////////////////
+//                      TEMPLATE CONTEXT                      //
+////////////////////////////////////////////////////////////////
+
+/// The values the batch verifier template is rendered with.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct BatchVerifierContext {
+    /// The signature of the verification function.
+    signature: &'static str,
+    /// The selector of the verification function, as hex without a `0x` prefix.
+    selector: String,
+    /// The number of public inputs of each proof, in decimal.
+    n_inputs: usize,
+    /// The number of public inputs of each proof, as a hex literal.
+    n_inputs_hex: String,
+    /// The memory location of the copy of the calldata, as a hex literal.
+    calldata_ptr: String,
+    /// The modulus of the scalar field, as hex without a `0x` prefix.
+    snark_scalar: String,
+    /// The keccak256 hash of the packed key, as hex without a `0x` prefix.
+    vkey_hash: String,
+    /// The packed key, as hex without a `0x` prefix.
+    packed_vkey: String,
+    /// The name of the EVM version the verifier targets.
+    evm_version: &'static str,
+    /// Whether the target EVM version has `PUSH0`.
+    push0: bool,
+    /// The addresses of the precompiles the verifier calls.
+    precompiles: PrecompileAddresses,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::{decode_hex, deploy, synthetic_circuit, transact};
+    use ark_bn254::Fr;
+    use ark_ff::PrimeField;
+    use revm::primitives::{TxKind, U256};
+
+    /// Deploys the batch verifier of `key`, calls it with `calldata`, and returns the
+    /// word it returned, or `None` if it reverted.
+    fn verify_batch(key: &VerificationKey, calldata: &str) -> Option<U256> {
+        let options = VerifierOptions::default();
+        let verifier = generate_batch_verifier(key, &options).unwrap();
+        let initcode = assemble_creation_code(&verifier, options.evm_version).unwrap();
+        let (db, address, _, _) = deploy(&initcode, options.evm_version).unwrap();
+        let calldata = decode_hex(calldata).unwrap();
+        let (result, _) =
+            transact(db, options.evm_version, TxKind::Call(address), calldata).unwrap();
+        result
+            .is_success()
+            .then(|| U256::from_be_slice(&result.into_output().unwrap()))
+    }
+
+    #[test]
+    fn batches_verify_with_a_single_pairing() {
+        for n_inputs in [0, 1, 3] {
+            let (key, proof, public) = synthetic_circuit(n_inputs);
+            for n_proofs in [1, 3] {
+                let batch = vec![(proof.clone(), public.clone()); n_proofs];
+                let calldata = encode_batch_calldata(&batch).unwrap();
+                assert_eq!(
+                    verify_batch(&key, &calldata),
+                    Some(U256::from(1)),
+                    "{} proofs of {} inputs",
+                    n_proofs,
+                    n_inputs
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn batches_with_an_invalid_proof_are_rejected() {
+        let (key, proof, public) = synthetic_circuit(2);
+        let mut batch = vec![(proof, public); 3];
+
+        // A wrong public input of one proof fails the whole batch
+        batch[1].1 .0[0] = "1".to_string();
+        let calldata = encode_batch_calldata(&batch).unwrap();
+        assert_eq!(verify_batch(&key, &calldata), Some(U256::ZERO));
+
+        // A public input outside of the scalar field reverts
+        batch[1].1 .0[0] = Fr::MODULUS.to_string();
+        let calldata = encode_batch_calldata(&batch).unwrap();
+        assert_eq!(verify_batch(&key, &calldata), None);
+
+        // So does a public inputs array that doesn't match the number of proofs
+        batch[1].1 .0[0] = "1".to_string();
+        let calldata = encode_batch_calldata(&batch[..2]).unwrap();
+        let truncated = &calldata[..calldata.len() - 64];
+        assert_eq!(verify_batch(&key, truncated), None);
+    }
+}
@@ -0,0 +1,382 @@
+use crate::calldata::{encode_calldata_with, encode_calldata_with_key};
+use crate::codegen::{generate_verifier_with, Emit, EvmVersion, VerifierOptions, VkeyStorage};
+use crate::error::{HuffvError, Result};
+use crate::gas::{CALLDATA_NONZERO_BYTE_GAS, CALLDATA_ZERO_BYTE_GAS, TX_BASE_GAS};
+use crate::proof::{Proof, PublicSignals};
+use crate::utils::keccak256;
+use crate::vkey::{Curve, Protocol, VerificationKey};
+use ark_bn254::{Fq, Fr, G1Affine, G1Projective, G2Affine, G2Projective};
+use ark_ec::{CurveGroup, Group};
+use ark_ff::PrimeField;
+use revm::db::{CacheDB, EmptyDB};
+use revm::primitives::{
+    address, AccountInfo, Address, ExecutionResult, Output, SpecId, TxKind, U256,
+};
+use revm::Evm;
+use serde::Serialize;
+use std::fmt;
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The account that deploys and calls the verifier under revm
+pub(crate) const BENCH_CALLER: Address = address!("1000000000000000000000000000000000000001");
+
+/// The gas limit of each transaction run under revm
+const BENCH_GAS_LIMIT: u64 = 30_000_000;
+
+////////////////////////////////////////////////////////////////
+//                        BENCH REPORT                        //
+////////////////////////////////////////////////////////////////
+
+/// The gas of verifying a proof, measured by deploying the compiled verifier and
+/// calling it under revm, as opposed to the estimate of [`crate::gas::GasReport`].
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchReport {
+    /// The number of public inputs.
+    pub n_inputs: usize,
+    /// The byte size of the verifier's runtime code.
+    pub code_size: usize,
+    /// The gas of the transaction deploying the verifier.
+    pub deployment: u64,
+    /// The gas of the transaction of each call to the verification function.
+    pub runs: Vec<u64>,
+    /// The least gas of a run.
+    pub min: u64,
+    /// The median gas of the runs.
+    pub median: u64,
+    /// The intrinsic gas of the calls: the transaction base cost and the calldata.
+    pub intrinsic: u64,
+}
+
+/// BenchReport implementation
+impl BenchReport {
+    /// The median gas of executing the verifier, without the intrinsic gas of the call.
+    pub fn execution(&self) -> u64 {
+        self.median - self.intrinsic
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Public inputs:       {}", self.n_inputs)?;
+        writeln!(f, "Runs:                {}", self.runs.len())?;
+        writeln!(f)?;
+        writeln!(f, "| MEASUREMENT        | GAS      |")?;
+        writeln!(f, "| ------------------ | -------- |")?;
+        writeln!(f, "| Transaction (min)  | {:>8} |", self.min)?;
+        writeln!(f, "| Transaction (med)  | {:>8} |", self.median)?;
+        writeln!(f, "| Intrinsic          | {:>8} |", self.intrinsic)?;
+        writeln!(f, "| Execution (med)    | {:>8} |", self.execution())?;
+        writeln!(f, "| Deployment         | {:>8} |", self.deployment)?;
+        writeln!(f)?;
+        write!(f, "Runtime code size:   {} bytes", self.code_size)
+    }
+}
+
+/// Deploys the verifier of `key` generated with `options` under revm, and verifies
+/// `proof` of the `public` signals with it `runs` times, each in a transaction of its
+/// own. Fails unless each call returns true.
+///
+/// The verifier is generated with its own `MAIN` macro and assembled by `huffv` (see
+/// [`Emit::Initcode`]), so it needs `--target huff`, and a key it can be deployed with
+/// alone: in its code, in its constructor arguments, or in calldata.
+pub fn bench_verifier(
+    key: &VerificationKey,
+    options: &VerifierOptions,
+    proof: &Proof,
+    public: &PublicSignals,
+    runs: usize,
+) -> Result<BenchReport> {
+    let (initcode, calldata) = verifier_transactions(key, options, proof, public)?;
+    bench_initcode(
+        &initcode,
+        &calldata,
+        options.evm_version,
+        key.ic.len() - 1,
+        runs,
+    )
+}
+
+/// The creation code of the verifier of `key` generated with `options`, with its own
+/// `MAIN` macro and its constructor arguments, and the calldata verifying `proof` of
+/// the `public` signals with it.
+pub(crate) fn verifier_transactions(
+    key: &VerificationKey,
+    options: &VerifierOptions,
+    proof: &Proof,
+    public: &PublicSignals,
+) -> Result<(Vec<u8>, Vec<u8>)> {
+    let calldata = verifier_calldata(key, options, proof, public)?;
+    Ok((verifier_initcode(key, options)?, calldata))
+}
+
+/// The creation code of the verifier of `key` generated with `options`, with its own
+/// `MAIN` macro and its constructor arguments, to be deployed alone.
+pub(crate) fn verifier_initcode(
+    key: &VerificationKey,
+    options: &VerifierOptions,
+) -> Result<Vec<u8>> {
+    let options = VerifierOptions {
+        main: true,
+        emit: Emit::Initcode,
+        fingerprint: false,
+        ..options.clone()
+    };
+    let constructor_args = match options.vkey_storage {
+        VkeyStorage::Code | VkeyStorage::Calldata => String::new(),
+        VkeyStorage::Constructor => key.to_packed()?[2..].to_string(),
+        VkeyStorage::External | VkeyStorage::Storage => {
+            return Err(HuffvError::InvalidOptions(
+                "The verifier is deployed alone, so it needs its key in its code, its constructor arguments, or calldata."
+                    .to_string(),
+            ))
+        }
+    };
+    let initcode = generate_verifier_with(key, &options)? + &constructor_args;
+    decode_hex(&initcode)
+}
+
+/// The calldata verifying `proof` of the `public` signals with the verifier of `key`
+/// generated with `options`, deployed alone under revm.
+pub(crate) fn verifier_calldata(
+    key: &VerificationKey,
+    options: &VerifierOptions,
+    proof: &Proof,
+    public: &PublicSignals,
+) -> Result<Vec<u8>> {
+    let calldata = match options.vkey_storage {
+        VkeyStorage::Code | VkeyStorage::Constructor => {
+            encode_calldata_with(proof, public, options)?
+        }
+        VkeyStorage::Calldata => encode_calldata_with_key(key, proof, public, options)?,
+        VkeyStorage::External | VkeyStorage::Storage => {
+            return Err(HuffvError::InvalidOptions(
+                "The verifier is deployed alone under revm, so it needs its key in its code, its constructor arguments, or calldata."
+                    .to_string(),
+            ))
+        }
+    };
+    decode_hex(&calldata)
+}
+
+/// Deploys a verifier with `initcode` under revm, and calls it with `calldata` `runs`
+/// times, each in a transaction of its own. Fails unless each call returns true, as
+/// the first word of its output.
+///
+/// This measures any verifier, e.g. one compiled with `solc` to compare against, see
+/// [`bench_verifier`] for `huffv`'s own.
+pub fn bench_initcode(
+    initcode: &[u8],
+    calldata: &[u8],
+    evm_version: EvmVersion,
+    n_inputs: usize,
+    runs: usize,
+) -> Result<BenchReport> {
+    if runs == 0 {
+        return Err(HuffvError::InvalidOptions(
+            "A benchmark needs at least 1 run.".to_string(),
+        ));
+    }
+    let (mut db, verifier, deployment, code_size) = deploy(initcode, evm_version)?;
+    let mut gas = Vec::with_capacity(runs);
+    for _ in 0..runs {
+        let (result, next) = transact(db, evm_version, TxKind::Call(verifier), calldata.to_vec())?;
+        db = next;
+        match result {
+            // Whatever else it returns, e.g. the key hash, the result is the first word
+            ExecutionResult::Success {
+                gas_used,
+                output: Output::Call(output),
+                ..
+            } if output.len() >= 0x20 && U256::from_be_slice(&output[..0x20]) == U256::from(1) => {
+                gas.push(gas_used)
+            }
+            result => {
+                return Err(HuffvError::Execution(format!(
+                    "The verifier did not verify the proof: {:?}",
+                    result
+                )))
+            }
+        }
+    }
+
+    let mut sorted = gas.clone();
+    sorted.sort_unstable();
+    Ok(BenchReport {
+        n_inputs,
+        code_size,
+        deployment,
+        min: sorted[0],
+        median: sorted[sorted.len() / 2],
+        runs: gas,
+        intrinsic: intrinsic_gas(calldata),
+    })
+}
+
+/// Deploys a contract with `initcode` from [`BENCH_CALLER`] under revm, and returns the
+/// state it is deployed in, its address, the gas of its deployment, and the size of its
+/// runtime code.
+pub(crate) fn deploy(
+    initcode: &[u8],
+    evm_version: EvmVersion,
+) -> Result<(CacheDB<EmptyDB>, Address, u64, usize)> {
+    let mut db = CacheDB::new(EmptyDB::default());
+    db.insert_account_info(
+        BENCH_CALLER,
+        AccountInfo {
+            balance: U256::MAX,
+            ..AccountInfo::default()
+        },
+    );
+    let (result, db) = transact(db, evm_version, TxKind::Create, initcode.to_vec())?;
+    match result {
+        ExecutionResult::Success {
+            gas_used,
+            output: Output::Create(code, _),
+            ..
+        } => Ok((db, BENCH_CALLER.create(0), gas_used, code.len())),
+        result => Err(HuffvError::Execution(format!(
+            "The verifier could not be deployed: {:?}",
+            result
+        ))),
+    }
+}
+
+/// Runs a transaction from [`BENCH_CALLER`] under revm on `db`, and returns its result
+/// with the state it committed.
+pub(crate) fn transact(
+    db: CacheDB<EmptyDB>,
+    evm_version: EvmVersion,
+    to: TxKind,
+    data: Vec<u8>,
+) -> Result<(ExecutionResult, CacheDB<EmptyDB>)> {
+    transact_from(db, evm_version, BENCH_CALLER, to, data)
+}
+
+/// Runs a transaction from `caller` under revm on `db`, and returns its result with
+/// the state it committed.
+pub(crate) fn transact_from(
+    db: CacheDB<EmptyDB>,
+    evm_version: EvmVersion,
+    caller: Address,
+    to: TxKind,
+    data: Vec<u8>,
+) -> Result<(ExecutionResult, CacheDB<EmptyDB>)> {
+    let spec_id = match evm_version {
+        EvmVersion::Paris => SpecId::MERGE,
+        EvmVersion::Shanghai => SpecId::SHANGHAI,
+        EvmVersion::Cancun => SpecId::CANCUN,
+    };
+    let mut evm = Evm::builder()
+        .with_db(db)
+        .with_spec_id(spec_id)
+        .modify_tx_env(|tx| {
+            tx.caller = caller;
+            tx.transact_to = to;
+            tx.data = data.into();
+            tx.gas_limit = BENCH_GAS_LIMIT;
+        })
+        .modify_cfg_env(|cfg| cfg.limit_contract_code_size = Some(usize::MAX))
+        .build();
+    let result = evm
+        .transact_commit()
+        .map_err(|e| HuffvError::Execution(format!("{:?}", e)))?;
+    Ok((result, evm.into_context().evm.inner.db))
+}
+
+/// The intrinsic gas of a call with `calldata`, as EIP-2028 prices it.
+fn intrinsic_gas(calldata: &[u8]) -> u64 {
+    let calldata_gas: usize = calldata
+        .iter()
+        .map(|&byte| match byte {
+            0 => CALLDATA_ZERO_BYTE_GAS,
+            _ => CALLDATA_NONZERO_BYTE_GAS,
+        })
+        .sum();
+    (TX_BASE_GAS + calldata_gas) as u64
+}
+
+/// Decodes `0x` prefixed hex.
+pub(crate) fn decode_hex(data: &str) -> Result<Vec<u8>> {
+    hex::decode(data.trim_start_matches("0x"))
+        .map_err(|e| HuffvError::Execution(format!("Invalid hex: {}", e)))
+}
+
+////////////////////////////////////////////////////////////////
+//                    SYNTHETIC CIRCUITS                      //
+////////////////////////////////////////////////////////////////
+
+/// A verification key with `n_inputs` public inputs, and a valid proof for it, to
+/// benchmark verifiers without a circuit.
+///
+/// No circuit has the key: its points are multiples of the generators by scalars
+/// derived from keccak256 hashes, so it is the same on every run. Knowing them,
+/// `proof_a` is chosen for `proof_b` and `proof_c` so that the pairing check holds:
+/// `a * b = alpha * beta + vk_x * gamma + c * delta`, in the exponent.
+pub fn synthetic_circuit(n_inputs: usize) -> (VerificationKey, Proof, PublicSignals) {
+    let scalar = |label: String| Fr::from_be_bytes_mod_order(&keccak256(label.as_bytes()));
+    let (alpha, beta, gamma, delta) = (
+        scalar("alpha".to_string()),
+        scalar("beta".to_string()),
+        scalar("gamma".to_string()),
+        scalar("delta".to_string()),
+    );
+    let ic = (0..=n_inputs)
+        .map(|i| scalar(format!("IC[{}]", i)))
+        .collect::<Vec<_>>();
+    let public = (0..n_inputs)
+        .map(|i| scalar(format!("public[{}]", i)))
+        .collect::<Vec<_>>();
+    let (b, c) = (scalar("proof_b".to_string()), scalar("proof_c".to_string()));
+
+    let vk_x = ic[0]
+        + public
+            .iter()
+            .zip(&ic[1..])
+            .map(|(input, ic)| *input * ic)
+            .sum::<Fr>();
+    let a = (alpha * beta + vk_x * gamma + c * delta) / b;
+
+    let key = VerificationKey {
+        protocol: Protocol::Groth16,
+        curve: Curve::Bn128,
+        n_public: n_inputs as u64,
+        vk_alpha_1: g1(alpha),
+        vk_beta_2: g2(beta),
+        vk_gamma_2: g2(gamma),
+        vk_delta_2: g2(delta),
+        vk_alphabeta_12: None,
+        ic: ic.into_iter().map(g1).collect(),
+    };
+    let proof = Proof {
+        pi_a: g1(a),
+        pi_b: g2(b),
+        pi_c: g1(c),
+    };
+    let public = PublicSignals(public.iter().map(|x| x.into_bigint().to_string()).collect());
+    (key, proof, public)
+}
+
+/// The G1 generator times `scalar`, as snarkjs writes points.
+fn g1(scalar: Fr) -> Vec<String> {
+    let point: G1Affine = (G1Projective::generator() * scalar).into_affine();
+    vec![fq(point.x), fq(point.y), "1".to_string()]
+}
+
+/// The G2 generator times `scalar`, as snarkjs writes points.
+fn g2(scalar: Fr) -> Vec<Vec<String>> {
+    let point: G2Affine = (G2Projective::generator() * scalar).into_affine();
+    vec![
+        vec![fq(point.x.c0), fq(point.x.c1)],
+        vec![fq(point.y.c0), fq(point.y.c1)],
+        vec!["1".to_string(), "0".to_string()],
+    ]
+}
+
+/// `x` in decimal.
+fn fq(x: Fq) -> String {
+    x.into_bigint().to_string()
+}
@@ -0,0 +1,90 @@
+//! The section-based binary container shared by the iden3 file formats, e.g.
+//! snarkjs's `.zkey` and circom's `.r1cs`.
+//!
+//! A file is a 4 byte magic, a `u32` version, and a `u32` section count, followed
+//! by its sections, each a `u32` type, a `u64` size, and its data. All integers
+//! are little endian.
+
+use crate::error::{HuffvError, Result};
+use std::collections::BTreeMap;
+
+/// The sections of a binary file, indexed by type.
+pub(crate) struct Sections<'a> {
+    /// The name of the file format, for errors.
+    kind: &'static str,
+    sections: BTreeMap<u32, &'a [u8]>,
+}
+
+impl<'a> Sections<'a> {
+    /// Reads the sections of `data`, which must start with `magic`. Only the
+    /// first section of each type is kept.
+    pub(crate) fn read(data: &'a [u8], magic: &'static [u8; 4]) -> Result<Self> {
+        let kind = std::str::from_utf8(magic).expect("Magic is ASCII.");
+        let mut reader = Reader::new(kind, data);
+        if reader.bytes(magic.len())? != magic {
+            return Err(reader.error(format!("Missing `{}` magic.", kind)));
+        }
+        let _version = reader.u32()?;
+
+        let mut sections = BTreeMap::new();
+        for _ in 0..reader.u32()? {
+            let section_type = reader.u32()?;
+            let size = usize::try_from(reader.u64()?)
+                .map_err(|_| reader.error("Section is too large.".to_string()))?;
+            sections.entry(section_type).or_insert(reader.bytes(size)?);
+        }
+        Ok(Self { kind, sections })
+    }
+
+    /// A reader over the section of type `section_type`.
+    pub(crate) fn section(&self, section_type: u32) -> Result<Reader<'a>> {
+        match self.sections.get(&section_type) {
+            Some(data) => Ok(Reader::new(self.kind, data)),
+            None => Err(HuffvError::BinaryFile {
+                kind: self.kind,
+                message: format!("Missing section {}.", section_type),
+            }),
+        }
+    }
+}
+
+/// A little endian reader over the bytes of a binary file.
+pub(crate) struct Reader<'a> {
+    /// The name of the file format, for errors.
+    kind: &'static str,
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(kind: &'static str, data: &'a [u8]) -> Self {
+        Self { kind, data }
+    }
+
+    /// An error at the current position of the file.
+    pub(crate) fn error(&self, message: String) -> HuffvError {
+        HuffvError::BinaryFile {
+            kind: self.kind,
+            message,
+        }
+    }
+
+    /// Reads the next `n` bytes.
+    pub(crate) fn bytes(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.data.len() < n {
+            return Err(self.error("Unexpected end of file.".to_string()));
+        }
+        let (bytes, rest) = self.data.split_at(n);
+        self.data = rest;
+        Ok(bytes)
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_le_bytes(bytes.try_into().expect("Read 4 bytes.")))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64> {
+        let bytes = self.bytes(8)?;
+        Ok(u64::from_le_bytes(bytes.try_into().expect("Read 8 bytes.")))
+    }
+}
@@ -0,0 +1,240 @@
+use crate::artifact::ArtifactMetadata;
+use crate::codegen::{
+    generate_verifier_with, Abi, Chain, Emit, EvmVersion, InputCommitment, MemoryLayout,
+    MsmStrategy, OnFailure, Optimization, PrecompileAddresses, PublicInputOrder, Selector,
+    SelfTest, Target, VerifierOptions, VkeyStorage, DEFAULT_EVENT_SIGNATURE,
+    DEFAULT_INPUTS_HASH_EVENT_SIGNATURE,
+};
+use crate::error::Result;
+use crate::proof::{Proof, PublicSignals};
+use crate::vkey::{IcConstantPosition, VerificationKey};
+
+////////////////////////////////////////////////////////////////
+//                          BUILDER                           //
+////////////////////////////////////////////////////////////////
+
+/// Generates a verifier for a verification key, one option at a time:
+///
+/// ```no_run
+/// use huff_snark_verifier::{Target, VerificationKey, VerifierBuilder};
+///
+/// let json = std::fs::read_to_string("verification_key.json").unwrap();
+/// let key = VerificationKey::from_json(&json).unwrap();
+/// let verifier = VerifierBuilder::from_vkey(key)
+///     .target(Target::Solidity)
+///     .emit_event()
+///     .build()
+///     .unwrap();
+/// println!("{}", verifier.source);
+/// ```
+#[derive(Debug, Clone)]
+pub struct VerifierBuilder {
+    key: VerificationKey,
+    options: VerifierOptions,
+    ic_constant_position: IcConstantPosition,
+    normalize_points: bool,
+}
+
+/// A verifier generated by a [`VerifierBuilder`].
+#[derive(Debug, Clone)]
+pub struct GeneratedVerifier {
+    /// The source of the verifier contract.
+    pub source: String,
+    /// The memory layout of the verifier.
+    pub layout: MemoryLayout,
+    /// What the verifier was generated from, and how.
+    pub metadata: ArtifactMetadata,
+}
+
+/// VerifierBuilder implementation
+impl VerifierBuilder {
+    /// Starts building a verifier for `key`, with the default options.
+    pub fn from_vkey(key: VerificationKey) -> Self {
+        Self {
+            key,
+            options: VerifierOptions::default(),
+            ic_constant_position: IcConstantPosition::default(),
+            normalize_points: false,
+        }
+    }
+
+    /// Sets the language of the verifier.
+    pub fn target(mut self, target: Target) -> Self {
+        self.options.target = target;
+        self
+    }
+
+    /// Renders a custom template instead of the embedded template of the target.
+    pub fn template(mut self, template: impl Into<String>) -> Self {
+        self.options.template = Some(template.into());
+        self
+    }
+
+    /// Sets the order in which the verifier consumes public inputs.
+    pub fn public_input_order(mut self, order: PublicInputOrder) -> Self {
+        self.options.public_input_order = order;
+        self
+    }
+
+    /// Sets the cost the verifier is optimized for.
+    pub fn optimize(mut self, optimization: Optimization) -> Self {
+        self.options.optimization = optimization;
+        self
+    }
+
+    /// Sets how the verifier computes the linear combination of the public inputs.
+    pub fn msm(mut self, msm: MsmStrategy) -> Self {
+        self.options.msm = msm;
+        self
+    }
+
+    /// Sets the EVM version the verifier is compiled for and deployed on.
+    pub fn evm_version(mut self, evm_version: EvmVersion) -> Self {
+        self.options.evm_version = evm_version;
+        self
+    }
+
+    /// Calls the precompiles at `precompiles` rather than at their standard addresses.
+    pub fn precompiles(mut self, precompiles: PrecompileAddresses) -> Self {
+        self.options.precompiles = precompiles;
+        self
+    }
+
+    /// Sets the chain the verifier is deployed on, see [`Chain`].
+    pub fn chain(mut self, chain: Chain) -> Self {
+        self.options.chain = chain;
+        self
+    }
+
+    /// Sets the signature and calldata encoding of the verification function.
+    pub fn abi(mut self, abi: Abi) -> Self {
+        self.options.abi = abi;
+        self
+    }
+
+    /// Sets what the verifier does when a proof or its calldata is invalid.
+    pub fn on_failure(mut self, on_failure: OnFailure) -> Self {
+        self.options.on_failure = on_failure;
+        self
+    }
+
+    /// Sets how the verification function's public inputs map to the circuit's.
+    pub fn input_commitment(mut self, input_commitment: InputCommitment) -> Self {
+        self.options.input_commitment = input_commitment;
+        self
+    }
+
+    /// Takes the proof with its points compressed to their `x` coordinates.
+    pub fn compressed_proof(mut self, compressed_proof: bool) -> Self {
+        self.options.compressed_proof = compressed_proof;
+        self
+    }
+
+    /// Renames the verification function.
+    pub fn fn_name(mut self, fn_name: impl Into<String>) -> Self {
+        self.options.fn_name = Some(fn_name.into());
+        self
+    }
+
+    /// Dispatches the verification function by `selector` rather than by the
+    /// selector of its signature.
+    pub fn selector(mut self, selector: Selector) -> Self {
+        self.options.selector = Some(selector);
+        self
+    }
+
+    /// Ends the Huff verifier with a `MAIN` macro, so that it can be deployed as is.
+    pub fn main(mut self, main: bool) -> Self {
+        self.options.main = main;
+        self
+    }
+
+    /// Sets what is emitted for the verifier.
+    pub fn emit(mut self, emit: Emit) -> Self {
+        self.options.emit = emit;
+        self
+    }
+
+    /// Sets where the verifier reads the verification key from.
+    pub fn vkey_storage(mut self, vkey_storage: VkeyStorage) -> Self {
+        self.options.vkey_storage = vkey_storage;
+        self
+    }
+
+    /// Emits `ProofVerified(bytes32)` on successful verification.
+    pub fn emit_event(self) -> Self {
+        self.event_signature(DEFAULT_EVENT_SIGNATURE)
+    }
+
+    /// Emits an event with `signature` on successful verification.
+    pub fn event_signature(mut self, signature: impl Into<String>) -> Self {
+        self.options.event_signature = Some(signature.into());
+        self
+    }
+
+    /// Emits `ProofVerified(bytes32,bytes32)` on successful verification, with the hash
+    /// of the public inputs as its data.
+    pub fn emit_inputs_hash_event(mut self) -> Self {
+        self.options.event_inputs_hash = true;
+        self.event_signature(DEFAULT_INPUTS_HASH_EVENT_SIGNATURE)
+    }
+
+    /// Ends the verifier with a fingerprint trailer, see [`crate::fingerprint`].
+    pub fn fingerprint(mut self, fingerprint: bool) -> Self {
+        self.options.fingerprint = fingerprint;
+        self
+    }
+
+    /// Answers ERC-165's `supportsInterface(bytes4)`, see [`VerifierOptions::interface_id`].
+    pub fn erc165(mut self, erc165: bool) -> Self {
+        self.options.erc165 = erc165;
+        self
+    }
+
+    /// Checks that the proof's G2 point is in the subgroup before the pairing, see
+    /// [`VerifierOptions::strict_subgroup_checks`].
+    pub fn strict_subgroup_checks(mut self, strict_subgroup_checks: bool) -> Self {
+        self.options.strict_subgroup_checks = strict_subgroup_checks;
+        self
+    }
+
+    /// Checks the proof's points before the linear combination, see
+    /// [`VerifierOptions::check_proof_points`].
+    pub fn check_proof_points(mut self, check_proof_points: bool) -> Self {
+        self.options.check_proof_points = check_proof_points;
+        self
+    }
+
+    /// Bakes a known-good proof of the circuit and its public signals into the
+    /// verifier's `SELF_TEST()`, see [`VerifierOptions::self_test`].
+    pub fn self_test(mut self, proof: Proof, public: PublicSignals) -> Self {
+        self.options.self_test = Some(SelfTest { proof, public });
+        self
+    }
+
+    /// Sets the position of the constant term within the key's ICs.
+    pub fn ic_constant_position(mut self, position: IcConstantPosition) -> Self {
+        self.ic_constant_position = position;
+        self
+    }
+
+    /// Converts Jacobian G1 points of the key to affine coordinates before packing.
+    pub fn normalize_points(mut self, normalize_points: bool) -> Self {
+        self.normalize_points = normalize_points;
+        self
+    }
+
+    /// Generates the verifier.
+    pub fn build(mut self) -> Result<GeneratedVerifier> {
+        self.key.normalize_ic_constant(self.ic_constant_position);
+        if self.normalize_points {
+            self.key.normalize_points()?;
+        }
+
+        Ok(GeneratedVerifier {
+            source: generate_verifier_with(&self.key, &self.options)?,
+            layout: MemoryLayout::new(self.key.ic.len()),
+            metadata: ArtifactMetadata::new(&self.key, &self.options)?,
+        })
+    }
+}
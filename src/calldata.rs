@@ -0,0 +1,166 @@
+use crate::codegen::{Abi, PublicInputOrder, VerifierOptions, VkeyStorage, SET_VKEY_SIGNATURE};
+use crate::error::{HuffvError, Result};
+use crate::proof::{Proof, PublicSignals};
+use crate::utils::{encode_num, signature_selector};
+use crate::vkey::VerificationKey;
+
+/// The signature of the verification function, as dispatched to `VERIFY()`
+/// by the test wrapper contracts.
+pub static VERIFY_SIGNATURE: &str = "verify(uint256[2],uint256[2][2],uint256[2],uint256[])";
+
+/// The name of the verification function of `huffv`'s verifiers.
+pub static VERIFY_NAME: &str = "verify";
+
+/// The name of the verification function of snarkjs's Solidity verifiers.
+pub static SNARKJS_VERIFY_NAME: &str = "verifyProof";
+
+/// ABI-encodes a call to the verification function of `abi` for `proof` and its
+/// public signals.
+///
+/// The public signals are ordered to match a verifier generated with `order`.
+pub fn encode_calldata(
+    proof: &Proof,
+    public: &PublicSignals,
+    order: PublicInputOrder,
+    abi: Abi,
+) -> Result<String> {
+    let options = VerifierOptions {
+        public_input_order: order,
+        abi,
+        ..Default::default()
+    };
+    encode_calldata_with(proof, public, &options)
+}
+
+/// ABI-encodes a call to the verification function of a verifier generated with
+/// `options` for `proof` and its public signals. Only the options that change the
+/// calldata (the public input order, ABI, selector, and proof compression) are used.
+///
+/// Verifiers generated with [`VkeyStorage::Calldata`] take the packed key as well, see
+/// [`encode_calldata_with_key`].
+pub fn encode_calldata_with(
+    proof: &Proof,
+    public: &PublicSignals,
+    options: &VerifierOptions,
+) -> Result<String> {
+    if options.vkey_storage == VkeyStorage::Calldata {
+        return Err(HuffvError::InvalidOptions(
+            "A verifier with `--vkey-storage calldata` takes the packed key with the proof."
+                .to_string(),
+        ));
+    }
+    encode(proof, public, options, None, None)
+}
+
+/// ABI-encodes a call to the verification function of a verifier generated with
+/// `options` and [`VkeyStorage::Calldata`], for `proof` and its public signals, to be
+/// verified against `key`. The packed key is the function's first parameter.
+pub fn encode_calldata_with_key(
+    key: &VerificationKey,
+    proof: &Proof,
+    public: &PublicSignals,
+    options: &VerifierOptions,
+) -> Result<String> {
+    if options.abi != Abi::Huffv {
+        return Err(HuffvError::InvalidOptions(
+            "`--vkey-storage calldata` needs the dynamic public inputs array of `--abi huffv`."
+                .to_string(),
+        ));
+    }
+    let options = VerifierOptions {
+        vkey_storage: VkeyStorage::Calldata,
+        ..options.clone()
+    };
+    encode(proof, public, &options, Some(&key.to_packed()?[2..]), None)
+}
+
+/// ABI-encodes a call to the verification function of a multi-circuit verifier
+/// generated with `options`, for `proof` of circuit `circuit_id` and its public signals.
+/// The circuit id is the function's first parameter.
+pub fn encode_calldata_for_circuit(
+    circuit_id: usize,
+    proof: &Proof,
+    public: &PublicSignals,
+    options: &VerifierOptions,
+) -> Result<String> {
+    if options.abi != Abi::Huffv || options.compressed_proof {
+        return Err(HuffvError::InvalidOptions(
+            "A multi-circuit verifier takes the uncompressed proof of `--abi huffv`.".to_string(),
+        ));
+    }
+    encode(proof, public, options, None, Some(circuit_id))
+}
+
+/// ABI-encodes a call to `setVerificationKey(bytes)` of a verifier generated with
+/// [`VkeyStorage::Storage`], replacing its key with `key`.
+pub fn encode_set_vkey_calldata(key: &VerificationKey) -> Result<String> {
+    encode_packed_vkey_call(SET_VKEY_SIGNATURE, key)
+}
+
+/// ABI-encodes a call to the function of `signature`, whose only parameter is the
+/// packed `key` as `bytes`.
+pub(crate) fn encode_packed_vkey_call(signature: &str, key: &VerificationKey) -> Result<String> {
+    let packed = &key.to_packed()?[2..];
+    Ok(format!(
+        "0x{}{}{}{}",
+        hex::encode(signature_selector(signature)),
+        encode_num("32")?,
+        encode_num(&(packed.len() / 2).to_string())?,
+        packed
+    ))
+}
+
+/// ABI-encodes a call for `proof` and its public signals with `options`, after the
+/// `packed` key as hex if there is one, or the `circuit_id` of a multi-circuit verifier.
+fn encode(
+    proof: &Proof,
+    public: &PublicSignals,
+    options: &VerifierOptions,
+    packed: Option<&str>,
+    circuit_id: Option<usize>,
+) -> Result<String> {
+    let mut calldata = match circuit_id {
+        Some(circuit_id) => format!(
+            "{}{}",
+            hex::encode(options.multi_verify_selector()),
+            encode_num(&circuit_id.to_string())?
+        ),
+        None => hex::encode(options.verify_selector(public.0.len())),
+    };
+    let words = if options.compressed_proof {
+        proof
+            .compressed_words()?
+            .iter()
+            .map(|word| encode_num(word))
+            .collect::<Result<String>>()?
+    } else {
+        proof.encode()?
+    };
+
+    // Head: the offset of the packed key, which is all words, so it has no padding
+    let mut head_words = words.len() / 64
+        + usize::from(options.abi == Abi::Huffv)
+        + usize::from(circuit_id.is_some());
+    let mut tail = String::new();
+    if let Some(packed) = packed {
+        head_words += 1;
+        calldata.push_str(&encode_num(&(head_words * 0x20).to_string())?);
+        tail.push_str(&encode_num(&(packed.len() / 2).to_string())?);
+        tail.push_str(packed);
+    }
+
+    // Then the proof words
+    calldata.push_str(&words);
+
+    // Then either the fixed size public inputs array in place, or the offset of the
+    // dynamic one, and its length in the tail
+    if options.abi == Abi::Huffv {
+        let offset = head_words * 0x20 + tail.len() / 2;
+        calldata.push_str(&encode_num(&offset.to_string())?);
+        calldata.push_str(&tail);
+        calldata.push_str(&encode_num(&public.0.len().to_string())?);
+    }
+    calldata.push_str(&public.encode(options.public_input_order)?);
+
+    Ok(format!("0x{}", calldata))
+}
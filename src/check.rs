@@ -0,0 +1,302 @@
+use crate::codegen::{MemoryLayout, PairingTerm, G1_SIZE, GROTH16_PAIRING_TERMS};
+use crate::error::{HuffvError, Result};
+use std::collections::HashMap;
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The largest memory offset that fits in the two byte pushes the templates use
+/// for hardcoded offsets.
+pub const MAX_OFFSET: usize = 0xFFFF;
+
+////////////////////////////////////////////////////////////////
+//                      CONTRACT CHECKS                       //
+////////////////////////////////////////////////////////////////
+
+/// Checks a rendered verifier `contract` before it is emitted, failing with every
+/// problem found:
+/// - Template tags that were left in the contract, e.g. by an escaped `\{{TAG}}`.
+/// - Memory regions of a Huff verifier that overlap, or don't match its `layout`.
+/// - Memory offsets that don't fit in a `PUSH2`.
+pub fn check_contract(contract: &str, layout: &MemoryLayout) -> Result<()> {
+    let mut problems = unreplaced_tags(contract);
+    problems.extend(memory_problems(contract, layout));
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(HuffvError::Check(problems))
+    }
+}
+
+/// The template tags left in `contract`, by line.
+fn unreplaced_tags(contract: &str) -> Vec<String> {
+    contract
+        .lines()
+        .enumerate()
+        .flat_map(|(i, line)| {
+            line.match_indices("{{").map(move |(start, _)| {
+                let tag = match line[start..].find("}}") {
+                    Some(end) => &line[start..start + end + 2],
+                    None => &line[start..],
+                };
+                format!("Line {}: unreplaced template tag `{}`.", i + 1, tag)
+            })
+        })
+        .collect()
+}
+
+/// The problems with the memory regions of a Huff verifier `contract`, as it actually
+/// places them, against the memory `layout` of its key: regions that don't start where
+/// the layout puts them, that overlap or leave a gap before the next one, and offsets
+/// that don't fit in a `PUSH2`.
+///
+/// The regions are read from the contract's `#define constant`s, the first pointer of
+/// `PAIRING()` (the proof it negates `proof_a` from) and the input pointer and size of
+/// its `ecPairing` call. Contracts that don't define `IC_PTR` (Solidity, Yul) don't
+/// lay out memory this way, and aren't checked.
+fn memory_problems(contract: &str, layout: &MemoryLayout) -> Vec<String> {
+    let constants = constants(contract);
+    let constant = |name: &str| constants.get(name).copied();
+    if constant("IC_PTR").is_none() {
+        return Vec::new();
+    }
+
+    let mut missing = Vec::new();
+    let mut found = |name: &'static str, value: Option<usize>| {
+        if value.is_none() && !missing.contains(&name) {
+            missing.push(name);
+        }
+        value.unwrap_or_default()
+    };
+    let vk_x_ptr = found("vk_x", constant("VK_X_X_PTR"));
+    let vk_x_y_ptr = found("vk_x", constant("VK_X_Y_PTR"));
+    let ic_ptr = found("ICs", constant("IC_PTR"));
+    let ic_ptr_last = found("ICs", constant("IC_PTR_LAST"));
+    let pairing = pairing_call(contract).or_else(|| {
+        constant("PAIRING_INPUT_PTR")
+            .map(|ptr| (ptr, GROTH16_PAIRING_TERMS.len() * PairingTerm::SIZE))
+    });
+    let pairing_input_ptr = found("ecPairing inputs", pairing.map(|(ptr, _)| ptr));
+    let pairing_input_size = found("ecPairing inputs", pairing.map(|(_, size)| size));
+    let input_ptr = found(
+        "proof",
+        first_pointer(contract, "PAIRING").or_else(|| constant("PROOF_A_PTR")),
+    );
+    let pub_input_len_ptr = found("public input length", constant("PUB_INPUT_LEN_PTR"));
+    let pub_input_ptr = found("public inputs", constant("PUB_INPUT_PTR"));
+    if !missing.is_empty() {
+        return missing
+            .iter()
+            .map(|name| format!("The contract doesn't locate the {} region.", name))
+            .collect();
+    }
+
+    let mut problems = Vec::new();
+    let n_inputs = layout.n_ics.saturating_sub(1);
+    // (name, start, end, where the layout starts it)
+    let regions = [
+        ("vk_x", vk_x_ptr, vk_x_y_ptr + 0x20, 0x80),
+        ("ICs", ic_ptr, ic_ptr_last + G1_SIZE, MemoryLayout::IC_PTR),
+        (
+            "ecPairing inputs",
+            pairing_input_ptr,
+            pairing_input_ptr + pairing_input_size,
+            layout.pairing_input_ptr(),
+        ),
+        ("proof", input_ptr, input_ptr + 0x100, layout.input_ptr()),
+        (
+            "public input length",
+            pub_input_len_ptr,
+            pub_input_len_ptr + 0x20,
+            layout.pub_input_len_ptr(),
+        ),
+        (
+            "public inputs",
+            pub_input_ptr,
+            pub_input_ptr + n_inputs * 0x20,
+            layout.pub_input_ptr(),
+        ),
+    ];
+
+    for (name, start, end, expected) in regions {
+        if start != expected {
+            problems.push(format!(
+                "The {} region starts at 0x{:x}, but the layout puts it at 0x{:x}.",
+                name, start, expected
+            ));
+        }
+        if end < start {
+            problems.push(format!(
+                "The {} region ends at 0x{:x}, before it starts at 0x{:x}.",
+                name, end, start
+            ));
+        }
+    }
+    regions.windows(2).for_each(|pair| {
+        let ((name_a, start_a, end_a, _), (name_b, start_b, _, _)) = (pair[0], pair[1]);
+        if end_a > start_b {
+            problems.push(format!(
+                "The {} region [0x{:x}:0x{:x}] overlaps the {} region starting at 0x{:x}.",
+                name_a, start_a, end_a, name_b, start_b
+            ));
+        } else if end_a < start_b {
+            problems.push(format!(
+                "The {} region [0x{:x}:0x{:x}] leaves a gap before the {} region starting at 0x{:x}.",
+                name_a, start_a, end_a, name_b, start_b
+            ));
+        }
+    });
+    // Every hardcoded offset is at most the start of the public inputs
+    if pub_input_ptr > MAX_OFFSET {
+        problems.push(format!(
+            "The public inputs start at 0x{:x}, past 0x{:x}, the widest offset of a PUSH2.",
+            pub_input_ptr, MAX_OFFSET
+        ));
+    }
+    problems
+}
+
+/// The value of every `#define constant` of `contract` that is a number, by name.
+fn constants(contract: &str) -> HashMap<&str, usize> {
+    contract
+        .lines()
+        .filter_map(|line| {
+            let (name, value) = line
+                .trim()
+                .strip_prefix("#define constant ")?
+                .split_once('=')?;
+            Some((name.trim(), parse_offset(value.trim())?))
+        })
+        .collect()
+}
+
+/// The input pointer and size of the `ecPairing` call of `contract`: the two words
+/// pushed before `[EC_PAIR]`.
+fn pairing_call(contract: &str) -> Option<(usize, usize)> {
+    let lines: Vec<&str> = contract.lines().map(code).collect();
+    let call = lines.iter().position(|line| *line == "[EC_PAIR]")?;
+    let args = lines[..call].iter().rev().find(|line| !line.is_empty())?;
+    match args.split_whitespace().collect::<Vec<_>>()[..] {
+        [size, ptr] => Some((parse_offset(ptr)?, parse_offset(size)?)),
+        _ => None,
+    }
+}
+
+/// The first word the macro `macro_name` of `contract` pushes.
+fn first_pointer(contract: &str, macro_name: &str) -> Option<usize> {
+    let definition = format!("#define macro {}()", macro_name);
+    contract
+        .lines()
+        .skip_while(|line| !line.starts_with(&definition))
+        .skip(1)
+        .take_while(|line| !line.starts_with('}'))
+        .flat_map(|line| code(line).split_whitespace())
+        .next()
+        .and_then(parse_offset)
+}
+
+/// `line` without its comment and surrounding whitespace.
+fn code(line: &str) -> &str {
+    line.split("//").next().unwrap_or_default().trim()
+}
+
+/// Parses a Huff literal, `0x` prefixed hex or decimal.
+fn parse_offset(literal: &str) -> Option<usize> {
+    match literal.strip_prefix("0x") {
+        Some(hex) => usize::from_str_radix(hex, 16).ok(),
+        None => literal.parse().ok(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codegen::{
+        generate_verifier_with, Emit, EvmVersion, Optimization, Target, VerifierOptions,
+    };
+    use crate::test_utils::sample_key;
+
+    #[test]
+    fn rendered_verifiers_match_their_memory_layout() {
+        let key = sample_key();
+        let layout = MemoryLayout::new(key.ic.len());
+        for options in [
+            VerifierOptions::default(),
+            VerifierOptions {
+                optimization: Optimization::Size,
+                ..VerifierOptions::default()
+            },
+            VerifierOptions {
+                evm_version: EvmVersion::Cancun,
+                ..VerifierOptions::default()
+            },
+            VerifierOptions {
+                emit: Emit::Constants,
+                ..VerifierOptions::default()
+            },
+        ] {
+            let contract = generate_verifier_with(&key, &options).unwrap();
+            assert_eq!(constants(&contract)["IC_PTR"], MemoryLayout::IC_PTR);
+            assert_eq!(
+                memory_problems(&contract, &layout),
+                Vec::<String>::new(),
+                "{:?}",
+                options
+            );
+        }
+
+        let solidity = VerifierOptions {
+            target: Target::Solidity,
+            ..VerifierOptions::default()
+        };
+        let contract = generate_verifier_with(&key, &solidity).unwrap();
+        assert!(memory_problems(&contract, &layout).is_empty());
+    }
+
+    #[test]
+    fn misplaced_regions_are_reported() {
+        let key = sample_key();
+        let layout = MemoryLayout::new(key.ic.len());
+        let contract = generate_verifier_with(&key, &VerifierOptions::default()).unwrap();
+        assert_eq!(pairing_call(&contract), Some((0x180, 0x300)));
+        assert_eq!(first_pointer(&contract, "PAIRING"), Some(0x480));
+
+        // The pairing inputs of a key with one more IC
+        let shifted = contract.replace("0x300 0x180", "0x300 0x1c0");
+        assert_eq!(
+            memory_problems(&shifted, &layout),
+            [
+                "The ecPairing inputs region starts at 0x1c0, but the layout puts it at 0x180.",
+                "The ICs region [0xc0:0x180] leaves a gap before the ecPairing inputs region \
+                 starting at 0x1c0.",
+                "The ecPairing inputs region [0x1c0:0x4c0] overlaps the proof region starting \
+                 at 0x480."
+            ]
+        );
+
+        let overlapping = contract.replace(
+            "#define constant IC_PTR_LAST = 0x140",
+            "#define constant IC_PTR_LAST = 0x180",
+        );
+        assert_ne!(overlapping, contract);
+        assert_eq!(
+            check_contract(&overlapping, &layout)
+                .unwrap_err()
+                .to_string(),
+            HuffvError::Check(vec![
+                "The ICs region [0xc0:0x1c0] overlaps the ecPairing inputs region starting at \
+                 0x180."
+                    .to_string()
+            ])
+            .to_string()
+        );
+
+        let unlocated = contract.replace("[EC_PAIR]", "[EC_ADD]");
+        assert_eq!(
+            memory_problems(&unlocated, &layout),
+            ["The contract doesn't locate the ecPairing inputs region."]
+        );
+    }
+}
@@ -0,0 +1,1721 @@
+use crate::assembler::{assemble_creation_code, assemble_runtime_code};
+use crate::calldata::{SNARKJS_VERIFY_NAME, VERIFY_NAME};
+use crate::check::check_contract;
+use crate::deploy::{data_contract_header, vkey_data_codehash};
+use crate::error::{HuffvError, Result};
+use crate::fingerprint::append_trailer;
+use crate::proof::{Proof, PublicSignals};
+use crate::size::{EIP170_LIMIT, VKEY_POINTS_SIZE};
+use crate::utils::{encode_num, keccak256, render_template, signature_selector};
+use crate::verify::{validate_verification_key, verify_proof};
+use crate::vkey::{Curve, Protocol, VerificationKey};
+use ark_bn254::{Fq, Fq2, Fq6Config, Fr};
+use ark_ff::{BigInteger, Field, Fp6Config, PrimeField};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use tracing::{debug, trace, warn};
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The Verifier template contract
+pub static HUFF_VERIFIER_CONTRACT: &str = include_str!("contracts/VerifierTemplate.huff");
+
+/// The template of the constants and packed verification key of the Huff verifier,
+/// for `--emit constants`
+pub static HUFF_CONSTANTS_CONTRACT: &str = include_str!("contracts/VerifierConstantsTemplate.huff");
+
+/// The Solidity port of the Verifier template contract
+pub static SOLIDITY_VERIFIER_CONTRACT: &str = include_str!("contracts/VerifierTemplate.sol");
+
+/// The Yul port of the Verifier template contract
+pub static YUL_VERIFIER_CONTRACT: &str = include_str!("contracts/VerifierTemplate.yul");
+
+/// The most public inputs [`MsmStrategy::Auto`] unrolls the linear combination for,
+/// past which the code of the unrolled steps outweighs the loop overhead they save.
+pub const MAX_UNROLLED_INPUTS: usize = 8;
+
+/// The selector of `VKEY_HASH()`, which every verifier dispatches besides its
+/// verification function.
+pub const VKEY_HASH_SELECTOR: [u8; 4] = [0xd0, 0x7a, 0x5d, 0xf8];
+
+/// The default signature of the event emitted on successful verification
+pub static DEFAULT_EVENT_SIGNATURE: &str = "ProofVerified(bytes32)";
+
+/// The default signature of the event emitted on successful verification, when it
+/// carries the hash of the public inputs
+pub static DEFAULT_INPUTS_HASH_EVENT_SIGNATURE: &str = "ProofVerified(bytes32,bytes32)";
+
+/// The signature of ERC-165's interface detection function
+pub static SUPPORTS_INTERFACE_SIGNATURE: &str = "supportsInterface(bytes4)";
+
+/// The ERC-165 interface id of ERC-165 itself, the selector of `supportsInterface(bytes4)`
+pub const ERC165_INTERFACE_ID: [u8; 4] = [0x01, 0xff, 0xc9, 0xa7];
+
+/// The signature of the function verifying the proof baked into the verifier with
+/// `--selftest-proof`
+pub static SELF_TEST_SIGNATURE: &str = "SELF_TEST()";
+
+/// The BN254 curve parameter u, which the G2 subgroup check of
+/// `--strict-subgroup-checks` multiplies `proof_b` by 6 * u^2
+pub const BN254_U: u64 = 4965661367192848881;
+
+/// The error reverted with, with `--on-failure revert`, when the pairing check fails
+pub static INVALID_PROOF_ERROR: &str = "InvalidProof()";
+
+/// The error reverted with, with `--on-failure revert`, when a public input is out of
+/// the scalar field, with the index of the input
+pub static INVALID_PUBLIC_INPUT_ERROR: &str = "InvalidPublicInput(uint256)";
+
+/// The error reverted with, with `--on-failure revert` and `--check-proof-points`, when a
+/// proof point is the point at infinity or has a coordinate out of the base field
+pub static INVALID_PROOF_POINT_ERROR: &str = "InvalidProofPoint()";
+
+/// The error reverted with, with `--on-failure revert`, when the calldata is malformed
+pub static INVALID_CALLDATA_ERROR: &str = "InvalidCalldata()";
+
+/// The signature of the owner's function that replaces the verification key of a
+/// verifier with `--vkey-storage storage`
+pub static SET_VKEY_SIGNATURE: &str = "setVerificationKey(bytes)";
+
+/// The signature of the owner's function that makes the verification key of a
+/// verifier with `--vkey-storage storage` permanent
+pub static FREEZE_SIGNATURE: &str = "freeze()";
+
+/// The signature of the getter of the owner of a verifier with `--vkey-storage storage`
+pub static OWNER_SIGNATURE: &str = "owner()";
+
+/// The signature of the function that sets up an EIP-1167 clone of a verifier with
+/// `--vkey-storage storage`, with its owner and packed verification key
+pub static INITIALIZE_SIGNATURE: &str = "initialize(address,bytes)";
+
+/// The signature of the event emitted when the verification key of a verifier with
+/// `--vkey-storage storage` is set, with the hash of the key as an indexed topic
+pub static VKEY_SET_EVENT_SIGNATURE: &str = "VerificationKeySet(bytes32)";
+
+/// The byte size of a G1 point
+pub const G1_SIZE: usize = 0x40;
+
+/// The byte size of a G2 point
+pub const G2_SIZE: usize = 0x80;
+
+/// The terms of the Groth16 pairing check, in the order they are written to the
+/// `ecPairing` precompile inputs:
+/// `e(NEGATE(proof_a), proof_b) * e(alpha, beta) * e(vk_x, gamma) * e(proof_c, delta)`
+pub static GROTH16_PAIRING_TERMS: [PairingTerm; 4] = [
+    PairingTerm {
+        g1: PointSource::Memory,
+        g2: PointSource::Memory,
+    },
+    PairingTerm {
+        g1: PointSource::VerificationKey,
+        g2: PointSource::VerificationKey,
+    },
+    PairingTerm {
+        g1: PointSource::Memory,
+        g2: PointSource::VerificationKey,
+    },
+    PairingTerm {
+        g1: PointSource::Memory,
+        g2: PointSource::VerificationKey,
+    },
+];
+
+////////////////////////////////////////////////////////////////
+//                          OPTIONS                           //
+////////////////////////////////////////////////////////////////
+
+/// Options for generating a verifier contract.
+#[derive(Debug, Clone, Default)]
+pub struct VerifierOptions {
+    /// The language of the generated verifier.
+    pub target: Target,
+    /// A custom template to render instead of the embedded template of `target`.
+    /// It is rendered with the same [`TemplateContext`].
+    pub template: Option<String>,
+    /// The order in which the verifier consumes public inputs.
+    pub public_input_order: PublicInputOrder,
+    /// If set, the verifier emits an event with this signature, and the verification
+    /// key hash as an indexed topic, when a proof is successfully verified.
+    pub event_signature: Option<String>,
+    /// Whether the event carries the keccak256 hash of the public inputs, as 32-byte
+    /// words, as its data. Needs `event_signature`.
+    pub event_inputs_hash: bool,
+    /// The cost the verifier is optimized for.
+    pub optimization: Optimization,
+    /// How the verifier computes the linear combination of the public inputs.
+    pub msm: MsmStrategy,
+    /// The EVM version the verifier is compiled for and deployed on.
+    pub evm_version: EvmVersion,
+    /// The addresses of the precompiles the verifier calls.
+    pub precompiles: PrecompileAddresses,
+    /// The chain the verifier is deployed on.
+    pub chain: Chain,
+    /// The signature and calldata encoding of the verification function.
+    pub abi: Abi,
+    /// What the verifier does when a proof or its calldata is invalid.
+    pub on_failure: OnFailure,
+    /// How the verification function's public inputs map to the circuit's.
+    pub input_commitment: InputCommitment,
+    /// Whether the verifier takes the proof with its points compressed to their `x`
+    /// coordinates, see [`Proof::compressed_words`](crate::proof::Proof::compressed_words).
+    pub compressed_proof: bool,
+    /// The name of the verification function, instead of the name of `abi`.
+    pub fn_name: Option<String>,
+    /// The selector of the verification function, instead of the selector of its
+    /// signature. Only the Huff and Yul verifiers dispatch arbitrary selectors.
+    pub selector: Option<Selector>,
+    /// Whether to end the Huff verifier with a `MAIN` macro that dispatches the
+    /// verification function and `VKEY_HASH()`, so that it can be deployed as is.
+    pub main: bool,
+    /// What is emitted for the verifier.
+    pub emit: Emit,
+    /// Where the verifier reads the verification key from.
+    pub vkey_storage: VkeyStorage,
+    /// Whether to end the verifier with a trailer comment recording its
+    /// [`Fingerprint`](crate::fingerprint::Fingerprint).
+    pub fingerprint: bool,
+    /// Whether the verifier answers ERC-165's `supportsInterface(bytes4)` for its
+    /// [`interface_id`](VerifierOptions::interface_id) and ERC-165's own.
+    pub erc165: bool,
+    /// Whether the verifier checks that `proof_b` is in the G2 subgroup before the
+    /// pairing, rather than relying on the `ecPairing` precompile to reject it.
+    pub strict_subgroup_checks: bool,
+    /// Whether the verifier checks that the proof's points aren't the point at infinity
+    /// and that their coordinates are in the base field before the linear combination,
+    /// rather than leaving invalid points to fail the pairing.
+    pub check_proof_points: bool,
+    /// A known-good proof of the circuit, baked into the verifier's `SELF_TEST()`, which
+    /// verifies it as a smoke check of the deployed verifier.
+    pub self_test: Option<SelfTest>,
+}
+
+/// A known-good proof of the circuit and its public signals, as snarkjs writes them,
+/// for the verifier's `SELF_TEST()`.
+#[derive(Debug, Clone)]
+pub struct SelfTest {
+    /// The proof.
+    pub proof: Proof,
+    /// Its public signals, the raw inputs with `--input-commitment keccak`.
+    pub public: PublicSignals,
+}
+
+/// VerifierOptions implementation
+impl VerifierOptions {
+    /// The name of the verification function.
+    pub fn verify_name(&self) -> &str {
+        self.fn_name.as_deref().unwrap_or(self.abi.name())
+    }
+
+    /// The signature of the verification function for `n_inputs` public inputs.
+    pub fn verify_signature(&self, n_inputs: usize) -> String {
+        if self.compressed_proof {
+            return format!("{}(uint256[4],uint256[])", self.verify_name());
+        }
+        if self.vkey_storage == VkeyStorage::Calldata {
+            return format!(
+                "{}(bytes,{})",
+                self.verify_name(),
+                self.abi.params(n_inputs)
+            );
+        }
+        format!("{}({})", self.verify_name(), self.abi.params(n_inputs))
+    }
+
+    /// The selector the verifier dispatches to its verification function, for
+    /// `n_inputs` public inputs.
+    pub fn verify_selector(&self, n_inputs: usize) -> [u8; 4] {
+        match self.selector {
+            Some(Selector(selector)) => selector,
+            None => signature_selector(&self.verify_signature(n_inputs)),
+        }
+    }
+
+    /// The ERC-165 interface id of the verifier for `n_inputs` public inputs: the XOR
+    /// of the selectors of its verification function and, unless it takes its key in
+    /// calldata, `VKEY_HASH()`.
+    pub fn interface_id(&self, n_inputs: usize) -> [u8; 4] {
+        let mut interface_id = self.verify_selector(n_inputs);
+        if self.vkey_storage != VkeyStorage::Calldata {
+            interface_id
+                .iter_mut()
+                .zip(VKEY_HASH_SELECTOR)
+                .for_each(|(byte, vkey_hash_byte)| *byte ^= vkey_hash_byte);
+        }
+        interface_id
+    }
+
+    /// Checks that the proof can be compressed with the ABI of the verification function.
+    fn check_compressed_proof(&self) -> Result<()> {
+        if self.compressed_proof && self.abi != Abi::Huffv {
+            return Err(HuffvError::InvalidOptions(
+                "`--compressed-proof` needs the dynamic public inputs array of `--abi huffv`."
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that the circuit's public inputs can be committed to as
+    /// `input_commitment` commits to them.
+    pub(crate) fn check_input_commitment(&self, n_inputs: usize) -> Result<()> {
+        if self.input_commitment == InputCommitment::None {
+            return Ok(());
+        }
+        if n_inputs != 1 {
+            return Err(HuffvError::InvalidOptions(format!(
+                "`--input-commitment` needs a circuit with the commitment as its only public input, not {} public inputs.",
+                n_inputs
+            )));
+        }
+        if self.abi != Abi::Huffv {
+            return Err(HuffvError::InvalidOptions(
+                "`--input-commitment` needs the dynamic public inputs array of `--abi huffv`."
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that the event's inputs hash comes with an event.
+    fn check_event(&self) -> Result<()> {
+        if self.event_inputs_hash && self.event_signature.is_none() {
+            return Err(HuffvError::InvalidOptions(
+                "`--event-inputs-hash` adds the hash of the public inputs to the event of `--emit-event`, so it needs `--emit-event`."
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Checks that what is emitted can be generated with the other options.
+    fn check_emit(&self) -> Result<()> {
+        let flag = match self.emit {
+            Emit::Contract => return Ok(()),
+            Emit::Library => "`--emit library`",
+            Emit::Constants => "`--emit constants`",
+            Emit::Initcode => "`--emit initcode`",
+        };
+        let problem = if self.target != Target::Huff && self.emit == Emit::Initcode {
+            format!("{} assembles Huff, so it needs `--target huff`.", flag)
+        } else if self.target != Target::Huff {
+            format!("{} emits Huff, so it needs `--target huff`.", flag)
+        } else if self.emit == Emit::Initcode && self.fingerprint {
+            "A fingerprint trailer is a comment, which the creation code of `--emit initcode` can't hold.".to_string()
+        } else if self.emit == Emit::Initcode {
+            return Ok(());
+        } else if self.emit == Emit::Constants && self.erc165 {
+            "`--emit constants` has no functions, so it can't answer `supportsInterface(bytes4)` for `--erc165`.".to_string()
+        } else if self.emit == Emit::Constants && self.strict_subgroup_checks {
+            "`--emit constants` has no functions, so it can't check subgroups for `--strict-subgroup-checks`.".to_string()
+        } else if self.emit == Emit::Constants && self.check_proof_points {
+            "`--emit constants` has no functions, so it can't check proof points for `--check-proof-points`.".to_string()
+        } else if self.main {
+            format!(
+                "{} has no `MAIN` macro, so it can't be combined with `--main`.",
+                flag
+            )
+        } else if self.emit == Emit::Library && self.input_commitment != InputCommitment::None {
+            "`--emit library` takes the circuit's public inputs as they are, so it can't be combined with `--input-commitment`.".to_string()
+        } else {
+            return Ok(());
+        };
+        Err(HuffvError::InvalidOptions(problem))
+    }
+
+    /// Checks that the verifier can verify the self-test proof against its own key.
+    fn check_self_test(&self) -> Result<()> {
+        if self.self_test.is_none() {
+            return Ok(());
+        }
+        let problem = if self.target != Target::Huff {
+            "`--selftest-proof` needs `--target huff`."
+        } else if self.emit == Emit::Constants {
+            "`--emit constants` has no functions, so it can't verify the proof of `--selftest-proof`."
+        } else if self.vkey_storage == VkeyStorage::Calldata {
+            "`--vkey-storage calldata` takes the key with each call, so `SELF_TEST()` has no key to verify the proof of `--selftest-proof` against."
+        } else {
+            return Ok(());
+        };
+        Err(HuffvError::InvalidOptions(problem.to_string()))
+    }
+
+    /// Checks that the verification key with `n_ics` ICs can be stored where
+    /// `vkey_storage` stores it.
+    fn check_vkey_storage(&self, n_ics: usize) -> Result<()> {
+        let flag = match self.vkey_storage {
+            VkeyStorage::Code => return Ok(()),
+            VkeyStorage::Constructor => "`--vkey-storage constructor`",
+            VkeyStorage::External => "`--vkey-storage external`",
+            VkeyStorage::Calldata => "`--vkey-storage calldata`",
+            VkeyStorage::Storage => "`--vkey-storage storage`",
+        };
+        // The data contract is a `STOP` byte, then the packed key
+        let data_size = 1 + VKEY_POINTS_SIZE + n_ics * G1_SIZE;
+        let problem = if self.target != Target::Huff {
+            format!("{} needs `--target huff`.", flag)
+        } else if !matches!(self.emit, Emit::Contract | Emit::Initcode) {
+            format!(
+                "{} deploys the verifier with its key, so it needs `--emit contract` or `--emit initcode`.",
+                flag
+            )
+        } else if self.vkey_storage == VkeyStorage::Calldata && self.abi != Abi::Huffv {
+            format!(
+                "{} needs the dynamic public inputs array of `--abi huffv`.",
+                flag
+            )
+        } else if self.vkey_storage == VkeyStorage::Calldata && self.compressed_proof {
+            format!(
+                "{} takes the packed key with the proof, which outweighs what `--compressed-proof` saves.",
+                flag
+            )
+        } else if matches!(
+            self.vkey_storage,
+            VkeyStorage::External | VkeyStorage::Storage
+        ) && data_size > EIP170_LIMIT
+        {
+            format!(
+                "The data contract of a key with {} ICs is 0x{:x} bytes, past the EIP-170 limit of 0x{:x} bytes.",
+                n_ics, data_size, EIP170_LIMIT
+            )
+        } else {
+            return Ok(());
+        };
+        Err(HuffvError::InvalidOptions(problem))
+    }
+
+    /// Checks the name and selector of the verification function, returning a
+    /// warning if calls to it can't be encoded from its ABI alone.
+    fn check_entrypoint(&self, n_inputs: usize) -> Result<Option<String>> {
+        let name = self.verify_name();
+        if !is_identifier(name) {
+            return Err(HuffvError::InvalidOptions(format!(
+                "`{}` is not a valid function name.",
+                name
+            )));
+        }
+        let selector = self.verify_selector(n_inputs);
+        if selector == VKEY_HASH_SELECTOR {
+            return Err(HuffvError::InvalidOptions(format!(
+                "The selector 0x{} of the verification function is the selector of `VKEY_HASH()`.",
+                hex::encode(selector)
+            )));
+        }
+        if self.erc165 && selector == ERC165_INTERFACE_ID {
+            return Err(HuffvError::InvalidOptions(format!(
+                "The selector 0x{} of the verification function is the selector of `{}`.",
+                hex::encode(selector),
+                SUPPORTS_INTERFACE_SIGNATURE
+            )));
+        }
+        if self.self_test.is_some() && selector == signature_selector(SELF_TEST_SIGNATURE) {
+            return Err(HuffvError::InvalidOptions(format!(
+                "The selector 0x{} of the verification function is the selector of `{}`.",
+                hex::encode(selector),
+                SELF_TEST_SIGNATURE
+            )));
+        }
+        if self.vkey_storage == VkeyStorage::Storage {
+            for signature in [
+                SET_VKEY_SIGNATURE,
+                FREEZE_SIGNATURE,
+                OWNER_SIGNATURE,
+                INITIALIZE_SIGNATURE,
+            ] {
+                if selector == signature_selector(signature) {
+                    return Err(HuffvError::InvalidOptions(format!(
+                        "The selector 0x{} of the verification function is the selector of `{}`.",
+                        hex::encode(selector),
+                        signature
+                    )));
+                }
+            }
+        }
+
+        let signature = self.verify_signature(n_inputs);
+        if selector == signature_selector(&signature) {
+            return Ok(None);
+        }
+        if self.target == Target::Solidity {
+            return Err(HuffvError::InvalidOptions(format!(
+                "The Solidity verifier can only dispatch the selector of `{}`, not 0x{}. Use `--fn-name`, or `--target huff` / `--target yul`.",
+                signature,
+                hex::encode(selector)
+            )));
+        }
+        Ok(Some(format!(
+            "The selector 0x{} isn't the selector of `{}`, so calls encoded from the verifier's ABI won't reach it. Encode them with `huffv calldata --selector 0x{}`.",
+            hex::encode(selector),
+            signature,
+            hex::encode(selector)
+        )))
+    }
+}
+
+/// The language of the generated verifier contract.
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Target {
+    /// A Huff contract, to be compiled with `huffc`.
+    #[default]
+    Huff,
+    /// A Solidity contract with the same memory layout, written in inline assembly.
+    Solidity,
+    /// A standalone Yul object with the same memory layout, to be compiled with `solc --strict-assembly`.
+    Yul,
+    /// The runtime and creation code of the Huff contract, assembled without `huffc`, as hex.
+    Bytecode,
+}
+
+impl Target {
+    /// The file extension of contracts in this language.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Target::Huff => "huff",
+            Target::Solidity => "sol",
+            Target::Yul => "yul",
+            Target::Bytecode => "hex",
+        }
+    }
+}
+
+/// The order in which public inputs are paired with the verification key's ICs.
+///
+/// `IC[0]` is always the constant term, so with `n` public inputs the pairings are:
+///
+/// | ORDER   | `input[0]` | `input[i]`     | `input[n - 1]` |
+/// | ------- | ---------- | -------------- | -------------- |
+/// | forward | `IC[1]`    | `IC[i + 1]`    | `IC[n]`        |
+/// | reverse | `IC[n]`    | `IC[n - i]`    | `IC[1]`        |
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PublicInputOrder {
+    /// Public inputs are passed in the same order as snarkjs's `public.json`.
+    #[default]
+    Forward,
+    /// Public inputs are passed in reverse relative to snarkjs's `public.json`.
+    Reverse,
+}
+
+/// The cost the generated verifier is optimized for.
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Optimization {
+    /// The lowest gas per verification, e.g. for L1.
+    #[default]
+    Gas,
+    /// The smallest code, e.g. for L2s where execution is cheap, at a small gas overhead.
+    Size,
+}
+
+/// How the verifier computes the linear combination `vk_x` of the ICs and the public inputs.
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MsmStrategy {
+    /// Unrolled when optimizing for gas with at most [`MAX_UNROLLED_INPUTS`] public
+    /// inputs, and looped otherwise.
+    #[default]
+    Auto,
+    /// A loop over the public inputs, whose code size doesn't grow with their number.
+    Loop,
+    /// An `ecMul` / `ecAdd` sequence per public input with hardcoded offsets, without
+    /// the loop's bookkeeping.
+    Unrolled,
+}
+
+/// MsmStrategy implementation
+impl MsmStrategy {
+    /// Whether the linear combination is unrolled for `n_inputs` public inputs,
+    /// optimizing for `optimization`.
+    pub fn unrolls(&self, n_inputs: usize, optimization: Optimization) -> bool {
+        match self {
+            MsmStrategy::Auto => {
+                optimization == Optimization::Gas && n_inputs <= MAX_UNROLLED_INPUTS
+            }
+            MsmStrategy::Loop => false,
+            MsmStrategy::Unrolled => true,
+        }
+    }
+}
+
+/// The EVM version the generated verifier targets, which decides the opcodes it may use.
+#[derive(
+    ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum EvmVersion {
+    /// Before `PUSH0`, for chains that haven't adopted Shanghai.
+    Paris,
+    /// `PUSH0` (EIP-3855), the default EVM version of `huffc`.
+    #[default]
+    Shanghai,
+    /// `MCOPY` (EIP-5656) and transient storage (EIP-1153).
+    Cancun,
+}
+
+/// EvmVersion implementation
+impl EvmVersion {
+    /// The name of the EVM version, as `huffc` and `solc` take it in `--evm-version`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EvmVersion::Paris => "paris",
+            EvmVersion::Shanghai => "shanghai",
+            EvmVersion::Cancun => "cancun",
+        }
+    }
+
+    /// Whether zero can be pushed with `PUSH0`.
+    pub fn has_push0(&self) -> bool {
+        *self >= EvmVersion::Shanghai
+    }
+
+    /// Whether memory can be copied with `MCOPY`.
+    pub fn has_mcopy(&self) -> bool {
+        *self >= EvmVersion::Cancun
+    }
+}
+
+/// The signature and calldata encoding of the verifier's verification function.
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Abi {
+    /// `verify(uint256[2],uint256[2][2],uint256[2],uint256[])`, as encoded by `huffv calldata`.
+    /// The public inputs are a dynamic array, so that circuits with any number of public
+    /// inputs share one calling convention. Calls whose array isn't as long as the
+    /// circuit has public inputs revert.
+    #[default]
+    Huffv,
+    /// `verifyProof(uint256[2],uint256[2][2],uint256[2],uint256[N])`, like the verifiers
+    /// of `snarkjs zkey export solidityverifier`, with the public inputs as a fixed size
+    /// array right after the proof.
+    Snarkjs,
+}
+
+/// Abi implementation
+impl Abi {
+    /// The name of the verification function.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Abi::Huffv => VERIFY_NAME,
+            Abi::Snarkjs => SNARKJS_VERIFY_NAME,
+        }
+    }
+
+    /// The parameter types of the verification function for `n_inputs` public inputs.
+    pub fn params(&self, n_inputs: usize) -> String {
+        match self {
+            Abi::Huffv => "uint256[2],uint256[2][2],uint256[2],uint256[]".to_string(),
+            Abi::Snarkjs => format!("uint256[2],uint256[2][2],uint256[2],uint256[{}]", n_inputs),
+        }
+    }
+
+    /// The signature of the verification function for `n_inputs` public inputs.
+    pub fn signature(&self, n_inputs: usize) -> String {
+        format!("{}({})", self.name(), self.params(n_inputs))
+    }
+
+    /// The selector of the verification function for `n_inputs` public inputs.
+    pub fn selector(&self, n_inputs: usize) -> [u8; 4] {
+        signature_selector(&self.signature(n_inputs))
+    }
+}
+
+/// A function selector, parsed from `0x` prefixed hex, e.g. `0x2612907c`.
+#[derive(Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(try_from = "String")]
+pub struct Selector(pub [u8; 4]);
+
+impl FromStr for Selector {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let bytes = s
+            .strip_prefix("0x")
+            .and_then(|digits| hex::decode(digits).ok())
+            .ok_or_else(|| format!("`{}` is not 0x prefixed hex.", s))?;
+        Ok(Self(bytes.try_into().map_err(|_| {
+            format!("`{}` is not a 4 byte selector.", s)
+        })?))
+    }
+}
+
+impl TryFrom<String> for Selector {
+    type Error = String;
+
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// How the public inputs of the verification function map to the public inputs of
+/// the circuit.
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InputCommitment {
+    /// The verification function takes the circuit's public inputs as is.
+    #[default]
+    None,
+    /// The verification function takes any number of raw inputs, and the circuit's
+    /// only public input is their keccak256 commitment (see
+    /// [`PublicSignals::keccak_commitment`](crate::proof::PublicSignals::keccak_commitment)),
+    /// which the verifier computes on-chain. Circuits with many public inputs then pay
+    /// for a single scalar multiplication.
+    Keccak,
+}
+
+/// What `huffv` emits for a verification key.
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Emit {
+    /// A verifier contract, called through its verification function.
+    #[default]
+    Contract,
+    /// A Huff file to `#include` into a larger Huff contract, without a `MAIN` macro or
+    /// a verification function. Its `VERIFY_PROOF()` macro verifies a proof and public
+    /// inputs from calldata inline, and leaves the result on the stack.
+    Library,
+    /// A Huff file with only the packed verification key as the `VKEY` table, and
+    /// the constants of the verifier's memory layout, for verification macros
+    /// maintained outside of `huffv`.
+    Constants,
+    /// The creation code of the verifier contract, with its `MAIN` macro, as `0x`
+    /// prefixed hex. `huffv` assembles it itself (see [`crate::assembler`]), so it can
+    /// be deployed without compiling the verifier with `huffc`.
+    Initcode,
+}
+
+/// Where the generated verifier reads the verification key from.
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VkeyStorage {
+    /// The `VKEY` table of the verifier's code, filled in at generation time.
+    #[default]
+    Code,
+    /// The end of the verifier's runtime code, where its `CONSTRUCTOR` appends the
+    /// packed key it is deployed with as constructor arguments. The generated verifier
+    /// only depends on the number of public inputs, so one audited verifier can be
+    /// deployed by a factory for any key of that size.
+    Constructor,
+    /// An SSTORE2-style data contract (see [`crate::deploy`]), whose address the
+    /// verifier is deployed with, and which it `EXTCODECOPY`s the key from. The key
+    /// then takes no space in the verifier's code, for circuits whose ICs would push
+    /// the verifier past the EIP-170 limit.
+    External,
+    /// The calldata of each call: the verification function takes the packed key as
+    /// its first parameter, and returns its hash with the result, for the caller to
+    /// check. One deployed verifier then serves every circuit with its number of
+    /// public inputs, e.g. every circuit with `--input-commitment keccak`.
+    Calldata,
+    /// A data contract, like [`VkeyStorage::External`], whose address is in storage.
+    /// The verifier is deployed with the packed key as constructor arguments, and its
+    /// deployer owns it: `setVerificationKey(bytes)` deploys a new data contract for
+    /// another key of the same size, and `freeze()` renounces ownership for good.
+    Storage,
+}
+
+/// What the generated verifier does when a proof or its calldata is invalid.
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OnFailure {
+    /// Return `false` for proofs that fail the pairing check, like snarkjs's verifiers,
+    /// and revert without data for malformed calldata and out of field public inputs.
+    #[default]
+    Return,
+    /// Revert with `InvalidProof()` for proofs that fail the pairing check,
+    /// `InvalidPublicInput(uint256 index)` for out of field public inputs, and
+    /// `InvalidCalldata()` for malformed calldata. The verifier only returns `true`.
+    Revert,
+}
+
+/// The chain the generated verifier is deployed on, for chains whose VM differs
+/// from the EVM in ways the verifier has to account for.
+#[derive(ValueEnum, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Chain {
+    /// Ethereum, or any chain that is EVM-equivalent.
+    #[default]
+    Ethereum,
+    /// zkSync Era. EraVM prices precompiles in its own gas, and only supports the
+    /// `ecAdd` / `ecMul` / `ecPairing` precompiles among those the verifier may call,
+    /// so precompile calls forward all gas and the proof is copied word by word.
+    Zksync,
+}
+
+/// Chain implementation
+impl Chain {
+    /// The constructs of a verifier generated with `options` that won't work as
+    /// intended on this chain.
+    pub fn unsupported(&self, options: &VerifierOptions) -> Vec<String> {
+        if *self != Chain::Zksync {
+            return Vec::new();
+        }
+        let mut problems = Vec::new();
+        match options.target {
+            Target::Huff => problems.push(
+                "zksolc can't compile Huff, so on zkSync Era the Huff verifier only runs on the EVM interpreter, at a much higher cost. Use `--target solidity` instead.".to_string(),
+            ),
+            Target::Yul => problems.push(
+                "The Yul verifier copies the verification key from its `VKEY` data, and EraVM has no runtime `datacopy`. Use `--target solidity` instead.".to_string(),
+            ),
+            Target::Solidity | Target::Bytecode => {}
+        }
+        if options.optimization == Optimization::Size {
+            problems.push(
+                "EraVM has no identity precompile, so `--opt size` copies the proof word by word like `--opt gas`.".to_string(),
+            );
+        }
+        if options.evm_version.has_mcopy() {
+            problems.push(
+                "The proof isn't copied with `MCOPY` on zkSync Era, whatever the `--evm-version`."
+                    .to_string(),
+            );
+        }
+        problems
+    }
+}
+
+/// The addresses of the precompiles the verifier calls, for chains that relocate or
+/// wrap them. Each is `0x` prefixed hex of at most 20 bytes.
+///
+/// Parsed from a comma separated list of overrides of the standard addresses, e.g.
+/// `ec-add=0x0106,ec-mul=0x0107,ec-pairing=0x0108`. The names are `identity`, `modexp`,
+/// `ec-add`, `ec-mul`, and `ec-pairing`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE", try_from = "String")]
+pub struct PrecompileAddresses {
+    /// The identity precompile, which copies the proof with `--opt size`.
+    pub identity: String,
+    /// The `modexp` precompile, which decompresses the proof with `--compressed-proof`.
+    pub modexp: String,
+    /// The `ecAdd` precompile.
+    pub ec_add: String,
+    /// The `ecMul` precompile.
+    pub ec_mul: String,
+    /// The `ecPairing` precompile.
+    pub ec_pairing: String,
+}
+
+impl Default for PrecompileAddresses {
+    fn default() -> Self {
+        Self {
+            identity: "0x04".to_string(),
+            modexp: "0x05".to_string(),
+            ec_add: "0x06".to_string(),
+            ec_mul: "0x07".to_string(),
+            ec_pairing: "0x08".to_string(),
+        }
+    }
+}
+
+/// PrecompileAddresses implementation
+impl PrecompileAddresses {
+    /// Whether every precompile is at its standard address.
+    pub fn is_standard(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl FromStr for PrecompileAddresses {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut addresses = Self::default();
+        for entry in s
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+        {
+            let (name, address) = entry
+                .split_once('=')
+                .ok_or_else(|| format!("Expected `name=address`, got `{}`.", entry))?;
+            let slot = match name.trim() {
+                "identity" => &mut addresses.identity,
+                "modexp" => &mut addresses.modexp,
+                "ec-add" => &mut addresses.ec_add,
+                "ec-mul" => &mut addresses.ec_mul,
+                "ec-pairing" => &mut addresses.ec_pairing,
+                name => {
+                    return Err(format!(
+                        "Unknown precompile `{}`, expected one of identity, modexp, ec-add, ec-mul, ec-pairing.",
+                        name
+                    ))
+                }
+            };
+            *slot = parse_address(address.trim())?;
+        }
+        Ok(addresses)
+    }
+}
+
+impl TryFrom<String> for PrecompileAddresses {
+    type Error = String;
+
+    fn try_from(s: String) -> std::result::Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Whether `name` is a valid Solidity identifier.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$')
+}
+
+/// Normalizes an `0x` prefixed hex `address` to lowercase, without leading zero bytes.
+fn parse_address(address: &str) -> std::result::Result<String, String> {
+    let digits = address
+        .strip_prefix("0x")
+        .filter(|digits| !digits.is_empty() && digits.chars().all(|c| c.is_ascii_hexdigit()))
+        .ok_or_else(|| format!("`{}` is not an 0x prefixed hex address.", address))?
+        .trim_start_matches('0')
+        .to_lowercase();
+    if digits.len() > 40 {
+        return Err(format!("`{}` is longer than 20 bytes.", address));
+    }
+    if digits.is_empty() {
+        return Err("A precompile can't be at the zero address.".to_string());
+    }
+    // Pad to whole bytes, so that the address is a valid Huff / Yul literal either way
+    Ok(format!(
+        "0x{:0>width$}",
+        digits,
+        width = digits.len().div_ceil(2) * 2
+    ))
+}
+
+////////////////////////////////////////////////////////////////
+//                       PAIRING LAYOUT                       //
+////////////////////////////////////////////////////////////////
+
+/// Where a point in the `ecPairing` precompile inputs is written from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointSource {
+    /// Written word by word from memory, with one `mstore` per 32 byte word.
+    Memory,
+    /// Copied from the `VKEY` table. Adjacent copied points share a single `codecopy`.
+    VerificationKey,
+}
+
+/// A single G1/G2 pair of the pairing check.
+#[derive(Clone, Copy, Debug)]
+pub struct PairingTerm {
+    /// The source of the G1 point.
+    pub g1: PointSource,
+    /// The source of the G2 point.
+    pub g2: PointSource,
+}
+
+impl PairingTerm {
+    /// The byte size of a pairing term in the `ecPairing` precompile inputs.
+    pub const SIZE: usize = G1_SIZE + G2_SIZE;
+}
+
+/// Computes the offset bases of the pairing inputs, relative to the start of the
+/// `ecPairing` precompile inputs in memory.
+///
+/// Every `mstore` / `codecopy` that writes the pairing inputs gets one offset
+/// base, in order. These fill the `{{pi_N}}` tags of the template.
+pub fn pairing_input_offset_bases(terms: &[PairingTerm]) -> Vec<usize> {
+    let mut bases = Vec::new();
+    let mut offset = 0;
+    let mut prev_source = None;
+    terms
+        .iter()
+        .flat_map(|t| [(t.g1, G1_SIZE), (t.g2, G2_SIZE)])
+        .for_each(|(source, size)| {
+            match source {
+                PointSource::Memory => bases.extend((offset..offset + size).step_by(0x20)),
+                PointSource::VerificationKey if prev_source != Some(source) => bases.push(offset),
+                PointSource::VerificationKey => {}
+            }
+            offset += size;
+            prev_source = Some(source);
+        });
+    bases
+}
+
+////////////////////////////////////////////////////////////////
+//                       MEMORY LAYOUT                        //
+////////////////////////////////////////////////////////////////
+
+/// The memory layout of the verifier, as described in the memory diagram at the
+/// top of the template contract. Everything after the ICs shifts with their count.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryLayout {
+    /// The number of ICs in the verification key.
+    pub n_ics: usize,
+}
+
+impl MemoryLayout {
+    /// The location of the first IC in memory.
+    pub const IC_PTR: usize = 0xC0;
+
+    /// The memory layout for a verification key with `n_ics` ICs.
+    pub fn new(n_ics: usize) -> Self {
+        Self { n_ics }
+    }
+
+    /// The byte size of the ICs.
+    pub fn ic_bytes(&self) -> usize {
+        self.n_ics * G1_SIZE
+    }
+
+    /// The location of the last IC in memory.
+    pub fn ic_ptr_last(&self) -> usize {
+        Self::IC_PTR + self.n_ics.saturating_sub(1) * G1_SIZE
+    }
+
+    /// The location of the `ecPairing` precompile inputs in memory.
+    pub fn pairing_input_ptr(&self) -> usize {
+        Self::IC_PTR + self.ic_bytes()
+    }
+
+    /// The location of every `mstore` / `codecopy` into the pairing inputs.
+    pub fn pairing_input_offsets(&self) -> Vec<usize> {
+        pairing_input_offset_bases(&GROTH16_PAIRING_TERMS)
+            .into_iter()
+            .map(|base| self.pairing_input_ptr() + base)
+            .collect()
+    }
+
+    /// The location of the `VERIFY` macro inputs (the proof) in memory.
+    pub fn input_ptr(&self) -> usize {
+        self.pairing_input_ptr() + GROTH16_PAIRING_TERMS.len() * PairingTerm::SIZE
+    }
+
+    /// The location of the length of the public inputs in memory.
+    pub fn pub_input_len_ptr(&self) -> usize {
+        self.input_ptr() + 0x100
+    }
+
+    /// The location of the public inputs in memory.
+    pub fn pub_input_ptr(&self) -> usize {
+        self.input_ptr() + 0x120
+    }
+}
+
+////////////////////////////////////////////////////////////////
+//                          CODEGEN                           //
+////////////////////////////////////////////////////////////////
+
+/// Generates a Huff verifier contract for `key` with the default options.
+pub fn generate_verifier(key: &VerificationKey) -> Result<String> {
+    generate_verifier_with(key, &VerifierOptions::default())
+}
+
+/// Generates a verifier contract for `key` in the language of `options.target`, or its
+/// creation code as `0x` prefixed hex with `--emit initcode`.
+///
+/// Fails if any point of `key` is not a valid BN254 point, see [`validate_verification_key`],
+/// or if the rendered contract is broken, see [`check_contract`].
+pub fn generate_verifier_with(key: &VerificationKey, options: &VerifierOptions) -> Result<String> {
+    validate_verification_key(key)?;
+    if options.target == Target::Bytecode {
+        return generate_bytecode(key, options);
+    }
+
+    let template = match (key.protocol, key.curve, &options.template, options.target) {
+        (_, _, Some(template), _) => template,
+        (Protocol::Groth16, Curve::Bn128, None, Target::Huff)
+            if options.emit == Emit::Constants =>
+        {
+            HUFF_CONSTANTS_CONTRACT
+        }
+        (Protocol::Groth16, Curve::Bn128, None, Target::Huff | Target::Bytecode) => {
+            HUFF_VERIFIER_CONTRACT
+        }
+        (Protocol::Groth16, Curve::Bn128, None, Target::Solidity) => SOLIDITY_VERIFIER_CONTRACT,
+        (Protocol::Groth16, Curve::Bn128, None, Target::Yul) => YUL_VERIFIER_CONTRACT,
+    };
+    let n_inputs = key.ic.len().saturating_sub(1);
+    options.check_input_commitment(n_inputs)?;
+    options.check_compressed_proof()?;
+    options.check_event()?;
+    options.check_emit()?;
+    options.check_self_test()?;
+    options.check_vkey_storage(key.ic.len())?;
+    options
+        .chain
+        .unsupported(options)
+        .iter()
+        .chain(&options.check_entrypoint(n_inputs)?)
+        .for_each(|problem| warn!("{}", problem));
+    let contract = render_template(template, &TemplateContext::new(key, options)?)?;
+    check_contract(&contract, &MemoryLayout::new(key.ic.len()))?;
+    if options.emit == Emit::Initcode {
+        let initcode = assemble_creation_code(&contract, options.evm_version)?;
+        return Ok(format!("0x{}", hex::encode(initcode)));
+    }
+    if options.fingerprint {
+        return append_trailer(contract, key);
+    }
+    Ok(contract)
+}
+
+/// Generates the Huff verifier contract for `key` with its own `MAIN`, and assembles
+/// it into its runtime code and its creation code, printed as `0x` prefixed hex on a
+/// `runtime:` and a `creation:` line.
+fn generate_bytecode(key: &VerificationKey, options: &VerifierOptions) -> Result<String> {
+    let problem = if options.emit != Emit::Contract {
+        "`--target bytecode` assembles the verifier contract, so it needs `--emit contract`."
+    } else if options.fingerprint {
+        "A fingerprint trailer is a comment, which the bytecode of `--target bytecode` can't hold."
+    } else {
+        ""
+    };
+    if !problem.is_empty() {
+        return Err(HuffvError::InvalidOptions(problem.to_string()));
+    }
+    let contract = generate_verifier_with(
+        key,
+        &VerifierOptions {
+            target: Target::Huff,
+            main: true,
+            ..options.clone()
+        },
+    )?;
+    let runtime = assemble_runtime_code(&contract, options.evm_version)?;
+    let creation = assemble_creation_code(&contract, options.evm_version)?;
+    Ok(format!(
+        "runtime: 0x{}\ncreation: 0x{}",
+        hex::encode(runtime),
+        hex::encode(creation)
+    ))
+}
+
+////////////////////////////////////////////////////////////////
+//                      TEMPLATE CONTEXT                      //
+////////////////////////////////////////////////////////////////
+
+/// The values the verifier templates are rendered with. Every field is available
+/// to the templates as a tag of the same name in `SCREAMING_SNAKE_CASE`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct TemplateContext {
+    /// The packed verification key, as hex without a `0x` prefix.
+    pub packed_vkey: String,
+    /// The keccak256 hash of the packed verification key, as hex without a `0x` prefix.
+    pub vkey_hash: String,
+    /// The number of ICs in the verification key.
+    pub n_ics: String,
+    /// The modulus of the scalar field, which every public input must be less than,
+    /// as hex without a `0x` prefix.
+    pub snark_scalar: String,
+    /// The byte size of the ICs in the verification key.
+    pub ic_bytes: String,
+    /// The byte size of the packed verification key.
+    pub vkey_bytes: String,
+    /// The code hash of the data contract holding the packed verification key, as hex
+    /// without a `0x` prefix.
+    pub vkey_data_codehash: String,
+    /// The location of the last IC in memory.
+    pub ic_ptr_last: String,
+    /// The location of the length of the public inputs in memory.
+    pub pub_input_len_ptr: String,
+    /// The location of the public inputs in memory.
+    pub pub_input_ptr: String,
+    /// Whether the circuit has any public inputs.
+    pub has_inputs: bool,
+    /// Whether public inputs are paired with the ICs in reverse.
+    pub reverse_inputs: bool,
+    /// Whether the verifier is optimized for code size rather than gas, and copies
+    /// the proof with the identity precompile.
+    pub optimize_size: bool,
+    /// The name of the EVM version the verifier targets.
+    pub evm_version: &'static str,
+    /// Whether the target EVM version has `PUSH0`.
+    pub push0: bool,
+    /// Whether the proof is copied with `MCOPY`.
+    pub mcopy: bool,
+    /// The addresses of the precompiles the verifier calls.
+    pub precompiles: PrecompileAddresses,
+    /// Whether precompile calls forward all gas rather than what the standard
+    /// precompiles cost, as relocated, wrapped, or repriced precompiles may need more.
+    pub forward_gas: bool,
+    /// Whether the verifier is generated for zkSync Era.
+    pub zksync: bool,
+    /// The verification function.
+    pub abi: AbiContext,
+    /// Whether the verifier hashes its raw inputs into a keccak256 commitment, the
+    /// circuit's only public input.
+    pub keccak_inputs: bool,
+    /// Whether the verifier decompresses a compressed proof.
+    pub compressed_proof: bool,
+    /// Whether the Huff verifier ends with a `MAIN` macro.
+    pub main: bool,
+    /// Whether the Huff verifier is emitted as a library, with a `VERIFY_PROOF()`
+    /// macro instead of `VERIFY_CALLDATA()`.
+    pub library: bool,
+    /// Where the verifier reads the verification key from.
+    pub vkey_storage: VkeyStorageContext,
+    /// The event emitted on successful verification, if any.
+    pub event: Option<EventContext>,
+    /// ERC-165 interface detection, if the verifier answers it.
+    pub erc165: Option<Erc165Context>,
+    /// The G2 subgroup check of `proof_b`, if the verifier checks it.
+    pub subgroup_check: Option<SubgroupCheckContext>,
+    /// Whether the verifier checks the proof's points before the linear combination.
+    pub check_proof_points: bool,
+    /// The proof baked into `SELF_TEST()`, if the verifier has one.
+    pub self_test: Option<SelfTestContext>,
+    /// The custom errors the verifier reverts with, if it reverts on failure.
+    pub errors: Option<ErrorsContext>,
+    /// The owner's functions, if the key is in storage.
+    pub admin: Option<AdminContext>,
+    /// The steps of the linear combination, if it is unrolled. Empty if it is looped.
+    pub unrolled_inputs: Vec<UnrolledInputContext>,
+    /// The `mstore`s that write the ICs to memory, for targets without a `VKEY` table.
+    pub mstore_ic: Vec<MstoreContext>,
+    /// The `mstore`s that write alpha and beta to the pairing inputs.
+    pub mstore_alpha_beta: Vec<MstoreContext>,
+    /// The `mstore`s that write gamma to the pairing inputs.
+    pub mstore_gamma: Vec<MstoreContext>,
+    /// The `mstore`s that write delta to the pairing inputs.
+    pub mstore_delta: Vec<MstoreContext>,
+    /// The `pi_N` pairing input offsets and `in_N` proof word offsets.
+    #[serde(flatten)]
+    pub offsets: BTreeMap<String, String>,
+}
+
+/// The event emitted on successful verification.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct EventContext {
+    /// The event signature.
+    pub signature: String,
+    /// The event topic (the hash of its signature), as hex without a `0x` prefix.
+    pub topic: String,
+    /// The verification key hash, as hex without a `0x` prefix.
+    pub vkey_hash: String,
+    /// Whether the event's data is the hash of the public inputs.
+    pub inputs_hash: bool,
+}
+
+/// ERC-165 interface detection, with `--erc165`. Each is hex without a `0x` prefix.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct Erc165Context {
+    /// `supportsInterface(bytes4)`, which is also ERC-165's own interface id
+    pub selector: String,
+    /// The verifier's interface id, see [`VerifierOptions::interface_id`].
+    pub interface_id: String,
+}
+
+/// The constants of the G2 subgroup check, with `--strict-subgroup-checks`: `proof_b`
+/// is in the subgroup iff psi(proof_b) == [6 * u^2] proof_b. Each is hex without a
+/// `0x` prefix.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct SubgroupCheckContext {
+    /// 6 * u^2, the scalar `proof_b` is multiplied by
+    pub six_u_squared: String,
+    /// The index of the second most significant bit of 6 * u^2, where the
+    /// double-and-add starts, as `0x` prefixed hex.
+    pub start_bit: String,
+    /// c_0 of psi_x = (9 + i)^((q - 1) / 3), where psi(x, y) = (conj(x) * psi_x,
+    /// conj(y) * psi_y) for the non-residue 9 + i of F_q^2
+    pub psi_x_0: String,
+    /// c_1 of psi_x
+    pub psi_x_1: String,
+    /// c_0 of psi_y = (9 + i)^((q - 1) / 2)
+    pub psi_y_0: String,
+    /// c_1 of psi_y
+    pub psi_y_1: String,
+}
+
+/// SubgroupCheckContext implementation
+impl SubgroupCheckContext {
+    /// The constants of the G2 subgroup check of BN254.
+    pub fn bn254() -> Self {
+        let six_u_squared = 6 * (BN254_U as u128) * (BN254_U as u128);
+        // The Frobenius coefficient of F_q^6 is (9 + i)^((q - 1) / 3)
+        let psi_x = Fq6Config::FROBENIUS_COEFF_FP6_C1[1];
+        let psi_y = Fq2::new(Fq::from(9u64), Fq::from(1u64)).pow(Fq::MODULUS_MINUS_ONE_DIV_TWO);
+        let fq = |x: Fq| hex::encode(x.into_bigint().to_bytes_be());
+        Self {
+            six_u_squared: format!("{:x}", six_u_squared),
+            start_bit: format!("0x{:02x}", 126 - six_u_squared.leading_zeros()),
+            psi_x_0: fq(psi_x.c0),
+            psi_x_1: fq(psi_x.c1),
+            psi_y_0: fq(psi_y.c0),
+            psi_y_1: fq(psi_y.c1),
+        }
+    }
+}
+
+/// The proof baked into the verifier's `SELF_TEST()`, with `--selftest-proof`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct SelfTestContext {
+    /// `SELF_TEST()`, as hex without a `0x` prefix.
+    pub selector: String,
+    /// The `mstore`s that write the proof, the number of public inputs, and the public
+    /// inputs where the verification function copies them.
+    pub mstore: Vec<MstoreContext>,
+}
+
+/// SelfTestContext implementation
+impl SelfTestContext {
+    /// The context of the `self_test` proof, verified off-chain against `key` first, so
+    /// that a verifier isn't generated with a `SELF_TEST()` that fails.
+    pub fn new(
+        self_test: &SelfTest,
+        key: &VerificationKey,
+        options: &VerifierOptions,
+    ) -> Result<Self> {
+        // The circuit's public inputs, in the order the verification function takes them
+        let public = match options.input_commitment {
+            InputCommitment::Keccak => self_test.public.keccak_commitment()?,
+            InputCommitment::None => self_test.public.clone(),
+        };
+        if !verify_proof(key, &self_test.proof, &public)? {
+            return Err(HuffvError::InvalidProof(
+                "The proof of `--selftest-proof` doesn't verify against the key.".to_string(),
+            ));
+        }
+        let mut inputs = public.0;
+        if options.public_input_order == PublicInputOrder::Reverse {
+            inputs.reverse();
+        }
+
+        // The proof, the number of public inputs, and the public inputs are contiguous
+        let layout = MemoryLayout::new(key.ic.len());
+        let words = self_test
+            .proof
+            .words()
+            .into_iter()
+            .map(str::to_string)
+            .chain(std::iter::once(inputs.len().to_string()))
+            .chain(inputs);
+        let mstore = words
+            .enumerate()
+            .map(|(i, word)| {
+                Ok(MstoreContext {
+                    ptr: format!("0x{:02x}", layout.input_ptr() + i * 0x20),
+                    word: format!("0x{}", encode_num(&word)?),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            selector: hex::encode(signature_selector(SELF_TEST_SIGNATURE)),
+            mstore,
+        })
+    }
+}
+
+/// The custom errors the verifier reverts with, with `--on-failure revert`. Each is a
+/// selector, as hex without a `0x` prefix.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct ErrorsContext {
+    /// `InvalidProof()`
+    pub invalid_proof: String,
+    /// `InvalidPublicInput(uint256)`
+    pub invalid_public_input: String,
+    /// `InvalidProofPoint()`
+    pub invalid_proof_point: String,
+    /// `InvalidCalldata()`
+    pub invalid_calldata: String,
+}
+
+/// The functions of the owner of a verifier with `--vkey-storage storage`. Each
+/// selector and topic is hex without a `0x` prefix.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct AdminContext {
+    /// `setVerificationKey(bytes)`
+    pub set_vkey_selector: String,
+    /// `freeze()`
+    pub freeze_selector: String,
+    /// `owner()`
+    pub owner_selector: String,
+    /// `initialize(address,bytes)`
+    pub initialize_selector: String,
+    /// The topic of `VerificationKeySet(bytes32)`.
+    pub vkey_set_topic: String,
+    /// The creation code of the data contract of a key, up to the key, see
+    /// [`data_contract_header`].
+    pub vkey_data_header: String,
+}
+
+/// The verification function of the verifier.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct AbiContext {
+    /// The function name.
+    pub name: String,
+    /// The function signature.
+    pub signature: String,
+    /// The function selector, as hex without a `0x` prefix.
+    pub selector: String,
+    /// Whether the public inputs are a fixed size array, as in snarkjs's verifiers.
+    pub snarkjs: bool,
+    /// The number of public inputs, in decimal.
+    pub n_inputs: usize,
+    /// The byte size of the public inputs.
+    pub input_bytes: String,
+    /// The calldata size of a call, for fixed size public inputs.
+    pub calldata_size: String,
+    /// The byte size of the dynamic public inputs array: its length, then its elements.
+    pub array_bytes: String,
+    /// The calldata location of the offset of the dynamic public inputs array.
+    pub offset_ptr: String,
+    /// The calldata location of the proof words.
+    pub proof_ptr: String,
+}
+
+/// Where the verifier reads the verification key from, one flag per [`VkeyStorage`].
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct VkeyStorageContext {
+    /// The `VKEY` table of the verifier's code.
+    pub code: bool,
+    /// The end of the verifier's runtime code, appended by its `CONSTRUCTOR`.
+    pub constructor: bool,
+    /// A data contract, whose address the `CONSTRUCTOR` appends to the runtime code.
+    pub external: bool,
+    /// The calldata of each call, as the first parameter of the verification function.
+    pub calldata: bool,
+    /// A data contract whose address is in storage, replaced by the owner.
+    pub storage: bool,
+    /// Whether the key is fixed when the verifier is generated, so that its hash is
+    /// a constant.
+    pub fixed: bool,
+    /// Whether alpha, beta, and gamma are in the `VKEY_BASE` table a multi-circuit
+    /// verifier shares between circuits of the same setup, and the `VKEY` table of
+    /// the code holds the rest of the key.
+    pub shared_base: bool,
+}
+
+/// A single step of the unrolled linear combination: `vk_x += input[index] * IC`.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct UnrolledInputContext {
+    /// The index of the public input.
+    pub index: usize,
+    /// The memory location of the public input.
+    pub input_ptr: String,
+    /// The memory location of the IC it is paired with.
+    pub ic_ptr: String,
+}
+
+/// A single `mstore` of a verification key word.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct MstoreContext {
+    /// The memory location of the word.
+    pub ptr: String,
+    /// The word, as `0x` prefixed hex.
+    pub word: String,
+}
+
+/// TemplateContext implementation
+impl TemplateContext {
+    /// The context of the verifier for `key`, generated with `options`.
+    pub fn new(key: &VerificationKey, options: &VerifierOptions) -> Result<Self> {
+        let layout = MemoryLayout::new(key.ic.len());
+        let packed = key.to_packed()?;
+        let pairing_input_offsets = layout.pairing_input_offsets();
+        let vkey_hash = hex::encode(key.hash()?);
+
+        // Fill pairing input offsets and proof offsets
+        let mut offsets = BTreeMap::new();
+        pairing_input_offsets
+            .iter()
+            .enumerate()
+            .for_each(|(i, offset)| {
+                offsets.insert(format!("pi_{}", i), format!("0x{:02x}", offset));
+            });
+        (0..8).for_each(|i| {
+            offsets.insert(
+                format!("in_{}", i),
+                format!("0x{:02x}", layout.input_ptr() + i * 0x20),
+            );
+        });
+
+        // Pair every input with its IC, at the same offsets as the loop computes
+        let n_inputs = layout.n_ics.saturating_sub(1);
+        let reverse_inputs = options.public_input_order == PublicInputOrder::Reverse;
+        let zksync = options.chain == Chain::Zksync;
+        // The packed key's offset comes before the proof words, if it is in calldata
+        let proof_ptr = if options.vkey_storage == VkeyStorage::Calldata {
+            0x24
+        } else {
+            0x04
+        };
+        let unrolled_inputs = if options.msm.unrolls(n_inputs, options.optimization) {
+            (0..n_inputs)
+                .map(|i| UnrolledInputContext {
+                    index: i,
+                    input_ptr: format!("0x{:02x}", layout.pub_input_ptr() + i * 0x20),
+                    ic_ptr: if reverse_inputs {
+                        format!("0x{:02x}", layout.ic_ptr_last() - i * G1_SIZE)
+                    } else {
+                        format!("0x{:02x}", MemoryLayout::IC_PTR + (i + 1) * G1_SIZE)
+                    },
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let event = options
+            .event_signature
+            .as_ref()
+            .map(|signature| EventContext {
+                signature: signature.clone(),
+                topic: hex::encode(keccak256(signature.as_bytes())),
+                vkey_hash: vkey_hash.clone(),
+                inputs_hash: options.event_inputs_hash,
+            });
+
+        // The verification key writes, at the same offsets as the Huff `codecopy`s
+        let context = Self {
+            mstore_ic: mstore_packed(&packed, 0x1E0, layout.ic_bytes(), MemoryLayout::IC_PTR),
+            mstore_alpha_beta: mstore_packed(
+                &packed,
+                0x00,
+                G1_SIZE + G2_SIZE,
+                pairing_input_offsets[6],
+            ),
+            mstore_gamma: mstore_packed(&packed, 0xC0, G2_SIZE, pairing_input_offsets[9]),
+            mstore_delta: mstore_packed(&packed, 0x140, G2_SIZE, pairing_input_offsets[12]),
+            packed_vkey: packed[2..].to_string(),
+            vkey_hash,
+            snark_scalar: hex::encode(Fr::MODULUS.to_bytes_be()),
+            n_ics: format!("0x{:02x}", layout.n_ics),
+            ic_bytes: format!("0x{:02x}", layout.ic_bytes()),
+            vkey_bytes: format!("0x{:02x}", (packed.len() - 2) / 2),
+            ic_ptr_last: format!("0x{:02x}", layout.ic_ptr_last()),
+            pub_input_len_ptr: format!("0x{:02x}", layout.pub_input_len_ptr()),
+            pub_input_ptr: format!("0x{:02x}", layout.pub_input_ptr()),
+            has_inputs: layout.n_ics > 1,
+            reverse_inputs,
+            optimize_size: options.optimization == Optimization::Size && !zksync,
+            evm_version: options.evm_version.name(),
+            push0: options.evm_version.has_push0(),
+            mcopy: options.evm_version.has_mcopy() && !zksync,
+            precompiles: options.precompiles.clone(),
+            forward_gas: !options.precompiles.is_standard() || zksync,
+            zksync,
+            abi: AbiContext {
+                name: options.verify_name().to_string(),
+                signature: options.verify_signature(n_inputs),
+                selector: hex::encode(options.verify_selector(n_inputs)),
+                snarkjs: options.abi == Abi::Snarkjs,
+                n_inputs,
+                input_bytes: format!("0x{:02x}", n_inputs * 0x20),
+                calldata_size: format!("0x{:02x}", 0x104 + n_inputs * 0x20),
+                array_bytes: format!("0x{:02x}", 0x20 + n_inputs * 0x20),
+                offset_ptr: format!(
+                    "0x{:02x}",
+                    proof_ptr
+                        + if options.compressed_proof {
+                            0x80
+                        } else {
+                            0x100
+                        }
+                ),
+                proof_ptr: format!("0x{:02x}", proof_ptr),
+            },
+            keccak_inputs: options.input_commitment == InputCommitment::Keccak,
+            compressed_proof: options.compressed_proof,
+            main: options.main || options.emit == Emit::Initcode,
+            library: options.emit == Emit::Library,
+            vkey_storage: VkeyStorageContext {
+                code: options.vkey_storage == VkeyStorage::Code,
+                constructor: options.vkey_storage == VkeyStorage::Constructor,
+                external: options.vkey_storage == VkeyStorage::External,
+                calldata: options.vkey_storage == VkeyStorage::Calldata,
+                storage: options.vkey_storage == VkeyStorage::Storage,
+                fixed: matches!(
+                    options.vkey_storage,
+                    VkeyStorage::Code | VkeyStorage::External
+                ),
+                shared_base: false,
+            },
+            vkey_data_codehash: hex::encode(vkey_data_codehash(key)?),
+            event,
+            subgroup_check: options
+                .strict_subgroup_checks
+                .then(SubgroupCheckContext::bn254),
+            check_proof_points: options.check_proof_points,
+            self_test: options
+                .self_test
+                .as_ref()
+                .map(|self_test| SelfTestContext::new(self_test, key, options))
+                .transpose()?,
+            erc165: options.erc165.then(|| Erc165Context {
+                selector: hex::encode(ERC165_INTERFACE_ID),
+                interface_id: hex::encode(options.interface_id(n_inputs)),
+            }),
+            errors: (options.on_failure == OnFailure::Revert).then(|| ErrorsContext {
+                invalid_proof: hex::encode(signature_selector(INVALID_PROOF_ERROR)),
+                invalid_public_input: hex::encode(signature_selector(INVALID_PUBLIC_INPUT_ERROR)),
+                invalid_proof_point: hex::encode(signature_selector(INVALID_PROOF_POINT_ERROR)),
+                invalid_calldata: hex::encode(signature_selector(INVALID_CALLDATA_ERROR)),
+            }),
+            admin: (options.vkey_storage == VkeyStorage::Storage).then(|| AdminContext {
+                set_vkey_selector: hex::encode(signature_selector(SET_VKEY_SIGNATURE)),
+                freeze_selector: hex::encode(signature_selector(FREEZE_SIGNATURE)),
+                owner_selector: hex::encode(signature_selector(OWNER_SIGNATURE)),
+                initialize_selector: hex::encode(signature_selector(INITIALIZE_SIGNATURE)),
+                vkey_set_topic: hex::encode(keccak256(VKEY_SET_EVENT_SIGNATURE.as_bytes())),
+                vkey_data_header: hex::encode(data_contract_header((packed.len() - 2) / 2)),
+            }),
+            unrolled_inputs,
+            offsets,
+        };
+
+        debug!(
+            n_ics = %context.n_ics,
+            packed_key_bytes = (packed.len() - 2) / 2,
+            vkey_hash = %context.vkey_hash,
+            "Packed the verification key"
+        );
+        debug!(
+            ic_ptr_last = %context.ic_ptr_last,
+            pairing_input_ptr = %format!("0x{:02x}", layout.pairing_input_ptr()),
+            input_ptr = %format!("0x{:02x}", layout.input_ptr()),
+            pub_input_len_ptr = %context.pub_input_len_ptr,
+            pub_input_ptr = %context.pub_input_ptr,
+            "Computed the memory layout"
+        );
+        trace!(offsets = ?context.offsets, "Computed the pairing input and proof offsets");
+        Ok(context)
+    }
+}
+
+/// The `mstore`s that write the `len` bytes of the `packed` verification key,
+/// starting at byte `offset`, to memory at `dest`.
+fn mstore_packed(packed: &str, offset: usize, len: usize, dest: usize) -> Vec<MstoreContext> {
+    let words = &packed[2..];
+    (0..len)
+        .step_by(0x20)
+        .map(|i| {
+            let start = (offset + i) * 2;
+            MstoreContext {
+                ptr: format!("0x{:02x}", dest + i),
+                word: format!("0x{}", &words[start..start + 0x40]),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::{decode_hex, deploy, synthetic_circuit, transact_from, BENCH_CALLER};
+    use crate::calldata::{encode_calldata_with, encode_set_vkey_calldata};
+    use crate::test_utils::sample_key;
+    use revm::primitives::{address, TxKind};
+
+    #[test]
+    fn bytecode_target_prints_runtime_and_creation_code() {
+        let key = sample_key();
+        let bytecode = generate_verifier_with(
+            &key,
+            &VerifierOptions {
+                target: Target::Bytecode,
+                ..VerifierOptions::default()
+            },
+        )
+        .unwrap();
+        let initcode = generate_verifier_with(
+            &key,
+            &VerifierOptions {
+                emit: Emit::Initcode,
+                ..VerifierOptions::default()
+            },
+        )
+        .unwrap();
+
+        let lines = bytecode.lines().collect::<Vec<_>>();
+        let runtime = lines[0].strip_prefix("runtime: 0x").unwrap();
+        let creation = lines[1].strip_prefix("creation: 0x").unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(format!("0x{}", creation), initcode);
+        assert!(creation.ends_with(runtime));
+    }
+
+    #[test]
+    fn storage_keys_are_only_set_by_the_owner_until_frozen() {
+        let (key, proof, public) = synthetic_circuit(2);
+        let mut other = key.clone();
+        other.vk_alpha_1 = other.ic[0].clone();
+        let options = VerifierOptions {
+            vkey_storage: VkeyStorage::Storage,
+            ..VerifierOptions::default()
+        };
+        let evm_version = options.evm_version;
+
+        // Deployed with the other key, owned by its deployer
+        let creation = VerifierOptions {
+            emit: Emit::Initcode,
+            ..options.clone()
+        };
+        let initcode = generate_verifier_with(&other, &creation).unwrap();
+        let initcode = initcode + &other.to_packed().unwrap()[2..];
+        let (db, verifier, _, _) = deploy(&decode_hex(&initcode).unwrap(), evm_version).unwrap();
+        let call = |db, caller, calldata: &str| {
+            let calldata = decode_hex(calldata).unwrap();
+            transact_from(db, evm_version, caller, TxKind::Call(verifier), calldata).unwrap()
+        };
+        let verify = encode_calldata_with(&proof, &public, &options).unwrap();
+        let set_vkey = encode_set_vkey_calldata(&key).unwrap();
+        let (owner, stranger) = (
+            BENCH_CALLER,
+            address!("2000000000000000000000000000000000000002"),
+        );
+
+        let (result, db) = call(db, owner, &verify);
+        assert_eq!(result.into_output().unwrap()[31], 0);
+
+        // Only the owner sets the key, after which the proof verifies
+        let (result, db) = call(db, stranger, &set_vkey);
+        assert!(!result.is_success());
+        let (result, db) = call(db, owner, &set_vkey);
+        assert!(result.is_success());
+        let (result, db) = call(db, owner, &verify);
+        assert_eq!(result.into_output().unwrap()[31], 1);
+
+        // Once frozen, the key can't be replaced
+        let freeze = format!("0x{}", hex::encode(signature_selector(FREEZE_SIGNATURE)));
+        let (result, db) = call(db, owner, &freeze);
+        assert!(result.is_success());
+        let (result, db) = call(db, owner, &encode_set_vkey_calldata(&other).unwrap());
+        assert!(!result.is_success());
+        let (result, _) = call(db, owner, &verify);
+        assert_eq!(result.into_output().unwrap()[31], 1);
+    }
+
+    #[test]
+    fn pairing_input_offsets_reproduce_the_groth16_layout() {
+        // The hardcoded `PI_OFFSET_BASES` the descriptor replaced
+        assert_eq!(
+            pairing_input_offset_bases(&GROTH16_PAIRING_TERMS),
+            [0x00, 0x20, 0x40, 0x60, 0x80, 0xA0, 0xC0, 0x180, 0x1A0, 0x1C0, 0x240, 0x260, 0x280]
+        );
+        assert_eq!(
+            MemoryLayout::new(3).pairing_input_offsets()[..2],
+            [0x180, 0x1A0]
+        );
+
+        // A fifth term, as a commitment would add, gets its own offsets
+        let mut terms = GROTH16_PAIRING_TERMS.to_vec();
+        terms.push(PairingTerm {
+            g1: PointSource::Memory,
+            g2: PointSource::VerificationKey,
+        });
+        let bases = pairing_input_offset_bases(&terms);
+        assert_eq!(bases.len(), 13 + 3);
+        assert_eq!(bases[13..], [0x300, 0x320, 0x340]);
+    }
+}
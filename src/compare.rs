@@ -0,0 +1,166 @@
+use crate::bench::BenchReport;
+use crate::error::Result;
+use crate::utils::render_template;
+use crate::vkey::VerificationKey;
+use serde::Serialize;
+use std::fmt;
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The Groth16 verifier template of snarkjs, the reference the Huff verifier is
+/// compared against
+pub static SNARKJS_VERIFIER_CONTRACT: &str = include_str!("contracts/SnarkjsVerifierTemplate.sol");
+
+/// The name of the contract of the snarkjs verifier
+pub static SNARKJS_CONTRACT_NAME: &str = "Groth16Verifier";
+
+////////////////////////////////////////////////////////////////
+//                     SNARKJS VERIFIER                       //
+////////////////////////////////////////////////////////////////
+
+/// Generates the Solidity verifier that `snarkjs zkey export solidityverifier` exports
+/// for `key`, from the key alone. Its `verifyProof` takes the calldata of
+/// [`crate::codegen::Abi::Snarkjs`].
+pub fn generate_snarkjs_verifier(key: &VerificationKey) -> Result<String> {
+    // snarkjs swaps the limbs of the G2 coordinates, like the packed key
+    let g2 = |point: &[Vec<String>]| G2Context {
+        x1: point[0][1].clone(),
+        x2: point[0][0].clone(),
+        y1: point[1][1].clone(),
+        y2: point[1][0].clone(),
+    };
+    let n_public = key.ic.len() - 1;
+    let context = SnarkjsContext {
+        alpha: G1Context {
+            index: 0,
+            x: key.vk_alpha_1[0].clone(),
+            y: key.vk_alpha_1[1].clone(),
+        },
+        beta: g2(&key.vk_beta_2),
+        gamma: g2(&key.vk_gamma_2),
+        delta: g2(&key.vk_delta_2),
+        ic: key
+            .ic
+            .iter()
+            .enumerate()
+            .map(|(index, point)| G1Context {
+                index,
+                x: point[0].clone(),
+                y: point[1].clone(),
+            })
+            .collect(),
+        n_public,
+        inputs: (0..n_public)
+            .map(|i| InputContext {
+                ic_index: i + 1,
+                offset: i * 32,
+            })
+            .collect(),
+        // snarkjs checks as many words as there are ICs, one past the public inputs
+        check_field_offsets: (0..key.ic.len()).map(|i| i * 32).collect(),
+    };
+    render_template(SNARKJS_VERIFIER_CONTRACT, &context)
+}
+
+/// The values the snarkjs verifier template is rendered with. Coordinates are decimal,
+/// as snarkjs writes them.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct SnarkjsContext {
+    alpha: G1Context,
+    beta: G2Context,
+    gamma: G2Context,
+    delta: G2Context,
+    ic: Vec<G1Context>,
+    n_public: usize,
+    /// The public inputs of the linear combination.
+    inputs: Vec<InputContext>,
+    /// The offsets of the public input words checked to be in the scalar field.
+    check_field_offsets: Vec<usize>,
+}
+
+/// A G1 point of the key, `IC[index]` for the ICs.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct G1Context {
+    index: usize,
+    x: String,
+    y: String,
+}
+
+/// A G2 point of the key, with the limbs of its coordinates in the order of the
+/// `ecPairing` precompile.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct G2Context {
+    x1: String,
+    x2: String,
+    y1: String,
+    y2: String,
+}
+
+/// A public input, multiplied by `IC[ic_index]`, at `offset` in the public inputs.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct InputContext {
+    ic_index: usize,
+    offset: usize,
+}
+
+////////////////////////////////////////////////////////////////
+//                     COMPARISON REPORT                      //
+////////////////////////////////////////////////////////////////
+
+/// The gas and code size of the Huff verifier of a key next to those of the snarkjs
+/// Solidity verifier, each measured by verifying the same proof under revm.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ComparisonReport {
+    /// The Huff verifier.
+    pub huff: BenchReport,
+    /// The snarkjs Solidity verifier.
+    pub solidity: BenchReport,
+}
+
+/// ComparisonReport implementation
+impl ComparisonReport {
+    /// The share of the Solidity verifier's `solidity` that the Huff verifier's `huff`
+    /// saves, as a signed percentage.
+    fn savings(huff: u64, solidity: u64) -> String {
+        let savings = 100.0 * (solidity as f64 - huff as f64) / solidity as f64;
+        format!("{:+.1}%", savings)
+    }
+}
+
+impl fmt::Display for ComparisonReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (huff, solidity) = (&self.huff, &self.solidity);
+        writeln!(f, "Public inputs:       {}", huff.n_inputs)?;
+        writeln!(f, "Runs:                {}", huff.runs.len())?;
+        writeln!(f)?;
+        writeln!(f, "| MEASUREMENT       | HUFF     | SOLIDITY | SAVED    |")?;
+        write!(f, "| ----------------- | -------- | -------- | -------- |")?;
+        for (name, huff, solidity) in [
+            ("Transaction (med)", huff.median, solidity.median),
+            ("Execution (med)", huff.execution(), solidity.execution()),
+            ("Deployment", huff.deployment, solidity.deployment),
+            (
+                "Code size (bytes)",
+                huff.code_size as u64,
+                solidity.code_size as u64,
+            ),
+        ] {
+            write!(
+                f,
+                "\n| {:<17} | {:>8} | {:>8} | {:>8} |",
+                name,
+                huff,
+                solidity,
+                Self::savings(huff, solidity)
+            )?;
+        }
+        Ok(())
+    }
+}
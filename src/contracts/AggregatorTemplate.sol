@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: GPL-3.0
+pragma solidity ^0.8.23;
+
+/// @notice The user operation of ERC-4337 EntryPoint v0.7.
+struct PackedUserOperation {
+    address sender;
+    uint256 nonce;
+    bytes initCode;
+    bytes callData;
+    bytes32 accountGasLimits;
+    uint256 preVerificationGas;
+    bytes32 gasFees;
+    bytes paymasterAndData;
+    bytes signature;
+}
+
+/// @notice The part of the ERC-4337 EntryPoint that the aggregator uses.
+interface IEntryPoint {
+    function getUserOpHash(PackedUserOperation calldata userOp) external view returns (bytes32);
+}
+
+/// @notice The ERC-4337 signature aggregator interface.
+interface IAggregator {
+    function validateSignatures(PackedUserOperation[] calldata userOps, bytes calldata signature) external view;
+
+    function validateUserOpSignature(PackedUserOperation calldata userOp)
+        external
+        view
+        returns (bytes memory sigForUserOp);
+
+    function aggregateSignatures(PackedUserOperation[] calldata userOps)
+        external
+        view
+        returns (bytes memory aggregatedSignature);
+}
+
+/// @title Huff Snark Verifier Aggregator
+/// @notice An ERC-4337 signature aggregator whose aggregated signature is a proof for
+///         the generated verifier, with the hashes of the bundle's user operations as
+///         its public inputs. Each hash has its top 3 bits cleared, so that it is in
+///         the scalar field.
+{{#if KECCAK_INPUTS}}
+/// @dev The verifier takes the hashes as raw inputs, and verifies the proof with
+///      their keccak256 commitment (`--input-commitment keccak`), so bundles can be
+///      of any size.
+{{else}}
+/// @dev Bundles must have exactly as many user operations as the circuit has public
+///      inputs ({{N_INPUTS}}). Pass `--input-commitment keccak` for bundles of any size.
+{{/if}}
+/// @dev This contract is generated by `huffv --aggregator`. The aggregated signature is
+///      the ABI-encoded `uint256[{{N_PROOF_WORDS}}]` proof words, as `huffv calldata{{#if COMPRESSED_PROOF}} --compressed-proof{{/if}}` encodes them.
+contract Aggregator is IAggregator {
+    /// @notice The keccak256 hash of the packed verification key of the verifier.
+    bytes32 public constant VKEY_HASH = 0x{{VKEY_HASH}};
+
+    /// @notice The selector of `{{SIGNATURE}}`.
+    bytes4 internal constant VERIFY_SELECTOR = 0x{{SELECTOR}};
+
+    /// @notice The entry point the user operations are hashed for.
+    IEntryPoint public immutable entryPoint;
+
+    /// @notice The generated verifier.
+    address public immutable verifier;
+
+    /// @notice The verifier was generated for a different verification key.
+    error VerifierMismatch();
+
+    /// @notice The aggregated signature isn't a valid proof for the bundle.
+    error InvalidAggregatedSignature();
+
+    /// @notice Proofs are generated off-chain, by the bundler's prover.
+    error OffChainAggregation();
+
+    constructor(IEntryPoint _entryPoint, address _verifier) {
+        (bool success, bytes memory vkeyHash) = _verifier.staticcall(abi.encodeWithSignature("VKEY_HASH()"));
+        if (!success || vkeyHash.length != 0x20 || abi.decode(vkeyHash, (bytes32)) != VKEY_HASH) {
+            revert VerifierMismatch();
+        }
+        entryPoint = _entryPoint;
+        verifier = _verifier;
+    }
+
+    /// @notice Reverts unless `signature` is a valid proof for `userOps`.
+    function validateSignatures(PackedUserOperation[] calldata userOps, bytes calldata signature)
+        external
+        view
+        override
+    {
+        uint256[{{N_PROOF_WORDS}}] memory proof = abi.decode(signature, (uint256[{{N_PROOF_WORDS}}]));
+        (bool success, bytes memory valid) =
+            verifier.staticcall(abi.encodeWithSelector(VERIFY_SELECTOR, proof, publicInputs(userOps)));
+        if (!success || valid.length != 0x20 || !abi.decode(valid, (bool))) {
+            revert InvalidAggregatedSignature();
+        }
+    }
+
+    /// @notice User operations carry no signature of their own, as the aggregated
+    ///         proof covers the whole bundle.
+    function validateUserOpSignature(PackedUserOperation calldata)
+        external
+        pure
+        override
+        returns (bytes memory sigForUserOp)
+    {
+        return "";
+    }
+
+    /// @notice Always reverts, as the aggregated proof can't be computed on-chain.
+    function aggregateSignatures(PackedUserOperation[] calldata)
+        external
+        pure
+        override
+        returns (bytes memory)
+    {
+        revert OffChainAggregation();
+    }
+
+    /// @notice The public inputs of the proof for `userOps`: their hashes, with the
+    ///         top 3 bits cleared.
+    function publicInputs(PackedUserOperation[] calldata userOps) public view returns (uint256[] memory inputs) {
+        inputs = new uint256[](userOps.length);
+        for (uint256 i = 0; i < userOps.length; i++) {
+            inputs[i] = uint256(entryPoint.getUserOpHash(userOps[i])) & (type(uint256).max >> 3);
+        }
+    }
+}
@@ -0,0 +1,661 @@
+// SPDX-License-Identifier: GPL-3.0
+pragma solidity {{#if MCOPY}}^0.8.24{{else}}^0.8.4{{/if}};
+
+/// @title Huff Snark Verifier
+/// @notice A gas-optimized, purpose-driven snark verification contract.
+///         This contract is an inline-assembly port of VerifierTemplate.huff, and it
+///         shares its memory layout. See the memory diagram at the top of that contract.
+{{#if MCOPY}}
+/// @dev The proof is copied into the `ecPairing` inputs with `MCOPY`, which takes both
+///      less code and less gas than the copies of `--opt size` and `--opt gas`.
+{{else if OPTIMIZE_SIZE}}
+/// @dev Optimized for code size (`--opt size`): the proof is copied into the `ecPairing`
+///      inputs with the identity precompile, for less code than `--opt gas` at ~80 more
+///      gas per verification.
+{{else}}
+/// @dev Optimized for gas (`--opt gas`): the proof is copied into the `ecPairing` inputs
+///      word by word, for ~80 less gas per verification than `--opt size` at the cost
+///      of more code.
+{{/if}}
+/// @dev Targets the `{{EVM_VERSION}}` EVM version (`--evm-version {{EVM_VERSION}}`). Compile it with
+///      `solc --evm-version {{EVM_VERSION}}`.
+{{#if ZKSYNC}}
+/// @dev Generated for zkSync Era (`--chain zksync`): EraVM has no identity precompile
+///      or `MCOPY`, so the proof is copied word by word.
+{{/if}}
+{{#if FORWARD_GAS}}
+/// @dev Forwards all gas to the precompiles rather than what the standard precompiles
+///      cost, as they are relocated (`--precompile-addresses`) or repriced (`--chain`).
+{{/if}}
+{{#if ERRORS}}
+/// @dev Reverts with a custom error rather than returning false (`--on-failure revert`).
+{{/if}}
+{{#if COMPRESSED_PROOF}}
+/// @dev Takes the proof with its points compressed to their `x` coordinates, and
+///      recovers their `y` coordinates with the modexp precompile (`--compressed-proof`).
+{{/if}}
+{{#if CHECK_PROOF_POINTS}}
+/// @dev Checks that the proof's points aren't the point at infinity and that their
+///      coordinates are in the base field (`--check-proof-points`), before spending gas
+///      on the linear combination and a pairing that would fail.
+{{/if}}
+{{#if SUBGROUP_CHECK}}
+/// @dev Checks that `proof_b` is in the G2 subgroup before the pairing
+///      (`--strict-subgroup-checks`), rather than relying on the `ecPairing` precompile,
+///      which EIP-197 requires to reject it, but which may be replaced or repriced.
+{{/if}}
+{{#if ERC165}}
+/// @dev Answers ERC-165's `supportsInterface(bytes4)` (`--erc165`) for the verifier's
+///      interface id, 0x{{ERC165.INTERFACE_ID}}, and for ERC-165's own.
+{{/if}}
+{{#if KECCAK_INPUTS}}
+/// @dev Takes any number of raw inputs, and verifies the proof with their keccak256
+///      commitment as its only public input (`--input-commitment keccak`).
+{{/if}}
+///
+/// @author clabby <https://github.com/clabby>
+contract Verifier {
+    ////////////////////////////////////////////////////////////////
+    //                         CONSTANTS                          //
+    ////////////////////////////////////////////////////////////////
+
+    /// @notice The snark scalar field. Every public input must be less than it.
+    uint256 internal constant SNARK_SCALAR = 0x{{SNARK_SCALAR}};
+
+    /// @notice The prime q in the base field F_q for G1.
+    uint256 internal constant Q = 0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47;
+{{#if COMPRESSED_PROOF}}
+
+    /// @notice (Q + 1) / 4, the exponent of a square root in the base field.
+    uint256 internal constant FQ_SQRT_EXP = 0xc19139cb84c680a6e14116da060561765e05aa45a1c72a34f082305b61f3f52;
+
+    /// @notice Q - 2, the exponent of an inverse in the base field.
+    uint256 internal constant Q_MINUS_2 = 0x30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45;
+
+    /// @notice The inverse of 2 in the base field.
+    uint256 internal constant INV_2 = 0x183227397098d014dc2822db40c0ac2ecbc0b548b438e5469e10460b6c3e7ea4;
+
+    /// @notice The coefficient `b` of the G2 curve, 3 / (9 + u).
+    uint256 internal constant B2_0 = 0x2b149d40ceb8aaae81be18991be06ac3b5b4c5e559dbefa33267e6dc24a138e5;
+    uint256 internal constant B2_1 = 0x9713b03af0fed4cd2cafadeed8fdf4a74fa084e52d1852e4a2bd0685c315d2;
+{{/if}}
+{{#if SUBGROUP_CHECK}}
+
+    /// @notice 6 * u^2, for the BN254 parameter u. `proof_b` is in the G2 subgroup iff
+    ///         psi(proof_b) == [6 * u^2] proof_b, where psi is the untwist-Frobenius-twist
+    ///         endomorphism.
+    uint256 internal constant SIX_U_SQUARED = 0x{{SUBGROUP_CHECK.SIX_U_SQUARED}};
+
+    /// @notice The coefficients of psi: psi(x, y) = (conj(x) * PSI_X, conj(y) * PSI_Y),
+    ///         where PSI_X = (9 + i)^((q - 1) / 3) and PSI_Y = (9 + i)^((q - 1) / 2).
+    uint256 internal constant PSI_X_0 = 0x{{SUBGROUP_CHECK.PSI_X_0}};
+    uint256 internal constant PSI_X_1 = 0x{{SUBGROUP_CHECK.PSI_X_1}};
+    uint256 internal constant PSI_Y_0 = 0x{{SUBGROUP_CHECK.PSI_Y_0}};
+    uint256 internal constant PSI_Y_1 = 0x{{SUBGROUP_CHECK.PSI_Y_1}};
+{{/if}}
+
+    /// @notice The number of ICs in the verification key.
+    uint256 internal constant N_ICS = {{N_ICS}};
+
+    /// @notice The location of the first element of the `IC` portion of the verification key in memory.
+    uint256 internal constant IC_PTR = 0xc0;
+
+    /// @notice The location of the second element of the `IC` portion of the verification key in memory.
+    uint256 internal constant IC_PTR_SECOND = 0x100;
+
+    /// @notice The location of the last element of the `IC` portion of the verification key in memory.
+    uint256 internal constant IC_PTR_LAST = {{IC_PTR_LAST}};
+
+    /// @notice The location of the proof in memory.
+    uint256 internal constant PROOF_PTR = {{in_0}};
+
+    /// @notice The location of the length of the public inputs in memory.
+    uint256 internal constant PUB_INPUT_LEN_PTR = {{PUB_INPUT_LEN_PTR}};
+
+    /// @notice The location of the public inputs in memory.
+    uint256 internal constant PUB_INPUT_PTR = {{PUB_INPUT_PTR}};
+
+    /// @notice The location of `vk_x`'s x component in memory
+    uint256 internal constant VK_X_X_PTR = 0x80;
+
+    /// @notice The location of `vk_x`'s y component in memory
+    uint256 internal constant VK_X_Y_PTR = 0xA0;
+
+    /// @notice The keccak256 hash of the packed verification key, so that a deployed
+    ///         verifier can be matched to its circuit.
+    bytes32 public constant VKEY_HASH = 0x{{VKEY_HASH}};
+{{#if ERRORS}}
+
+    ////////////////////////////////////////////////////////////////
+    //                           ERRORS                           //
+    ////////////////////////////////////////////////////////////////
+
+    /// @notice The proof failed the pairing check, or its points aren't on their curves.
+    error InvalidProof();
+
+    /// @notice The public input at `index` is out of the scalar field.
+    error InvalidPublicInput(uint256 index);
+
+    /// @notice The calldata is malformed.
+    error InvalidCalldata();
+{{#if CHECK_PROOF_POINTS}}
+
+    /// @notice A proof point is the point at infinity, or has a coordinate out of the base field.
+    error InvalidProofPoint();
+{{/if}}
+{{/if}}
+{{#if ERC165}}
+
+    ////////////////////////////////////////////////////////////////
+    //                          ERC-165                           //
+    ////////////////////////////////////////////////////////////////
+
+    /// @notice Returns whether `interfaceId` is the verifier's interface id, the XOR of
+    ///         the selectors of `{{ABI.SIGNATURE}}` and `VKEY_HASH()`, or ERC-165's.
+    function supportsInterface(bytes4 interfaceId) external pure returns (bool) {
+        return interfaceId == 0x{{ERC165.INTERFACE_ID}} || interfaceId == 0x{{ERC165.SELECTOR}};
+    }
+{{/if}}
+
+    ////////////////////////////////////////////////////////////////
+    //                          VERIFIER                          //
+    ////////////////////////////////////////////////////////////////
+
+    /// @notice Verifies a SNARK proof.
+{{#if ABI.SNARKJS}}
+    /// @dev Matches the `verifyProof` of snarkjs's Solidity verifiers. The function
+    ///      never returns to Solidity, so the assembly below is free to use all of memory.
+    /// @return valid True if proof is valid, false if not
+    function {{ABI.NAME}}(
+        uint256[2] calldata,
+        uint256[2][2] calldata,
+        uint256[2] calldata,
+        uint256[{{ABI.N_INPUTS}}] calldata
+    ) external {{#unless EVENT}}view {{/unless}}returns (bool valid) {
+{{else}}
+{{#if KECCAK_INPUTS}}
+    /// @dev Takes any number of raw inputs, committed to with keccak256. The function
+    ///      never returns to Solidity, so the assembly below is free to use all of memory.
+{{else}}
+    /// @dev Reverts unless there are exactly as many public inputs as the circuit has.
+    ///      The function never returns to Solidity, so the assembly below is free to
+    ///      use all of memory.
+{{/if}}
+{{#if COMPRESSED_PROOF}}
+    /// @dev The proof is compressed to `a_0, b_0_1, b_0_0, c_0`, with the parities of
+    ///      `a_1`, `b_1_1`, and `c_1` in their top bits, see `huffv calldata --compressed-proof`.
+{{/if}}
+    /// @return valid True if proof is valid, false if not
+    function {{ABI.NAME}}(
+{{#if COMPRESSED_PROOF}}
+        uint256[4] calldata,
+{{else}}
+        uint256[2] calldata,
+        uint256[2][2] calldata,
+        uint256[2] calldata,
+{{/if}}
+        uint256[] calldata input
+    ) external {{#unless EVENT}}view {{/unless}}returns (bool valid) {
+{{/if}}
+        assembly {
+            ////////////////////////////////////////////////////////////////
+            //                         EC HELPERS                         //
+            ////////////////////////////////////////////////////////////////
+
+            /// @notice Calls the ecAdd precompile with the G1 points @ 0x00 and 0x40.
+            ///         Writes the result to memory @ VK_X_X_PTR (0x80).
+            function addition() {
+                if iszero(staticcall({{#if FORWARD_GAS}}gas(){{else}}0x96{{/if}}, {{PRECOMPILES.EC_ADD}}, 0x00, 0x80, 0x80, 0x40)) {
+                    revert(0x00, 0x00)
+                }
+            }
+
+            /// @notice Calls the ecMul precompile with the G1 point @ `ptr_a` and `scalar`.
+            ///         Writes the result to scratch memory @ 0x00.
+            function scalar_mul(ptr_a, scalar) {
+                mstore(0x00, mload(ptr_a))
+                mstore(0x20, mload(add(ptr_a, 0x20)))
+                mstore(0x40, scalar)
+                if iszero(staticcall({{#if FORWARD_GAS}}gas(){{else}}0x1770{{/if}}, {{PRECOMPILES.EC_MUL}}, 0x00, 0x60, 0x00, 0x40)) {
+                    revert(0x00, 0x00)
+                }
+            }
+{{#if COMPRESSED_PROOF}}
+
+            ////////////////////////////////////////////////////////////////
+            //                       DECOMPRESSION                        //
+            ////////////////////////////////////////////////////////////////
+
+            /// @notice Calls the modexp precompile for `base ** exponent % Q`.
+            function modexp(base, exponent) -> result {
+                mstore(0x00, 0x20)
+                mstore(0x20, 0x20)
+                mstore(0x40, 0x20)
+                mstore(0x60, base)
+                mstore(0x80, exponent)
+                mstore(0xa0, Q)
+                if iszero(staticcall({{#if FORWARD_GAS}}gas(){{else}}0x0546{{/if}}, {{PRECOMPILES.MODEXP}}, 0x00, 0xc0, 0x00, 0x20)) {
+                    revert(0x00, 0x00)
+                }
+                result := mload(0x00)
+            }
+
+            /// @notice Decompresses the G1 point @ `cd_ptr` in calldata, its `x` coordinate
+            ///         with the parity of `y` in its top bit, to memory @ `out_ptr`.
+            function decompress_g1(cd_ptr, out_ptr) {
+                let word := calldataload(cd_ptr)
+                let x := shr(0x01, shl(0x01, word))
+
+                // y = sqrt(x^3 + 3), negated if its parity isn't the flag's
+                let y := modexp(addmod(mulmod(mulmod(x, x, Q), x, Q), 0x03, Q), FQ_SQRT_EXP)
+                if xor(and(y, 0x01), shr(0xff, word)) {
+                    y := sub(Q, y)
+                }
+                mstore(out_ptr, x)
+                mstore(add(out_ptr, 0x20), y)
+            }
+
+            /// @notice Decompresses the G2 point @ `cd_ptr` in calldata, `x_1` with the
+            ///         parity of `y_1` in its top bit followed by `x_0`, to memory @ `out_ptr`
+            ///         with the limbs of its coordinates swapped like the proof's `b`.
+            function decompress_g2(cd_ptr, out_ptr) {
+                let word := calldataload(cd_ptr)
+                let x_1 := shr(0x01, shl(0x01, word))
+                let x_0 := calldataload(add(cd_ptr, 0x20))
+
+                // a = x^3 + b
+                let t_0 := addmod(mulmod(x_0, x_0, Q), sub(Q, mulmod(x_1, x_1, Q)), Q)
+                let t_1 := mulmod(addmod(x_0, x_0, Q), x_1, Q)
+                let a_0 := addmod(addmod(mulmod(t_0, x_0, Q), sub(Q, mulmod(t_1, x_1, Q)), Q), B2_0, Q)
+                let a_1 := addmod(addmod(mulmod(t_0, x_1, Q), mulmod(t_1, x_0, Q), Q), B2_1, Q)
+
+                // y_0 = sqrt(d), where d = (a_0 + alpha) / 2 if it is a square, or else
+                // (a_0 - alpha) / 2, and alpha = sqrt(a_0^2 + a_1^2)
+                let alpha := modexp(addmod(mulmod(a_0, a_0, Q), mulmod(a_1, a_1, Q), Q), FQ_SQRT_EXP)
+                let d := mulmod(addmod(a_0, alpha, Q), INV_2, Q)
+                let y_0 := modexp(d, FQ_SQRT_EXP)
+                if iszero(eq(mulmod(y_0, y_0, Q), d)) {
+                    y_0 := modexp(addmod(d, sub(Q, alpha), Q), FQ_SQRT_EXP)
+                }
+
+                // y_1 = a_1 / (2 * y_0), and y is negated if the parity of y_1 isn't the flag's
+                let y_1 := mulmod(a_1, modexp(addmod(y_0, y_0, Q), Q_MINUS_2), Q)
+                if xor(and(y_1, 0x01), shr(0xff, word)) {
+                    y_0 := mod(sub(Q, y_0), Q)
+                    y_1 := sub(Q, y_1)
+                }
+                mstore(out_ptr, x_1)
+                mstore(add(out_ptr, 0x20), x_0)
+                mstore(add(out_ptr, 0x40), y_1)
+                mstore(add(out_ptr, 0x60), y_0)
+            }
+{{/if}}
+{{#if SUBGROUP_CHECK}}
+
+            ////////////////////////////////////////////////////////////////
+            //                       SUBGROUP CHECK                       //
+            ////////////////////////////////////////////////////////////////
+
+            // The scratch memory of `g2_subgroup_check()`, below the proof: the Jacobian
+            // point T @ 0x00 (X), 0x40 (Y), and 0x80 (Z), `proof_b` as P @ 0xC0 (x) and
+            // 0x100 (y), and 7 temporaries @ 0x140 to 0x2C0. Each is an F_q^2 element
+            // (c_0, c_1) of 0x40 bytes.
+
+            /// @notice Writes `a * b` to `dst`, for the F_q^2 elements at `a`, `b`, and `dst`.
+            function fq2_mul(dst, a, b) {
+                let a_0 := mload(a)
+                let a_1 := mload(add(a, 0x20))
+                let b_0 := mload(b)
+                let b_1 := mload(add(b, 0x20))
+                mstore(dst, addmod(mulmod(a_0, b_0, Q), sub(Q, mulmod(a_1, b_1, Q)), Q))
+                mstore(add(dst, 0x20), addmod(mulmod(a_0, b_1, Q), mulmod(a_1, b_0, Q), Q))
+            }
+
+            /// @notice Writes `a^2` to `dst`, for the F_q^2 elements at `a` and `dst`.
+            function fq2_sqr(dst, a) {
+                let a_0 := mload(a)
+                let a_1 := mload(add(a, 0x20))
+                mstore(dst, mulmod(addmod(a_0, a_1, Q), addmod(a_0, sub(Q, a_1), Q), Q))
+                mstore(add(dst, 0x20), mulmod(addmod(a_0, a_0, Q), a_1, Q))
+            }
+
+            /// @notice Writes `a + b` to `dst`, for the F_q^2 elements at `a`, `b`, and `dst`.
+            function fq2_add(dst, a, b) {
+                let c_1 := addmod(mload(add(a, 0x20)), mload(add(b, 0x20)), Q)
+                mstore(dst, addmod(mload(a), mload(b), Q))
+                mstore(add(dst, 0x20), c_1)
+            }
+
+            /// @notice Writes `a - b` to `dst`, for the F_q^2 elements at `a`, `b`, and `dst`.
+            function fq2_sub(dst, a, b) {
+                let c_1 := addmod(mload(add(a, 0x20)), sub(Q, mload(add(b, 0x20))), Q)
+                mstore(dst, addmod(mload(a), sub(Q, mload(b)), Q))
+                mstore(add(dst, 0x20), c_1)
+            }
+
+            /// @notice Doubles the Jacobian point T in place (dbl-2009-l, for a = 0).
+            function g2_double() {
+                fq2_sqr(0x140, 0x00)          // A = X^2
+                fq2_sqr(0x180, 0x40)          // B = Y^2
+                fq2_sqr(0x1c0, 0x180)         // C = B^2
+                fq2_mul(0x200, 0x00, 0x180)   // D = X * B
+                fq2_add(0x200, 0x200, 0x200)  // D = 2 * X * B
+                fq2_add(0x200, 0x200, 0x200)  // D = 4 * X * B
+                fq2_add(0x240, 0x140, 0x140)  // E = 2 * A
+                fq2_add(0x240, 0x240, 0x140)  // E = 3 * A
+                fq2_sqr(0x280, 0x240)         // F = E^2
+                fq2_mul(0x80, 0x40, 0x80)     // Z = Y * Z
+                fq2_add(0x80, 0x80, 0x80)     // Z_3 = 2 * Y * Z
+                fq2_sub(0x00, 0x280, 0x200)   // X = F - D
+                fq2_sub(0x00, 0x00, 0x200)    // X_3 = F - 2 * D
+                fq2_sub(0x2c0, 0x200, 0x00)   // D - X_3
+                fq2_mul(0x2c0, 0x240, 0x2c0)  // E * (D - X_3)
+                fq2_add(0x1c0, 0x1c0, 0x1c0)  // 2 * C
+                fq2_add(0x1c0, 0x1c0, 0x1c0)  // 4 * C
+                fq2_add(0x1c0, 0x1c0, 0x1c0)  // 8 * C
+                fq2_sub(0x40, 0x2c0, 0x1c0)   // Y_3 = E * (D - X_3) - 8 * C
+            }
+
+            /// @notice Adds the affine point P to the Jacobian point T in place
+            ///         (madd-2004-hmv). If T is P or -P, which it can only be if P isn't
+            ///         in the subgroup, Z becomes zero and stays zero, and
+            ///         `g2_subgroup_check()` rejects P.
+            function g2_add_p() {
+                fq2_sqr(0x140, 0x80)          // Z^2
+                fq2_mul(0x180, 0x140, 0x80)   // Z^3
+                fq2_mul(0x140, 0x140, 0xc0)   // U = P_x * Z^2
+                fq2_mul(0x180, 0x180, 0x100)  // S = P_y * Z^3
+                fq2_sub(0x140, 0x140, 0x00)   // H = U - X
+                fq2_sub(0x180, 0x180, 0x40)   // R = S - Y
+                fq2_mul(0x80, 0x80, 0x140)    // Z_3 = Z * H
+                fq2_sqr(0x1c0, 0x140)         // H^2
+                fq2_mul(0x200, 0x1c0, 0x140)  // H^3
+                fq2_mul(0x1c0, 0x00, 0x1c0)   // V = X * H^2
+                fq2_sqr(0x00, 0x180)          // X = R^2
+                fq2_sub(0x00, 0x00, 0x200)    // X = R^2 - H^3
+                fq2_sub(0x00, 0x00, 0x1c0)    // X = R^2 - H^3 - V
+                fq2_sub(0x00, 0x00, 0x1c0)    // X_3 = R^2 - H^3 - 2 * V
+                fq2_sub(0x1c0, 0x1c0, 0x00)   // V - X_3
+                fq2_mul(0x1c0, 0x180, 0x1c0)  // R * (V - X_3)
+                fq2_mul(0x200, 0x40, 0x200)   // Y * H^3
+                fq2_sub(0x40, 0x1c0, 0x200)   // Y_3 = R * (V - X_3) - Y * H^3
+            }
+
+            /// @notice Returns whether `proof_b` is the point at infinity or in the G2
+            ///         subgroup, that is whether psi(proof_b) == [6 * u^2] proof_b. The
+            ///         ecPairing precompile checks that it is on the curve.
+            function g2_subgroup_check() -> in_subgroup {
+                let x_1 := mod(mload({{in_2}}), Q)
+                let x_0 := mod(mload({{in_3}}), Q)
+                let y_1 := mod(mload({{in_4}}), Q)
+                let y_0 := mod(mload({{in_5}}), Q)
+
+                // The point at infinity is in the subgroup
+                if iszero(or(or(x_1, x_0), or(y_1, y_0))) {
+                    in_subgroup := 0x01
+                    leave
+                }
+
+                // T = P = proof_b, in (c_0, c_1) order
+                mstore(0xc0, x_0)
+                mstore(0xe0, x_1)
+                mstore(0x100, y_0)
+                mstore(0x120, y_1)
+                mstore(0x00, x_0)
+                mstore(0x20, x_1)
+                mstore(0x40, y_0)
+                mstore(0x60, y_1)
+                mstore(0x80, 0x01)
+                mstore(0xa0, 0x00)
+
+                // T = [6 * u^2] P, doubling and adding from the second most significant bit
+                for { let i := {{SUBGROUP_CHECK.START_BIT}} } 0x01 { i := sub(i, 0x01) } {
+                    g2_double()
+                    if and(shr(i, SIX_U_SQUARED), 0x01) {
+                        g2_add_p()
+                    }
+                    if iszero(i) {
+                        break
+                    }
+                }
+
+                // psi(P) = (conj(P_x) * psi_x, conj(P_y) * psi_y) @ 0x140 and 0x1c0
+                mstore(0x140, x_0)
+                mstore(0x160, sub(Q, x_1))
+                mstore(0x180, PSI_X_0)
+                mstore(0x1a0, PSI_X_1)
+                fq2_mul(0x140, 0x140, 0x180)
+                mstore(0x1c0, y_0)
+                mstore(0x1e0, sub(Q, y_1))
+                mstore(0x200, PSI_Y_0)
+                mstore(0x220, PSI_Y_1)
+                fq2_mul(0x1c0, 0x1c0, 0x200)
+
+                // In Jacobian coordinates with Z != 0, psi(P) == T iff
+                // psi(P)_x * Z^2 == X and psi(P)_y * Z^3 == Y
+                fq2_sqr(0x200, 0x80)
+                fq2_mul(0x140, 0x140, 0x200)
+                fq2_mul(0x200, 0x200, 0x80)
+                fq2_mul(0x1c0, 0x1c0, 0x200)
+                in_subgroup := and(
+                    and(
+                        and(eq(mload(0x140), mload(0x00)), eq(mload(0x160), mload(0x20))),
+                        and(eq(mload(0x1c0), mload(0x40)), eq(mload(0x1e0), mload(0x60)))
+                    ),
+                    iszero(iszero(or(mload(0x80), mload(0xa0))))
+                )
+            }
+{{/if}}
+
+            // Copy the proof and public inputs to memory
+{{#if COMPRESSED_PROOF}}
+            decompress_g1(PROOF_PTR, 0x04)
+            decompress_g2({{in_2}}, 0x24)
+            decompress_g1({{in_6}}, 0x64)
+
+            // Zero vk_x, which the modexp calls overwrote
+            mstore(VK_X_X_PTR, 0x00)
+            mstore(VK_X_Y_PTR, 0x00)
+{{else}}
+            calldatacopy(PROOF_PTR, 0x04, 0x100)
+{{/if}}
+{{#if ABI.SNARKJS}}
+            calldatacopy(PUB_INPUT_PTR, 0x104, {{ABI.INPUT_BYTES}})
+            mstore(PUB_INPUT_LEN_PTR, {{ABI.N_INPUTS}})
+{{else if KECCAK_INPUTS}}
+            // Hash the raw inputs into the only public input. Clearing the top 3 bits
+            // of the hash puts it in the scalar field.
+            let input_bytes := shl(0x05, input.length)
+            calldatacopy(PUB_INPUT_PTR, input.offset, input_bytes)
+            mstore(PUB_INPUT_PTR, shr(0x03, shl(0x03, keccak256(PUB_INPUT_PTR, input_bytes))))
+            mstore(PUB_INPUT_LEN_PTR, 0x01)
+{{else}}
+            calldatacopy(PUB_INPUT_PTR, input.offset, shl(0x05, input.length))
+            mstore(PUB_INPUT_LEN_PTR, input.length)
+{{/if}}
+{{#if CHECK_PROOF_POINTS}}
+
+            // require(no proof point is the point at infinity, and every coordinate < q)
+            let valid_points := and(lt(mload({{in_0}}), Q), lt(mload({{in_1}}), Q))
+            valid_points := and(valid_points, and(lt(mload({{in_2}}), Q), lt(mload({{in_3}}), Q)))
+            valid_points := and(valid_points, and(lt(mload({{in_4}}), Q), lt(mload({{in_5}}), Q)))
+            valid_points := and(valid_points, and(lt(mload({{in_6}}), Q), lt(mload({{in_7}}), Q)))
+            valid_points := and(valid_points, iszero(iszero(or(mload({{in_0}}), mload({{in_1}})))))
+            valid_points := and(valid_points, iszero(iszero(or(or(mload({{in_2}}), mload({{in_3}})), or(mload({{in_4}}), mload({{in_5}}))))))
+            valid_points := and(valid_points, iszero(iszero(or(mload({{in_6}}), mload({{in_7}})))))
+            if iszero(valid_points) {
+{{#if ERRORS}}
+                mstore(0x00, 0x{{ERRORS.INVALID_PROOF_POINT}})
+                revert(0x1c, 0x04)
+{{else}}
+                revert(0x00, 0x00)
+{{/if}}
+            }
+{{/if}}
+{{#if SUBGROUP_CHECK}}
+
+            // require(proof_b is in the G2 subgroup)
+            if iszero(g2_subgroup_check()) {
+{{#if ERRORS}}
+                mstore(0x00, 0x{{ERRORS.INVALID_PROOF}})
+                revert(0x1c, 0x04)
+{{else}}
+                revert(0x00, 0x00)
+{{/if}}
+            }
+
+            // Zero vk_x, which the subgroup check overwrote
+            mstore(VK_X_X_PTR, 0x00)
+            mstore(VK_X_Y_PTR, 0x00)
+{{/if}}
+
+            // Store the ICs in memory @ 0xC0
+{{#each MSTORE_IC}}
+            mstore({{PTR}}, {{WORD}})
+{{/each}}
+
+            // require(input_len + 1 == n_ics)
+            let input_len := mload(PUB_INPUT_LEN_PTR)
+            if iszero(eq(add(input_len, 0x01), N_ICS)) {
+{{#if ERRORS}}
+                mstore(0x00, 0x{{ERRORS.INVALID_CALLDATA}})
+                revert(0x1c, 0x04)
+{{else}}
+                revert(0x00, 0x00)
+{{/if}}
+            }
+
+{{#if UNROLLED_INPUTS}}
+            // Compute the linear combination vk_x, unrolled with each input's offsets
+{{#each UNROLLED_INPUTS}}
+            // require(input[{{INDEX}}] < snark_scalar)
+            if iszero(lt(mload({{INPUT_PTR}}), SNARK_SCALAR)) {
+{{#if ../ERRORS}}
+                mstore(0x00, 0x{{../ERRORS.INVALID_PUBLIC_INPUT}})
+                mstore(0x20, {{INDEX}})
+                revert(0x1c, 0x24)
+{{else}}
+                revert(0x00, 0x00)
+{{/if}}
+            }
+            // Scalar mul the IC paired with input[{{INDEX}}], and add it to vk_x
+            scalar_mul({{IC_PTR}}, mload({{INPUT_PTR}}))
+            mstore(0x40, mload(VK_X_X_PTR))
+            mstore(0x60, mload(VK_X_Y_PTR))
+            addition()
+{{/each}}
+{{else}}
+            // Compute the linear combination vk_x
+            for { let i := 0x00 } lt(i, input_len) { i := add(i, 0x01) } {
+                let input := mload(add(PUB_INPUT_PTR, shl(0x05, i)))
+
+                // require(input[i] < snark_scalar)
+                if iszero(lt(input, SNARK_SCALAR)) {
+{{#if ERRORS}}
+                    mstore(0x00, 0x{{ERRORS.INVALID_PUBLIC_INPUT}})
+                    mstore(0x20, i)
+                    revert(0x1c, 0x24)
+{{else}}
+                    revert(0x00, 0x00)
+{{/if}}
+                }
+
+                // Scalar mul the IC paired with input[i], input[i]
+                scalar_mul({{#if REVERSE_INPUTS}}sub(IC_PTR_LAST, shl(0x06, i)){{else}}add(IC_PTR_SECOND, shl(0x06, i)){{/if}}, input)
+
+                // Add the scalar mul result @ 0x00 to vk_x
+                mstore(0x40, mload(VK_X_X_PTR))
+                mstore(0x60, mload(VK_X_Y_PTR))
+                addition()
+            }
+{{/if}}
+
+            // Add the point in IC[0] to `vk_x`
+            mstore(0x00, mload(IC_PTR))
+            mstore(0x20, mload(add(IC_PTR, 0x20)))
+            mstore(0x40, mload(VK_X_X_PTR))
+            mstore(0x60, mload(VK_X_Y_PTR))
+            addition()
+
+            // Store negate(proof_a), proof_b @ {{pi_0}}
+{{#if MCOPY}}
+            mcopy({{pi_0}}, {{in_0}}, 0xc0)
+            let proof_a_1 := mload({{pi_1}})
+            if or(mload({{pi_0}}), proof_a_1) {
+                proof_a_1 := sub(Q, mod(proof_a_1, Q))
+            }
+            mstore({{pi_1}}, proof_a_1)
+{{else if OPTIMIZE_SIZE}}
+            pop(staticcall(gas(), {{PRECOMPILES.IDENTITY}}, {{in_0}}, 0xc0, {{pi_0}}, 0xc0))
+            let proof_a_1 := mload({{pi_1}})
+            if or(mload({{pi_0}}), proof_a_1) {
+                proof_a_1 := sub(Q, mod(proof_a_1, Q))
+            }
+            mstore({{pi_1}}, proof_a_1)
+{{else}}
+            mstore({{pi_0}}, mload({{in_0}}))
+            let proof_a_1 := mload({{in_1}})
+            if or(mload({{in_0}}), proof_a_1) {
+                proof_a_1 := sub(Q, mod(proof_a_1, Q))
+            }
+            mstore({{pi_1}}, proof_a_1)
+            mstore({{pi_2}}, mload({{in_2}}))
+            mstore({{pi_3}}, mload({{in_3}}))
+            mstore({{pi_4}}, mload({{in_4}}))
+            mstore({{pi_5}}, mload({{in_5}}))
+{{/if}}
+
+            // Store alpha_1, beta_2, @ {{pi_6}}
+{{#each MSTORE_ALPHA_BETA}}
+            mstore({{PTR}}, {{WORD}})
+{{/each}}
+
+            // Store vk_x, gamma_2, @ {{pi_7}}
+            mstore({{pi_7}}, mload(VK_X_X_PTR))
+            mstore({{pi_8}}, mload(VK_X_Y_PTR))
+{{#each MSTORE_GAMMA}}
+            mstore({{PTR}}, {{WORD}})
+{{/each}}
+
+            // Store proof_c, delta_2, @ {{pi_10}}
+            mstore({{pi_10}}, mload({{in_6}}))
+            mstore({{pi_11}}, mload({{in_7}}))
+{{#each MSTORE_DELTA}}
+            mstore({{PTR}}, {{WORD}})
+{{/each}}
+
+            if iszero(staticcall({{#if FORWARD_GAS}}gas(){{else}}0x2C308{{/if}}, {{PRECOMPILES.EC_PAIRING}}, {{pi_0}}, 0x300, 0x00, 0x20)) {
+{{#if ERRORS}}
+                // The proof points aren't on their curves
+                mstore(0x00, 0x{{ERRORS.INVALID_PROOF}})
+                revert(0x1c, 0x04)
+{{else}}
+                revert(0x00, 0x00)
+{{/if}}
+            }
+            let is_valid_pair := mload(0x00)
+{{#if ERRORS}}
+            if iszero(is_valid_pair) {
+                mstore(0x00, 0x{{ERRORS.INVALID_PROOF}})
+                revert(0x1c, 0x04)
+            }
+{{/if}}
+{{#if EVENT}}
+
+            // Emit `{{EVENT.SIGNATURE}}` if the proof is valid
+            if is_valid_pair {
+{{#if EVENT.INPUTS_HASH}}
+                // The event's data is the hash of the public inputs
+                mstore(0x00, keccak256(PUB_INPUT_PTR, shl(0x05, input_len)))
+                log2(0x00, 0x20, 0x{{EVENT.TOPIC}}, 0x{{EVENT.VKEY_HASH}})
+{{else}}
+                log2(0x00, 0x00, 0x{{EVENT.TOPIC}}, 0x{{EVENT.VKEY_HASH}})
+{{/if}}
+            }
+{{/if}}
+
+            mstore(0x00, is_valid_pair)
+            return(0x00, 0x20)
+        }
+    }
+}
@@ -0,0 +1,174 @@
+// SPDX-License-Identifier: GPL-3.0
+pragma solidity ^0.8.16;
+
+import "forge-std/Test.sol";
+import "foundry-huff/HuffDeployer.sol";
+
+/// @title Huff Snark Verifier Tests
+/// @notice Checks that the generated verifier accepts a known-good proof and
+///         rejects the same proof with `proof_c` negated.
+{{#if FUZZ}}
+/// @notice Fuzzes each calldata word of the proof, checking that every mutation is
+///         rejected, and checks that the proof is never accepted with other public
+///         inputs with the invariant tests of `VerifierHandler`.
+{{/if}}
+/// @dev This contract is generated by `huffv scaffold-test`.
+contract VerifierTest is Test {
+    /// @notice A `MAIN` macro for the generated verifier, which dispatches
+    ///         `verify(uint256[2],uint256[2][2],uint256[2],uint256[])` to `VERIFY`
+    ///         and `VKEY_HASH()` to `RETURN_VKEY_HASH`.
+    string internal constant WRAPPER =
+        "#define macro MAIN() = {\n"
+        "    0x00 calldataload 0xE0 shr\n"
+        "    dup1 0x2612907c eq verify jumpi\n"
+        "    dup1 0xd07a5df8 eq vkey_hash jumpi\n"
+        "    0x00 dup1 revert\n"
+        "    vkey_hash:\n"
+        "        RETURN_VKEY_HASH()\n"
+        "    verify:\n"
+        "        0x100 0x04 {{PROOF_PTR}} calldatacopy\n"
+        "        0x124 calldatasize sub 0x124 {{PUB_INPUT_LEN_PTR}} calldatacopy\n"
+        "        VERIFY()\n"
+        "        0x00 mstore\n"
+        "        0x20 0x00 return\n"
+        "}\n";
+
+    /// @notice The keccak256 hash of the packed verification key.
+    bytes32 internal constant VKEY_HASH = 0x{{VKEY_HASH}};
+
+    /// @notice The calldata of a valid proof.
+    bytes internal constant VALID_PROOF = hex"{{VALID_PROOF}}";
+
+    /// @notice The calldata of the valid proof with `proof_c` negated.
+    bytes internal constant INVALID_PROOF = hex"{{INVALID_PROOF}}";
+
+{{#if FUZZ}}
+    /// @notice The number of calldata words of a proof that `WRAPPER` passes to the
+    ///         verifier: the proof, the length of the public inputs, and the public
+    ///         inputs, skipping their offset, the 9th word.
+    uint256 internal constant N_VERIFIED_WORDS = {{FUZZ.N_VERIFIED_WORDS}};
+{{#if FUZZ.PUBLIC_INPUTS}}
+
+    /// @notice The calldata of the valid proof up to its public inputs.
+    bytes internal constant PROOF_HEAD = hex"{{FUZZ.PROOF_HEAD}}";
+{{/if}}
+
+{{/if}}
+    /// @notice The generated verifier
+    address public verifier;
+{{#if FUZZ.PUBLIC_INPUTS}}
+
+    /// @notice The target of the invariant tests
+    VerifierHandler public handler;
+{{/if}}
+
+    function setUp() public {
+        verifier = HuffDeployer.deploy_with_code("{{VERIFIER_PATH}}", WRAPPER);
+{{#if FUZZ.PUBLIC_INPUTS}}
+
+        // The public inputs of the valid proof, in calldata order
+        uint256[] memory fixture = new uint256[]({{FUZZ.N_INPUTS}});
+{{#each FUZZ.PUBLIC_INPUTS}}
+        fixture[{{@index}}] = {{this}};
+{{/each}}
+        handler = new VerifierHandler(verifier, PROOF_HEAD, fixture);
+        targetContract(address(handler));
+{{/if}}
+    }
+
+    function testVerifyValidProof() public {
+        (bool success, bytes memory result) = verifier.call(VALID_PROOF);
+        assertTrue(success);
+        assertTrue(abi.decode(result, (bool)));
+    }
+
+    function testRejectInvalidProof() public {
+        (bool success, bytes memory result) = verifier.call(INVALID_PROOF);
+        assertTrue(success);
+        assertFalse(abi.decode(result, (bool)));
+    }
+
+    function testVkeyHash() public {
+        (bool success, bytes memory result) = verifier.call(abi.encodeWithSignature("VKEY_HASH()"));
+        assertTrue(success);
+        assertEq(abi.decode(result, (bytes32)), VKEY_HASH);
+    }
+{{#if FUZZ}}
+
+    function testFuzzRejectMutatedWord(uint256 index, uint256 value) public {
+        // The offset of the public inputs isn't passed to the verifier
+        index = bound(index, 0, N_VERIFIED_WORDS - 1);
+        if (index >= 8) index += 1;
+        uint256 ptr = 0x24 + index * 0x20;
+
+        bytes memory proof = VALID_PROOF;
+        uint256 word;
+        assembly {
+            word := mload(add(proof, ptr))
+        }
+        vm.assume(value != word);
+        assembly {
+            mstore(add(proof, ptr), value)
+        }
+
+        (bool success, bytes memory result) = verifier.call(proof);
+        assertTrue(!success || !abi.decode(result, (bool)));
+    }
+{{#if FUZZ.PUBLIC_INPUTS}}
+
+    function invariantAcceptsOnlyFixtureInputs() public {
+        assertFalse(handler.acceptedOtherInputs());
+    }
+{{/if}}
+{{/if}}
+}
+{{#if FUZZ.PUBLIC_INPUTS}}
+
+/// @title Huff Snark Verifier Handler
+/// @notice The target of the invariant tests of `VerifierTest`: verifies the valid
+///         proof with fuzzed public inputs, and records whether the verifier accepted
+///         any other than the proof's own.
+contract VerifierHandler is StdUtils {
+    /// @notice The generated verifier
+    address internal immutable verifier;
+
+    /// @notice The calldata of the valid proof up to its public inputs.
+    bytes internal proofHead;
+
+    /// @notice The public inputs of the valid proof, in calldata order.
+    uint256[] internal fixture;
+
+    /// @notice Whether the verifier accepted the proof with other public inputs.
+    bool public acceptedOtherInputs;
+
+    constructor(address _verifier, bytes memory _proofHead, uint256[] memory _fixture) {
+        verifier = _verifier;
+        proofHead = _proofHead;
+        fixture = _fixture;
+    }
+
+    /// @notice Verifies the proof with its own public inputs, but `value` at `index`.
+    function verifyWithInput(uint256 index, uint256 value) external {
+        uint256[] memory inputs = fixture;
+        inputs[bound(index, 0, inputs.length - 1)] = value;
+        verify(inputs);
+    }
+
+    /// @notice Verifies the proof with fuzzed public inputs.
+    function verifyWithInputs(uint256[{{FUZZ.N_INPUTS}}] memory fuzzed) external {
+        uint256[] memory inputs = new uint256[](fuzzed.length);
+        for (uint256 i = 0; i < fuzzed.length; i++) {
+            inputs[i] = fuzzed[i];
+        }
+        verify(inputs);
+    }
+
+    function verify(uint256[] memory inputs) internal {
+        (bool success, bytes memory result) = verifier.call(abi.encodePacked(proofHead, inputs));
+        bool accepted = success && abi.decode(result, (bool));
+        if (accepted && keccak256(abi.encodePacked(inputs)) != keccak256(abi.encodePacked(fixture))) {
+            acceptedOtherInputs = true;
+        }
+    }
+}
+{{/if}}
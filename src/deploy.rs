@@ -0,0 +1,154 @@
+use crate::error::{HuffvError, Result};
+use crate::utils::keccak256;
+use crate::vkey::VerificationKey;
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The creation code of an SSTORE2-style data contract, before the two byte size of
+/// its runtime code is filled in: `PUSH2 size DUP1 PUSH1 0x0a RETURNDATASIZE CODECOPY
+/// RETURNDATASIZE RETURN`, which returns the code that follows it as the runtime code.
+pub const DATA_CONTRACT_PREFIX: [u8; 10] =
+    [0x61, 0x00, 0x00, 0x80, 0x60, 0x0a, 0x3d, 0x39, 0x3d, 0xf3];
+
+/// The address of the deterministic deployment proxy that Foundry deploys `CREATE2`
+/// contracts with, `0x4e59b44847b379578588920cA78FbF26c0B4956C`, deployed at that
+/// address on most chains.
+pub const DETERMINISTIC_DEPLOYER: [u8; 20] = [
+    0x4e, 0x59, 0xb4, 0x48, 0x47, 0xb3, 0x79, 0x57, 0x85, 0x88, 0x92, 0x0c, 0xa7, 0x8f, 0xbf, 0x26,
+    0xc0, 0xb4, 0x95, 0x6c,
+];
+
+////////////////////////////////////////////////////////////////
+//                       DATA CONTRACTS                       //
+////////////////////////////////////////////////////////////////
+
+/// The runtime code of an SSTORE2-style data contract holding `data`: a `STOP` byte,
+/// so that calls to the contract return right away, then `data`.
+pub fn data_contract_code(data: &[u8]) -> Vec<u8> {
+    let mut code = Vec::with_capacity(1 + data.len());
+    code.push(0x00);
+    code.extend_from_slice(data);
+    code
+}
+
+/// The creation code of an SSTORE2-style data contract holding `data`, see
+/// [`data_contract_code`].
+pub fn data_contract_initcode(data: &[u8]) -> Vec<u8> {
+    let mut initcode = data_contract_header(data.len());
+    initcode.extend_from_slice(data);
+    initcode
+}
+
+/// The creation code of an SSTORE2-style data contract holding `data_len` bytes of
+/// data, up to the data: the creation code prefix, then the `STOP` byte.
+pub fn data_contract_header(data_len: usize) -> Vec<u8> {
+    let mut header = DATA_CONTRACT_PREFIX.to_vec();
+    header[1..3].copy_from_slice(&((1 + data_len) as u16).to_be_bytes());
+    header.push(0x00);
+    header
+}
+
+/// The creation code of the data contract holding the packed `key`, which a verifier
+/// generated with [`VkeyStorage::External`](crate::codegen::VkeyStorage::External)
+/// reads the key from.
+pub fn vkey_data_initcode(key: &VerificationKey) -> Result<Vec<u8>> {
+    Ok(data_contract_initcode(&packed_bytes(key)?))
+}
+
+/// The code hash of the data contract holding the packed `key`, which a verifier
+/// generated with [`VkeyStorage::External`](crate::codegen::VkeyStorage::External)
+/// checks the data contract it is deployed with against.
+pub fn vkey_data_codehash(key: &VerificationKey) -> Result<[u8; 32]> {
+    Ok(keccak256(&data_contract_code(&packed_bytes(key)?)))
+}
+
+////////////////////////////////////////////////////////////////
+//                          CREATE2                           //
+////////////////////////////////////////////////////////////////
+
+/// The address `factory` deploys `initcode` at with `CREATE2` and `salt`:
+/// `keccak256(0xff ++ factory ++ salt ++ keccak256(initcode))[12:]`.
+pub fn create2_address(factory: &[u8; 20], salt: &[u8; 32], initcode: &[u8]) -> [u8; 20] {
+    let mut preimage = Vec::with_capacity(1 + 20 + 32 + 32);
+    preimage.push(0xff);
+    preimage.extend_from_slice(factory);
+    preimage.extend_from_slice(salt);
+    preimage.extend_from_slice(&keccak256(initcode));
+    keccak256(&preimage)[12..]
+        .try_into()
+        .expect("An address is 20 bytes.")
+}
+
+/// Mines a salt for which `factory` deploys `initcode` at an address starting with the
+/// hex digits of `prefix`, trying salts in order from `start`. Returns the salt and the
+/// address.
+pub fn mine_create2_salt(
+    factory: &[u8; 20],
+    start: &[u8; 32],
+    initcode: &[u8],
+    prefix: &str,
+) -> Result<([u8; 32], [u8; 20])> {
+    let prefix = prefix
+        .strip_prefix("0x")
+        .unwrap_or(prefix)
+        .to_ascii_lowercase();
+    if prefix.len() > 40 || !prefix.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(HuffvError::InvalidOptions(format!(
+            "`{}` is not the hex prefix of an address.",
+            prefix
+        )));
+    }
+
+    // Only the last 8 bytes of the salt are counted up, with the hash of the initcode
+    // computed once
+    let initcode_hash = keccak256(initcode);
+    let mut preimage = [0u8; 1 + 20 + 32 + 32];
+    preimage[0] = 0xff;
+    preimage[1..21].copy_from_slice(factory);
+    preimage[21..53].copy_from_slice(start);
+    preimage[53..].copy_from_slice(&initcode_hash);
+    let first = u64::from_be_bytes(start[24..].try_into().expect("8 bytes"));
+    for i in 0..=u64::MAX {
+        preimage[45..53].copy_from_slice(&first.wrapping_add(i).to_be_bytes());
+        let address = &keccak256(&preimage)[12..];
+        if hex::encode(address).starts_with(&prefix) {
+            let salt = preimage[21..53].try_into().expect("A salt is 32 bytes.");
+            return Ok((salt, address.try_into().expect("An address is 20 bytes.")));
+        }
+    }
+    Err(HuffvError::InvalidOptions(format!(
+        "No salt gives an address starting with `{}`.",
+        prefix
+    )))
+}
+
+/// The EIP-55 checksummed hex of `address`, as Solidity expects address literals.
+pub fn checksum_address(address: &[u8; 20]) -> String {
+    let lower = hex::encode(address);
+    let hash = keccak256(lower.as_bytes());
+    let checksummed = lower
+        .chars()
+        .enumerate()
+        .map(|(i, c)| {
+            let nibble = (hash[i / 2] >> if i % 2 == 0 { 4 } else { 0 }) & 0x0f;
+            if nibble >= 8 {
+                c.to_ascii_uppercase()
+            } else {
+                c
+            }
+        })
+        .collect::<String>();
+    format!("0x{}", checksummed)
+}
+
+////////////////////////////////////////////////////////////////
+//                      HELPER FUNCTIONS                      //
+////////////////////////////////////////////////////////////////
+
+/// The packed `key`, as bytes.
+fn packed_bytes(key: &VerificationKey) -> Result<Vec<u8>> {
+    let packed = key.to_packed()?;
+    Ok(hex::decode(&packed[2..]).expect("Packed verification key is valid hex."))
+}
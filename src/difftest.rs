@@ -0,0 +1,312 @@
+use crate::bench::{decode_hex, verifier_calldata, verifier_transactions};
+use crate::calldata::encode_calldata_with;
+use crate::codegen::{Abi, VerifierOptions};
+use crate::error::{HuffvError, Result};
+use crate::proof::{Proof, PublicSignals};
+use crate::simulate::simulate_initcode;
+use crate::utils::parse_num;
+use crate::vectors::{off_curve, q, Outcome};
+use crate::verify::verify_proof;
+use crate::vkey::VerificationKey;
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use ibig::UBig;
+use serde::Serialize;
+use std::fmt;
+
+////////////////////////////////////////////////////////////////
+//                     DIFFERENTIAL TEST                      //
+////////////////////////////////////////////////////////////////
+
+/// A proof run through both verifiers, and whether each accepted it.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffCase {
+    /// A unique, snake_case name of the case.
+    pub name: String,
+    /// How the proof differs from the valid proof.
+    pub description: String,
+    /// Whether [`verify_proof`] accepts the proof.
+    pub expected: bool,
+    /// What the Huff verifier did with the proof.
+    pub huff: Outcome,
+    /// What the snarkjs Solidity verifier did with the proof.
+    pub solidity: Outcome,
+}
+
+/// DiffCase implementation
+impl DiffCase {
+    /// Whether both verifiers accepted the proof exactly if it is valid. Rejecting and
+    /// reverting are the same: the snarkjs verifier returns false where the Huff
+    /// verifier may revert.
+    pub fn agrees(&self) -> bool {
+        (self.huff == Outcome::Accept) == self.expected
+            && (self.solidity == Outcome::Accept) == self.expected
+    }
+}
+
+/// The cases of a differential test of the Huff verifier against the snarkjs Solidity
+/// verifier, as printed by `huffv difftest`.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffReport {
+    /// The number of public inputs.
+    pub n_inputs: usize,
+    /// The valid proof first, then its mutations.
+    pub cases: Vec<DiffCase>,
+}
+
+/// DiffReport implementation
+impl DiffReport {
+    /// The cases where a verifier disagreed with [`verify_proof`].
+    pub fn disagreements(&self) -> Vec<&DiffCase> {
+        self.cases.iter().filter(|case| !case.agrees()).collect()
+    }
+}
+
+impl fmt::Display for DiffReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Public inputs:       {}", self.n_inputs)?;
+        writeln!(f)?;
+        writeln!(
+            f,
+            "| CASE                           | EXPECTED | HUFF     | SOLIDITY | AGREE |"
+        )?;
+        write!(
+            f,
+            "| ------------------------------ | -------- | -------- | -------- | ----- |"
+        )?;
+        for case in &self.cases {
+            let expected = match case.expected {
+                true => "accept",
+                false => "reject",
+            };
+            write!(
+                f,
+                "\n| {:<30} | {:<8} | {:<8} | {:<8} | {:<5} |",
+                case.name,
+                expected,
+                outcome_name(case.huff),
+                outcome_name(case.solidity),
+                if case.agrees() { "yes" } else { "NO" }
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs the valid `proof` of the `public` signals, and mutations of it, through the Huff
+/// verifier of `key` generated with `options` and through the snarkjs Solidity verifier
+/// deployed with `solidity_initcode`, under revm, with [`verify_proof`] as the reference.
+///
+/// The mutations are proofs the verifiers must reject, each for a different reason:
+///
+/// - `proof_a` negated, on the curve but failing the pairing check
+/// - `proof_a` and `proof_c` swapped
+/// - `proof_b` with the limbs of its coordinates swapped
+/// - `proof_c` off the curve
+/// - `proof_a` with the base field modulus `q` added to its `x` coordinate
+/// - each public input plus 1, and plus the scalar field modulus `r`
+///
+/// The Huff verifier is deployed as `huffv bench` deploys it, and the Solidity verifier
+/// is called with the calldata of `--abi snarkjs`.
+pub fn difftest_verifier(
+    key: &VerificationKey,
+    options: &VerifierOptions,
+    proof: &Proof,
+    public: &PublicSignals,
+    solidity_initcode: &[u8],
+) -> Result<DiffReport> {
+    let (huff_initcode, _) = verifier_transactions(key, options, proof, public)?;
+    let snarkjs = VerifierOptions {
+        abi: Abi::Snarkjs,
+        ..VerifierOptions::default()
+    };
+
+    let mut cases = Vec::new();
+    for (name, description, proof, public) in mutations(proof, public)? {
+        let expected = match verify_proof(key, &proof, &public) {
+            Ok(valid) => valid,
+            Err(HuffvError::InvalidProof(_)) => false,
+            Err(e) => return Err(e),
+        };
+        let huff = simulate_initcode(
+            &huff_initcode,
+            &verifier_calldata(key, options, &proof, &public)?,
+            options.evm_version,
+        )?;
+        let calldata = encode_calldata_with(&proof, &public, &snarkjs)?;
+        let solidity = simulate_initcode(
+            solidity_initcode,
+            &decode_hex(&calldata)?,
+            options.evm_version,
+        )?;
+        cases.push(DiffCase {
+            name,
+            description,
+            expected,
+            huff: huff.outcome,
+            solidity: solidity.outcome,
+        });
+    }
+    Ok(DiffReport {
+        n_inputs: public.0.len(),
+        cases,
+    })
+}
+
+////////////////////////////////////////////////////////////////
+//                      HELPER FUNCTIONS                      //
+////////////////////////////////////////////////////////////////
+
+/// A proof and its public signals, with the name and description of its case.
+type Mutation = (String, String, Proof, PublicSignals);
+
+/// The valid `proof` of the `public` signals, then its mutations, see
+/// [`difftest_verifier`].
+fn mutations(proof: &Proof, public: &PublicSignals) -> Result<Vec<Mutation>> {
+    let q = q();
+    let r = UBig::from_be_bytes(&Fr::MODULUS.to_bytes_be());
+    let mut mutations = vec![(
+        "valid".to_string(),
+        "The valid proof.".to_string(),
+        proof.clone(),
+        public.clone(),
+    )];
+    let mut push = |name: &str, description: &str, proof: Proof, public: PublicSignals| {
+        mutations.push((name.to_string(), description.to_string(), proof, public))
+    };
+
+    let mut negated = proof.clone();
+    negated.pi_a[1] = ((&q - parse_num(&proof.pi_a[1])?) % &q).to_string();
+    push(
+        "proof_a_negated",
+        "proof_a negated, on the curve.",
+        negated,
+        public.clone(),
+    );
+    push(
+        "proof_a_c_swapped",
+        "proof_a and proof_c swapped.",
+        Proof {
+            pi_a: proof.pi_c.clone(),
+            pi_c: proof.pi_a.clone(),
+            ..proof.clone()
+        },
+        public.clone(),
+    );
+    let mut swapped = proof.clone();
+    swapped.pi_b[0].swap(0, 1);
+    swapped.pi_b[1].swap(0, 1);
+    push(
+        "proof_b_swapped_limbs",
+        "proof_b with the limbs of both of its coordinates swapped.",
+        swapped,
+        public.clone(),
+    );
+    push(
+        "proof_c_off_curve",
+        "proof_c with 1 added to its y coordinate, off the curve.",
+        Proof {
+            pi_c: off_curve(&proof.pi_c)?,
+            ..proof.clone()
+        },
+        public.clone(),
+    );
+    let mut aliased = proof.clone();
+    aliased.pi_a[0] = (parse_num(&proof.pi_a[0])? + &q).to_string();
+    push(
+        "proof_a_x_plus_q",
+        "proof_a with the base field modulus q added to its x coordinate.",
+        aliased,
+        public.clone(),
+    );
+
+    for i in 0..public.0.len() {
+        let input = parse_num(&public.0[i])?;
+        let mut incremented = public.clone();
+        incremented.0[i] = ((&input + UBig::from(1u8)) % &r).to_string();
+        push(
+            &format!("public_input_{}_plus_1", i),
+            &format!("public[{}] plus 1.", i),
+            proof.clone(),
+            incremented,
+        );
+        let mut aliased = public.clone();
+        aliased.0[i] = (&input + &r).to_string();
+        push(
+            &format!("public_input_{}_plus_r", i),
+            &format!("public[{}] plus the scalar field modulus r.", i),
+            proof.clone(),
+            aliased,
+        );
+    }
+    Ok(mutations)
+}
+
+/// The name of `outcome`, as the table prints it.
+fn outcome_name(outcome: Outcome) -> &'static str {
+    match outcome {
+        Outcome::Accept => "accept",
+        Outcome::Reject => "reject",
+        Outcome::Revert => "revert",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::synthetic_circuit;
+    use crate::codegen::OnFailure;
+
+    #[test]
+    fn verifiers_with_the_same_abi_agree() {
+        let (key, proof, public) = synthetic_circuit(2);
+        // Without solc, the Huff verifier of `--abi snarkjs` stands in for the snarkjs
+        // verifier, as it takes the same calldata
+        let snarkjs = VerifierOptions {
+            abi: Abi::Snarkjs,
+            ..VerifierOptions::default()
+        };
+        let (solidity_initcode, _) =
+            verifier_transactions(&key, &snarkjs, &proof, &public).unwrap();
+        let options = VerifierOptions {
+            on_failure: OnFailure::Revert,
+            ..VerifierOptions::default()
+        };
+
+        let report =
+            difftest_verifier(&key, &options, &proof, &public, &solidity_initcode).unwrap();
+        assert_eq!(report.cases.len(), 6 + 2 * 2);
+        assert!(report.cases[0].expected);
+        assert!(report.cases[1..].iter().all(|case| !case.expected));
+        assert!(report.disagreements().is_empty(), "{}", report);
+    }
+
+    #[test]
+    fn disagreements_are_reported() {
+        let (key, proof, public) = synthetic_circuit(1);
+        let (other_key, other_proof, other_public) = synthetic_circuit(2);
+        // A verifier of another key rejects even the valid proof
+        let (solidity_initcode, _) = verifier_transactions(
+            &other_key,
+            &VerifierOptions {
+                abi: Abi::Snarkjs,
+                ..VerifierOptions::default()
+            },
+            &other_proof,
+            &other_public,
+        )
+        .unwrap();
+
+        let report = difftest_verifier(
+            &key,
+            &VerifierOptions::default(),
+            &proof,
+            &public,
+            &solidity_initcode,
+        )
+        .unwrap();
+        assert_eq!(report.disagreements()[0].name, "valid");
+    }
+}
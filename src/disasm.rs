@@ -0,0 +1,542 @@
+use crate::assembler::assemble_runtime_tables;
+use crate::codegen::{
+    generate_verifier_with, Emit, MemoryLayout, PairingTerm, Target, VerifierOptions, G1_SIZE,
+    GROTH16_PAIRING_TERMS,
+};
+use crate::error::{HuffvError, Result};
+use crate::vkey::VerificationKey;
+use std::collections::HashMap;
+use std::fmt;
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The opcode of `PUSH1`. `PUSH1` to `PUSH32` are followed by 1 to 32 bytes.
+const PUSH1: u8 = 0x60;
+
+/// The opcode of `DUP1`, up to `DUP16`.
+const DUP1: u8 = 0x80;
+
+/// The opcode of `SWAP1`, up to `SWAP16`.
+const SWAP1: u8 = 0x90;
+
+/// The opcode of `LOG0`, up to `LOG4`.
+const LOG0: u8 = 0xa0;
+
+/// The names of the points of the pairing inputs, G1 then G2 for each pairing term.
+const PAIRING_POINTS: [&str; 8] = [
+    "-proof_a", "proof_b", "alpha_1", "beta_2", "vk_x", "gamma_2", "proof_c", "delta_2",
+];
+
+/// The names of the proof words in memory, as the `VERIFY` macro reads them.
+const PROOF_WORDS: [&str; 8] = [
+    "a_0", "a_1", "b_0_0", "b_0_1", "b_1_0", "b_1_1", "c_0", "c_1",
+];
+
+////////////////////////////////////////////////////////////////
+//                        DISASSEMBLER                        //
+////////////////////////////////////////////////////////////////
+
+/// An instruction of EVM bytecode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    /// The code offset of the instruction.
+    pub offset: usize,
+    /// The opcode of the instruction.
+    pub opcode: u8,
+    /// The bytes pushed by `PUSH1` to `PUSH32`, empty for any other instruction. Shorter
+    /// than the push if the code ends first.
+    pub immediate: Vec<u8>,
+}
+
+/// Instruction implementation
+impl Instruction {
+    /// The mnemonic of the instruction, e.g. `PUSH2` or `STATICCALL`.
+    pub fn mnemonic(&self) -> String {
+        match self.opcode {
+            op @ PUSH1..=0x7f => format!("PUSH{}", op - PUSH1 + 1),
+            op @ DUP1..=0x8f => format!("DUP{}", op - DUP1 + 1),
+            op @ SWAP1..=0x9f => format!("SWAP{}", op - SWAP1 + 1),
+            op @ LOG0..=0xa4 => format!("LOG{}", op - LOG0),
+            op => match stack_effect(op) {
+                Some((name, _, _)) => name.to_string(),
+                None => format!("UNKNOWN_0x{:02x}", op),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.opcode {
+            PUSH1..=0x7f => write!(f, "{} 0x{}", self.mnemonic(), hex::encode(&self.immediate)),
+            _ => write!(f, "{}", self.mnemonic()),
+        }
+    }
+}
+
+/// Disassembles EVM `code` into its instructions, in order.
+pub fn disassemble(code: &[u8]) -> Vec<Instruction> {
+    let mut instructions = Vec::new();
+    let mut offset = 0;
+    while offset < code.len() {
+        let opcode = code[offset];
+        let size = match opcode {
+            PUSH1..=0x7f => (opcode - PUSH1 + 1) as usize,
+            _ => 0,
+        };
+        let end = (offset + 1 + size).min(code.len());
+        instructions.push(Instruction {
+            offset,
+            opcode,
+            immediate: code[offset + 1..end].to_vec(),
+        });
+        offset += 1 + size;
+    }
+    instructions
+}
+
+/// Disassembles the runtime code of the Huff verifier of `key` generated with `options`,
+/// annotated for review, as `huffv generate --disasm-out` writes it.
+///
+/// The runtime code is assembled as `--target bytecode` assembles it. Every `MLOAD`,
+/// `MSTORE`, and copy into memory whose offset is pushed in the same block is annotated
+/// with the region of the memory layout it reads or writes, e.g. `pairing input vk_x`,
+/// and every precompile call with the precompile and its input and output. The tables
+/// of the runtime code follow its instructions as data.
+pub fn annotated_disassembly(key: &VerificationKey, options: &VerifierOptions) -> Result<String> {
+    let problem = if options.emit != Emit::Contract {
+        "The disassembly is of the verifier contract, so it needs `--emit contract`."
+    } else if !matches!(options.target, Target::Huff | Target::Bytecode) {
+        "The disassembly is of the Huff verifier, so it needs `--target huff`."
+    } else {
+        ""
+    };
+    if !problem.is_empty() {
+        return Err(HuffvError::InvalidOptions(problem.to_string()));
+    }
+    let contract = generate_verifier_with(
+        key,
+        &VerifierOptions {
+            target: Target::Huff,
+            main: true,
+            fingerprint: false,
+            ..options.clone()
+        },
+    )?;
+    let (code, tables) = assemble_runtime_tables(&contract, options.evm_version)?;
+    let code_end = tables.first().map_or(code.len(), |(_, offset)| *offset);
+    let layout = MemoryLayout::new(key.ic.len());
+    let labels = Labels::new(&layout, options);
+
+    let precompiles = labels
+        .precompiles
+        .iter()
+        .map(|(name, address)| format!("{} 0x{}", name, hex::encode(address)))
+        .collect::<Vec<_>>();
+    let mut lines = vec![
+        format!(
+            "; Runtime code of the verifier, 0x{:x} bytes, assembled for {}",
+            code.len(),
+            options.evm_version.name()
+        ),
+        format!(
+            "; Memory: ICs at 0x{:x}, pairing inputs at 0x{:x}, proof at 0x{:x}, public inputs at 0x{:x}",
+            MemoryLayout::IC_PTR,
+            layout.pairing_input_ptr(),
+            layout.input_ptr(),
+            layout.pub_input_ptr()
+        ),
+        format!("; Precompiles: {}", precompiles.join(", ")),
+    ];
+
+    let mut stack = Stack::default();
+    for instruction in disassemble(&code[..code_end]) {
+        let line = format!("0x{:04x}  {}", instruction.offset, instruction);
+        lines.push(match labels.annotate(&instruction, &stack, &tables) {
+            Some(note) => format!("{:<48}; {}", line, note),
+            None => line,
+        });
+        stack.execute(&instruction);
+    }
+
+    for (i, (name, offset)) in tables.iter().enumerate() {
+        let end = tables.get(i + 1).map_or(code.len(), |(_, next)| *next);
+        lines.push(String::new());
+        lines.push(format!("; Table {}, 0x{:x} bytes", name, end - offset));
+        for start in (*offset..end).step_by(0x20) {
+            let data = &code[start..(start + 0x20).min(end)];
+            lines.push(format!("0x{:04x}  0x{}", start, hex::encode(data)));
+        }
+    }
+    Ok(lines.join("\n"))
+}
+
+////////////////////////////////////////////////////////////////
+//                        ANNOTATIONS                         //
+////////////////////////////////////////////////////////////////
+
+/// The stack of a basic block, with the values pushed by the block itself. Any other
+/// value is unknown.
+#[derive(Default)]
+struct Stack(Vec<Option<Vec<u8>>>);
+
+/// Stack implementation
+impl Stack {
+    /// The `n`th value from the top of the stack, as a big-endian word without leading
+    /// zeros, if known.
+    fn peek(&self, n: usize) -> Option<&[u8]> {
+        let index = self.0.len().checked_sub(n + 1)?;
+        self.0[index].as_deref()
+    }
+
+    /// The `n`th value from the top of the stack as an offset, if known.
+    fn offset(&self, n: usize) -> Option<usize> {
+        let value = self.peek(n)?;
+        (value.len() <= 8).then(|| value.iter().fold(0, |acc, byte| acc << 8 | *byte as usize))
+    }
+
+    /// Applies `instruction` to the stack. Jump destinations start a new block, and
+    /// only pushes, `DUP`s, and `SWAP`s keep their values known.
+    fn execute(&mut self, instruction: &Instruction) {
+        let len = self.0.len();
+        match instruction.opcode {
+            0x5f => self.0.push(Some(Vec::new())),
+            PUSH1..=0x7f => {
+                let start = instruction
+                    .immediate
+                    .iter()
+                    .take_while(|b| **b == 0)
+                    .count();
+                self.0.push(Some(instruction.immediate[start..].to_vec()));
+            }
+            op @ DUP1..=0x8f => {
+                let n = (op - DUP1) as usize;
+                self.0.push(self.peek(n).map(<[u8]>::to_vec));
+            }
+            op @ SWAP1..=0x9f => {
+                let n = (op - SWAP1 + 1) as usize;
+                match len.checked_sub(n + 1) {
+                    Some(index) => self.0.swap(index, len - 1),
+                    // Swapped with a value from before the block
+                    None => self.0.clear(),
+                }
+            }
+            // JUMPDEST, and the instructions that end a block
+            0x5b | 0x00 | 0x56 | 0xf3 | 0xfd | 0xfe | 0xff => self.0.clear(),
+            op => {
+                let (inputs, outputs) = match op {
+                    LOG0..=0xa4 => ((op - LOG0) as usize + 2, 0),
+                    op => stack_effect(op).map_or((len, 0), |(_, i, o)| (i, o)),
+                };
+                self.0.truncate(len.saturating_sub(inputs));
+                self.0.extend((0..outputs).map(|_| None));
+            }
+        }
+    }
+}
+
+/// The names of the memory regions and precompiles of a verifier.
+struct Labels {
+    /// The name of every word of the memory layout, by its offset.
+    memory: HashMap<usize, String>,
+    /// The name and address of every precompile, as big-endian bytes without leading zeros.
+    precompiles: Vec<(&'static str, Vec<u8>)>,
+}
+
+/// Labels implementation
+impl Labels {
+    /// The labels of the verifier with memory `layout` generated with `options`.
+    fn new(layout: &MemoryLayout, options: &VerifierOptions) -> Self {
+        let mut memory = HashMap::new();
+        let mut point = |offset: usize, name: &str, size: usize| {
+            for word in (0..size).step_by(0x20) {
+                let label = match word {
+                    0 => name.to_string(),
+                    word => format!("{}+0x{:x}", name, word),
+                };
+                memory.insert(offset + word, label);
+            }
+        };
+        point(0x80, "vk_x", G1_SIZE);
+        for i in 0..layout.n_ics {
+            point(
+                MemoryLayout::IC_PTR + i * G1_SIZE,
+                &format!("IC[{}]", i),
+                G1_SIZE,
+            );
+        }
+        let mut offset = layout.pairing_input_ptr();
+        for i in 0..GROTH16_PAIRING_TERMS.len() {
+            point(
+                offset,
+                &format!("pairing input {}", PAIRING_POINTS[2 * i]),
+                G1_SIZE,
+            );
+            point(
+                offset + G1_SIZE,
+                &format!("pairing input {}", PAIRING_POINTS[2 * i + 1]),
+                PairingTerm::SIZE - G1_SIZE,
+            );
+            offset += PairingTerm::SIZE;
+        }
+        for (i, word) in PROOF_WORDS.iter().enumerate() {
+            memory.insert(layout.input_ptr() + i * 0x20, format!("proof.{}", word));
+        }
+        memory.insert(layout.pub_input_len_ptr(), "n_inputs".to_string());
+        for i in 0..layout.n_ics.saturating_sub(1) {
+            memory.insert(layout.pub_input_ptr() + i * 0x20, format!("input[{}]", i));
+        }
+
+        let addresses = &options.precompiles;
+        let precompiles = [
+            ("identity", &addresses.identity),
+            ("modexp", &addresses.modexp),
+            ("ecAdd", &addresses.ec_add),
+            ("ecMul", &addresses.ec_mul),
+            ("ecPairing", &addresses.ec_pairing),
+        ]
+        .into_iter()
+        .map(|(name, address)| {
+            // Normalized to whole bytes without a leading zero byte
+            let bytes = hex::decode(address.trim_start_matches("0x")).unwrap_or_default();
+            let start = bytes.iter().take_while(|b| **b == 0).count();
+            (name, bytes[start..].to_vec())
+        })
+        .collect();
+        Self {
+            memory,
+            precompiles,
+        }
+    }
+
+    /// The memory region at the `n`th value from the top of the `stack`, or the value
+    /// in hex if it isn't one, or `?` if it is unknown.
+    fn region(&self, stack: &Stack, n: usize) -> String {
+        match stack.offset(n) {
+            Some(offset) => match self.memory.get(&offset) {
+                Some(label) => label.clone(),
+                None => format!("0x{:x}", offset),
+            },
+            None => "?".to_string(),
+        }
+    }
+
+    /// The `n`th value from the top of the `stack` as a size in hex, or `?` if unknown.
+    fn size(stack: &Stack, n: usize) -> String {
+        stack
+            .offset(n)
+            .map_or("?".to_string(), |size| format!("0x{:x}", size))
+    }
+
+    /// The annotation of `instruction`, run with `stack`, if any. `tables` are the names
+    /// and code offsets of the tables of the code.
+    fn annotate(
+        &self,
+        instruction: &Instruction,
+        stack: &Stack,
+        tables: &[(&str, usize)],
+    ) -> Option<String> {
+        let labeled = |n: usize| {
+            stack
+                .offset(n)
+                .and_then(|offset| self.memory.get(&offset))
+                .cloned()
+        };
+        match instruction.opcode {
+            // MLOAD, MSTORE
+            0x51 | 0x52 => labeled(0),
+            // CODECOPY
+            0x39 => {
+                let source = stack.offset(1).map(|offset| {
+                    match tables.iter().rev().find(|(_, start)| *start <= offset) {
+                        Some((table, start)) if offset > *start => {
+                            format!("{}+0x{:x}", table, offset - start)
+                        }
+                        Some((table, _)) => table.to_string(),
+                        None => format!("code 0x{:x}", offset),
+                    }
+                });
+                match (labeled(0), source) {
+                    (None, None) => None,
+                    (dest, source) => Some(format!(
+                        "{} <- {}",
+                        dest.unwrap_or_else(|| self.region(stack, 0)),
+                        source.unwrap_or_else(|| "?".to_string())
+                    )),
+                }
+            }
+            // CALLDATACOPY, MCOPY
+            0x37 | 0x5e => {
+                let source = match instruction.opcode {
+                    0x37 => format!("calldata {}", Self::size(stack, 1)),
+                    _ => self.region(stack, 1),
+                };
+                labeled(0).map(|dest| format!("{} <- {}", dest, source))
+            }
+            // STATICCALL, CALL
+            0xfa | 0xf1 => {
+                let address = stack.peek(1)?;
+                let (name, _) = self
+                    .precompiles
+                    .iter()
+                    .find(|(_, precompile)| precompile == address)?;
+                // CALL has a value between the address and the input
+                let args = if instruction.opcode == 0xf1 { 3 } else { 2 };
+                Some(format!(
+                    "{} (0x{}): input {} bytes at {}, output {} bytes at {}",
+                    name,
+                    hex::encode(address),
+                    Self::size(stack, args + 1),
+                    self.region(stack, args),
+                    Self::size(stack, args + 3),
+                    self.region(stack, args + 2)
+                ))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The mnemonic, and the number of stack inputs and outputs, of an `opcode` other than
+/// `PUSH`, `DUP`, `SWAP`, and `LOG`.
+fn stack_effect(opcode: u8) -> Option<(&'static str, usize, usize)> {
+    Some(match opcode {
+        0x00 => ("STOP", 0, 0),
+        0x01 => ("ADD", 2, 1),
+        0x02 => ("MUL", 2, 1),
+        0x03 => ("SUB", 2, 1),
+        0x04 => ("DIV", 2, 1),
+        0x05 => ("SDIV", 2, 1),
+        0x06 => ("MOD", 2, 1),
+        0x07 => ("SMOD", 2, 1),
+        0x08 => ("ADDMOD", 3, 1),
+        0x09 => ("MULMOD", 3, 1),
+        0x0a => ("EXP", 2, 1),
+        0x0b => ("SIGNEXTEND", 2, 1),
+        0x10 => ("LT", 2, 1),
+        0x11 => ("GT", 2, 1),
+        0x12 => ("SLT", 2, 1),
+        0x13 => ("SGT", 2, 1),
+        0x14 => ("EQ", 2, 1),
+        0x15 => ("ISZERO", 1, 1),
+        0x16 => ("AND", 2, 1),
+        0x17 => ("OR", 2, 1),
+        0x18 => ("XOR", 2, 1),
+        0x19 => ("NOT", 1, 1),
+        0x1a => ("BYTE", 2, 1),
+        0x1b => ("SHL", 2, 1),
+        0x1c => ("SHR", 2, 1),
+        0x1d => ("SAR", 2, 1),
+        0x20 => ("KECCAK256", 2, 1),
+        0x30 => ("ADDRESS", 0, 1),
+        0x31 => ("BALANCE", 1, 1),
+        0x32 => ("ORIGIN", 0, 1),
+        0x33 => ("CALLER", 0, 1),
+        0x34 => ("CALLVALUE", 0, 1),
+        0x35 => ("CALLDATALOAD", 1, 1),
+        0x36 => ("CALLDATASIZE", 0, 1),
+        0x37 => ("CALLDATACOPY", 3, 0),
+        0x38 => ("CODESIZE", 0, 1),
+        0x39 => ("CODECOPY", 3, 0),
+        0x3a => ("GASPRICE", 0, 1),
+        0x3b => ("EXTCODESIZE", 1, 1),
+        0x3c => ("EXTCODECOPY", 4, 0),
+        0x3d => ("RETURNDATASIZE", 0, 1),
+        0x3e => ("RETURNDATACOPY", 3, 0),
+        0x3f => ("EXTCODEHASH", 1, 1),
+        0x40 => ("BLOCKHASH", 1, 1),
+        0x41 => ("COINBASE", 0, 1),
+        0x42 => ("TIMESTAMP", 0, 1),
+        0x43 => ("NUMBER", 0, 1),
+        0x44 => ("PREVRANDAO", 0, 1),
+        0x45 => ("GASLIMIT", 0, 1),
+        0x46 => ("CHAINID", 0, 1),
+        0x47 => ("SELFBALANCE", 0, 1),
+        0x48 => ("BASEFEE", 0, 1),
+        0x49 => ("BLOBHASH", 1, 1),
+        0x4a => ("BLOBBASEFEE", 0, 1),
+        0x50 => ("POP", 1, 0),
+        0x51 => ("MLOAD", 1, 1),
+        0x52 => ("MSTORE", 2, 0),
+        0x53 => ("MSTORE8", 2, 0),
+        0x54 => ("SLOAD", 1, 1),
+        0x55 => ("SSTORE", 2, 0),
+        0x56 => ("JUMP", 1, 0),
+        0x57 => ("JUMPI", 2, 0),
+        0x58 => ("PC", 0, 1),
+        0x59 => ("MSIZE", 0, 1),
+        0x5a => ("GAS", 0, 1),
+        0x5b => ("JUMPDEST", 0, 0),
+        0x5c => ("TLOAD", 1, 1),
+        0x5d => ("TSTORE", 2, 0),
+        0x5e => ("MCOPY", 3, 0),
+        0x5f => ("PUSH0", 0, 1),
+        0xf0 => ("CREATE", 3, 1),
+        0xf1 => ("CALL", 7, 1),
+        0xf2 => ("CALLCODE", 7, 1),
+        0xf3 => ("RETURN", 2, 0),
+        0xf4 => ("DELEGATECALL", 6, 1),
+        0xf5 => ("CREATE2", 4, 1),
+        0xfa => ("STATICCALL", 6, 1),
+        0xfd => ("REVERT", 2, 0),
+        0xfe => ("INVALID", 0, 0),
+        0xff => ("SELFDESTRUCT", 1, 0),
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::synthetic_circuit;
+    use crate::codegen::PrecompileAddresses;
+
+    #[test]
+    fn instructions_are_split_at_push_data() {
+        let instructions = disassemble(&[0x61, 0x03, 0x40, 0x5f, 0x52, 0x7f, 0x01]);
+        let listed = instructions
+            .iter()
+            .map(|i| format!("{}:{}", i.offset, i))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            listed,
+            ["0:PUSH2 0x0340", "3:PUSH0", "4:MSTORE", "5:PUSH32 0x01"]
+        );
+    }
+
+    #[test]
+    fn disassembly_lists_the_pairing_staticcall_to_the_configured_precompile() {
+        let (key, _, _) = synthetic_circuit(2);
+        let options = VerifierOptions {
+            precompiles: "ec-pairing=0x0108".parse::<PrecompileAddresses>().unwrap(),
+            ..VerifierOptions::default()
+        };
+        let layout = MemoryLayout::new(key.ic.len());
+
+        let disassembly = annotated_disassembly(&key, &options).unwrap();
+        let call = disassembly
+            .lines()
+            .find(|line| line.contains("; ecPairing"))
+            .unwrap_or_else(|| panic!("No pairing call in:\n{}", disassembly));
+        assert!(call.contains("STATICCALL"), "{}", call);
+        assert!(
+            call.ends_with(
+                "; ecPairing (0x0108): input 0x300 bytes at pairing input -proof_a, output 0x20 bytes at 0x0"
+            ),
+            "{}",
+            call
+        );
+        assert!(!disassembly.contains("(0x08)"));
+        // The pairing inputs are written where the layout puts them
+        assert!(disassembly.contains(&format!(
+            "pairing inputs at 0x{:x}",
+            layout.pairing_input_ptr()
+        )));
+        assert!(disassembly
+            .lines()
+            .any(|line| line.contains("MSTORE") && line.ends_with("; pairing input proof_c")));
+        assert!(disassembly.contains("; Table VKEY"));
+    }
+}
@@ -0,0 +1,87 @@
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// A `huffv` result
+pub type Result<T> = std::result::Result<T, HuffvError>;
+
+/// The errors that can occur while parsing a verification key or generating a verifier.
+#[derive(Error, Debug)]
+pub enum HuffvError {
+    /// A file could not be read or written.
+    #[error("Error accessing {}: {source}", path.display())]
+    Io {
+        /// The path of the file.
+        path: PathBuf,
+        /// The underlying IO error.
+        source: io::Error,
+    },
+
+    /// The verification key JSON could not be deserialized.
+    #[error("Error while deserializing verification key JSON: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// A number in the verification key could not be parsed.
+    #[error("Failed to parse number {0:?} in verification key.")]
+    NumberParse(String),
+
+    /// A binary file, e.g. a snarkjs `.zkey`, could not be read.
+    #[error("Invalid {kind} file: {message}")]
+    BinaryFile {
+        /// The file format.
+        kind: &'static str,
+        /// What is wrong with the file.
+        message: String,
+    },
+
+    /// The verification key is for a protocol or curve that `huffv` does not support.
+    #[error("Unsupported verification key: {0}")]
+    UnsupportedKey(String),
+
+    /// The verification key is well-formed JSON but not a usable key.
+    #[error("Invalid verification key: {0}")]
+    InvalidKey(String),
+
+    /// The proof is well-formed JSON but not a usable proof.
+    #[error("Invalid proof: {0}")]
+    InvalidProof(String),
+
+    /// The options can't generate a working verifier.
+    #[error("Invalid options: {0}")]
+    InvalidOptions(String),
+
+    /// The template could not be filled.
+    #[error("Template error: {0}")]
+    Template(String),
+
+    /// The fingerprint trailer of a verifier could not be read.
+    #[error("Invalid fingerprint trailer: {0}")]
+    Fingerprint(String),
+
+    /// A Huff contract could not be assembled, see [`crate::assembler`].
+    #[error("Failed to assemble the Huff contract: {0}")]
+    Assembly(String),
+
+    /// The verifier failed to deploy or to verify a proof under revm, see
+    /// [`crate::bench::bench_verifier`].
+    #[error("Failed to run the verifier: {0}")]
+    Execution(String),
+
+    /// A JSON-RPC request to a node failed, see [`crate::rpc::deploy_verifier`].
+    #[error("JSON-RPC request failed: {0}")]
+    Rpc(String),
+
+    /// The rendered contract failed its checks, see [`crate::check::check_contract`].
+    #[error("The generated contract is broken:\n{}", .0.join("\n"))]
+    Check(Vec<String>),
+}
+
+impl HuffvError {
+    /// Wraps an IO error that occurred while accessing `path`.
+    pub fn io(path: impl Into<PathBuf>, source: io::Error) -> Self {
+        HuffvError::Io {
+            path: path.into(),
+            source,
+        }
+    }
+}
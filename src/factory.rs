@@ -0,0 +1,211 @@
+use crate::assembler::assemble_creation_code;
+use crate::calldata::encode_packed_vkey_call;
+use crate::codegen::{Emit, VerifierOptions, VkeyStorage, INITIALIZE_SIGNATURE};
+use crate::error::{HuffvError, Result};
+use crate::size::VKEY_POINTS_SIZE;
+use crate::utils::{keccak256, render_template, signature_selector};
+use crate::vkey::VerificationKey;
+use serde::Serialize;
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The clone factory template contract
+pub static FACTORY_CONTRACT: &str = include_str!("contracts/FactoryTemplate.huff");
+
+/// The signature of the factory's function deploying a clone for a packed key
+pub static DEPLOY_CLONE_SIGNATURE: &str = "deployClone(bytes)";
+
+/// The signature of the getter of the factory's implementation
+pub static IMPLEMENTATION_SIGNATURE: &str = "implementation()";
+
+/// The signature of the event emitted when a clone is deployed, with the clone and the
+/// hash of its key as indexed topics
+pub static CLONE_DEPLOYED_EVENT_SIGNATURE: &str = "CloneDeployed(address,bytes32)";
+
+/// The EIP-1167 proxy code before the implementation address
+pub const CLONE_PROXY_PREFIX: [u8; 10] =
+    [0x36, 0x3d, 0x3d, 0x37, 0x3d, 0x3d, 0x3d, 0x36, 0x3d, 0x73];
+
+/// The EIP-1167 proxy code after the implementation address
+pub const CLONE_PROXY_SUFFIX: [u8; 15] = [
+    0x5a, 0xf4, 0x3d, 0x82, 0x80, 0x3e, 0x90, 0x3d, 0x91, 0x60, 0x2b, 0x57, 0xfd, 0x5b, 0xf3,
+];
+
+/// The byte size of the EIP-1167 proxy code
+const CLONE_PROXY_SIZE: usize = 45;
+
+////////////////////////////////////////////////////////////////
+//                          FACTORY                           //
+////////////////////////////////////////////////////////////////
+
+/// Generates a Huff factory deploying EIP-1167 clones of the verifier generated for
+/// `key` with `options`, one per circuit with the same number of ICs. The factory is
+/// deployed with the address of such a verifier as its implementation, and deploys a
+/// clone for a packed key with the calldata of [`encode_deploy_clone_calldata`].
+///
+/// Only verifiers that don't fix their key in their code can be cloned:
+/// - With `--vkey-storage constructor`, the key is appended to the code of the clone,
+///   and the verifier reads it from the code at `address()`.
+/// - With `--vkey-storage storage`, the clone is initialized with the key and owned by
+///   its deployer, see [`INITIALIZE_SIGNATURE`].
+///
+/// With `--emit initcode`, the factory's creation code is returned as `0x` prefixed hex.
+pub fn generate_factory(key: &VerificationKey, options: &VerifierOptions) -> Result<String> {
+    let storage = match options.vkey_storage {
+        VkeyStorage::Constructor => false,
+        VkeyStorage::Storage => true,
+        _ => {
+            return Err(HuffvError::InvalidOptions(
+                "Only verifiers with `--vkey-storage constructor` or `--vkey-storage storage` can be cloned by a factory.".to_string(),
+            ))
+        }
+    };
+
+    let vkey_bytes = VKEY_POINTS_SIZE + key.ic.len() * 0x40;
+    // The clone's creation code returns the rest of itself: the proxy, then the key if
+    // it is appended to the clone's code
+    let clone_size = CLONE_PROXY_SIZE + if storage { 0 } else { vkey_bytes };
+    let mut header = match storage {
+        true => vec![0x3d, 0x60, CLONE_PROXY_SIZE as u8, 0x80],
+        false => vec![0x61, (clone_size >> 8) as u8, clone_size as u8, 0x80],
+    };
+    header.extend([0x60, 0x0a, 0x3d, 0x39]);
+    header.extend(match storage {
+        true => [0x81, 0xf3],
+        false => [0x3d, 0xf3],
+    });
+    let initcode_prefix_size = header.len();
+    header.extend(CLONE_PROXY_PREFIX);
+
+    let context = FactoryContext {
+        deploy_clone_selector: hex::encode(signature_selector(DEPLOY_CLONE_SIGNATURE)),
+        implementation_selector: hex::encode(signature_selector(IMPLEMENTATION_SIGNATURE)),
+        initialize_selector: hex::encode(signature_selector(INITIALIZE_SIGNATURE)),
+        clone_deployed_topic: hex::encode(keccak256(CLONE_DEPLOYED_EVENT_SIGNATURE.as_bytes())),
+        n_ics: format!("0x{:02x}", key.ic.len()),
+        vkey_bytes: format!("0x{:02x}", vkey_bytes),
+        clone_header: hex::encode(header),
+        clone_footer: hex::encode(CLONE_PROXY_SUFFIX),
+        clone_initcode_bytes: format!("0x{:02x}", initcode_prefix_size + clone_size),
+        vkey_ptr: match storage {
+            true => "0xC0".to_string(),
+            false => format!("0x{:02x}", initcode_prefix_size + CLONE_PROXY_SIZE),
+        },
+        storage,
+    };
+    let factory = render_template(FACTORY_CONTRACT, &context)?;
+    if options.emit == Emit::Initcode {
+        let initcode = assemble_creation_code(&factory, options.evm_version)?;
+        return Ok(format!("0x{}", hex::encode(initcode)));
+    }
+    Ok(factory)
+}
+
+/// ABI-encodes a call to `deployClone(bytes)` of the factory, deploying a clone of its
+/// implementation for `key`.
+pub fn encode_deploy_clone_calldata(key: &VerificationKey) -> Result<String> {
+    encode_packed_vkey_call(DEPLOY_CLONE_SIGNATURE, key)
+}
+
+/// The values the factory template is rendered with. Each selector, topic, and code
+/// fragment is hex without a `0x` prefix.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct FactoryContext {
+    /// `deployClone(bytes)`
+    deploy_clone_selector: String,
+    /// `implementation()`
+    implementation_selector: String,
+    /// The clones' `initialize(address,bytes)`
+    initialize_selector: String,
+    /// The topic of `CloneDeployed(address,bytes32)`.
+    clone_deployed_topic: String,
+    /// The number of ICs in the keys, as a hex literal.
+    n_ics: String,
+    /// The byte size of the packed keys, as a hex literal.
+    vkey_bytes: String,
+    /// The creation code of a clone, up to the implementation address.
+    clone_header: String,
+    /// The EIP-1167 proxy after the implementation address.
+    clone_footer: String,
+    /// The byte size of the creation code of a clone, as a hex literal.
+    clone_initcode_bytes: String,
+    /// The location of the packed key in memory, as a hex literal.
+    vkey_ptr: String,
+    /// Whether clones are initialized with the key, rather than have it appended.
+    storage: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::{
+        decode_hex, deploy, synthetic_circuit, transact, transact_from, BENCH_CALLER,
+    };
+    use crate::calldata::encode_calldata_with;
+    use crate::codegen::generate_verifier_with;
+    use crate::utils::encode_num;
+    use revm::primitives::{address, Address, TxKind};
+
+    #[test]
+    fn clones_are_initialized_once_by_their_factory() {
+        let (key, proof, public) = synthetic_circuit(2);
+        let mut other = key.clone();
+        other.vk_alpha_1 = other.ic[0].clone();
+        let options = VerifierOptions {
+            vkey_storage: VkeyStorage::Storage,
+            emit: Emit::Initcode,
+            ..VerifierOptions::default()
+        };
+        let evm_version = options.evm_version;
+
+        // The implementation has another key, and the factory its address
+        let implementation = generate_verifier_with(&other, &options).unwrap();
+        let implementation = implementation + &other.to_packed().unwrap()[2..];
+        let (db, implementation, _, _) =
+            deploy(&decode_hex(&implementation).unwrap(), evm_version).unwrap();
+        let factory = generate_factory(&key, &options).unwrap();
+        let factory = format!("{}{:0>64}", factory, hex::encode(implementation));
+        let (result, db) = transact(
+            db,
+            evm_version,
+            TxKind::Create,
+            decode_hex(&factory).unwrap(),
+        )
+        .unwrap();
+        assert!(result.is_success());
+        let factory = BENCH_CALLER.create(1);
+
+        let owner = address!("2000000000000000000000000000000000000002");
+        let call = |db, to, calldata: &str| {
+            let calldata = decode_hex(calldata).unwrap();
+            transact_from(db, evm_version, owner, TxKind::Call(to), calldata).unwrap()
+        };
+        let (result, db) = call(db, factory, &encode_deploy_clone_calldata(&key).unwrap());
+        let clone = Address::from_slice(&result.into_output().unwrap()[12..]);
+
+        // The clone verifies proofs against the key it was initialized with
+        let verify = encode_calldata_with(&proof, &public, &options).unwrap();
+        let (result, db) = call(db, clone, &verify);
+        assert_eq!(result.into_output().unwrap()[31], 1);
+
+        // Neither the clone nor the implementation can be initialized again
+        let packed = &other.to_packed().unwrap()[2..];
+        let initialize = format!(
+            "0x{}{:0>64}{}{}{}",
+            hex::encode(signature_selector(INITIALIZE_SIGNATURE)),
+            hex::encode(owner),
+            encode_num("64").unwrap(),
+            encode_num(&(packed.len() / 2).to_string()).unwrap(),
+            packed
+        );
+        let (result, db) = call(db, clone, &initialize);
+        assert!(!result.is_success());
+        let (result, db) = call(db, implementation, &initialize);
+        assert!(!result.is_success());
+        let (result, _) = call(db, clone, &verify);
+        assert_eq!(result.into_output().unwrap()[31], 1);
+    }
+}
@@ -0,0 +1,119 @@
+//! A C-compatible FFI surface, for embedding the generator in other languages
+//! instead of spawning `huffv`. Enabled with the `ffi` feature; the declarations
+//! are in `include/huffv.h`.
+//!
+//! Every function returns `0` on success and writes its result to `out`. On
+//! failure, it returns `1` and writes the error message to `out` instead. Either
+//! way, the string written to `out` must be released with [`huffv_free`].
+
+use crate::calldata::encode_calldata;
+use crate::codegen::{generate_verifier, Abi, PublicInputOrder};
+use crate::error::HuffvError;
+use crate::proof::{Proof, PublicSignals};
+use crate::vkey::VerificationKey;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+
+/// Generates a Huff verifier contract from a snarkjs verification key JSON string.
+///
+/// # Safety
+///
+/// `vkey_json` must be a valid, NUL-terminated C string, and `out` must be a valid
+/// pointer to write a string pointer to.
+#[no_mangle]
+pub unsafe extern "C" fn huffv_generate(vkey_json: *const c_char, out: *mut *mut c_char) -> c_int {
+    write_result(
+        read_str(vkey_json)
+            .and_then(|json| Ok(generate_verifier(&VerificationKey::from_json(json)?)?)),
+        out,
+    )
+}
+
+/// Packs a snarkjs verification key JSON string as the verifier's `VKEY` table.
+///
+/// # Safety
+///
+/// See [`huffv_generate`].
+#[no_mangle]
+pub unsafe extern "C" fn huffv_pack(vkey_json: *const c_char, out: *mut *mut c_char) -> c_int {
+    write_result(
+        read_str(vkey_json).and_then(|json| Ok(VerificationKey::from_json(json)?.to_packed()?)),
+        out,
+    )
+}
+
+/// Encodes the calldata for verifying a snarkjs proof with the generated verifier.
+/// Public inputs are passed in reverse if `reverse_inputs` is non-zero.
+///
+/// # Safety
+///
+/// `proof_json` and `public_json` must be valid, NUL-terminated C strings, and
+/// `out` must be a valid pointer to write a string pointer to.
+#[no_mangle]
+pub unsafe extern "C" fn huffv_calldata(
+    proof_json: *const c_char,
+    public_json: *const c_char,
+    reverse_inputs: c_int,
+    out: *mut *mut c_char,
+) -> c_int {
+    let order = if reverse_inputs != 0 {
+        PublicInputOrder::Reverse
+    } else {
+        PublicInputOrder::Forward
+    };
+    let calldata = read_str(proof_json).and_then(|proof_json| {
+        let proof = Proof::from_json(proof_json)?;
+        let public = PublicSignals::from_json(read_str(public_json)?)?;
+        Ok(encode_calldata(&proof, &public, order, Abi::default())?)
+    });
+    write_result(calldata, out)
+}
+
+/// Releases a string written by one of the functions above.
+///
+/// # Safety
+///
+/// `s` must be a string written by this library that has not already been
+/// released, or null.
+#[no_mangle]
+pub unsafe extern "C" fn huffv_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+////////////////////////////////////////////////////////////////
+//                      HELPER FUNCTIONS                      //
+////////////////////////////////////////////////////////////////
+
+/// An error message to hand back to the caller.
+struct FfiError(String);
+
+impl From<HuffvError> for FfiError {
+    fn from(e: HuffvError) -> Self {
+        FfiError(e.to_string())
+    }
+}
+
+/// Borrows a C string as UTF-8.
+unsafe fn read_str<'a>(s: *const c_char) -> Result<&'a str, FfiError> {
+    if s.is_null() {
+        return Err(FfiError("Unexpected null string.".to_string()));
+    }
+    CStr::from_ptr(s)
+        .to_str()
+        .map_err(|e| FfiError(format!("String is not valid UTF-8: {}", e)))
+}
+
+/// Writes the value or error message of `result` to `out`, returning the status code.
+unsafe fn write_result(result: Result<String, FfiError>, out: *mut *mut c_char) -> c_int {
+    let (status, message) = match result {
+        Ok(value) => (0, value),
+        Err(FfiError(message)) => (1, message),
+    };
+    if !out.is_null() {
+        // Neither the outputs nor the error messages contain NUL bytes
+        *out = CString::new(message).unwrap_or_default().into_raw();
+    }
+    status
+}
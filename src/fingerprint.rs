@@ -0,0 +1,114 @@
+use crate::error::{HuffvError, Result};
+use crate::utils::keccak256;
+use crate::vkey::VerificationKey;
+use serde::Serialize;
+use std::fmt;
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The start of the trailer comment that records a verifier's fingerprint. Every
+/// target accepts `//` comments.
+pub static TRAILER_PREFIX: &str = "// huffv fingerprint:";
+
+////////////////////////////////////////////////////////////////
+//                        FINGERPRINT                         //
+////////////////////////////////////////////////////////////////
+
+/// What a verifier was generated from, and with which `huffv`, so that a deployed
+/// verifier can be traced back to a specific key and generator version.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Fingerprint {
+    /// The version of `huffv` that generated the verifier.
+    pub version: String,
+    /// The keccak256 hash of the canonical verification key JSON, see [`key_fingerprint`].
+    pub vkey_hash: String,
+    /// The keccak256 hash of the verifier source, without its trailer.
+    pub source_hash: String,
+}
+
+/// Fingerprint implementation
+impl Fingerprint {
+    /// The fingerprint of the verifier `source` generated for `key` by this version of `huffv`.
+    pub fn new(key: &VerificationKey, source: &str) -> Result<Self> {
+        Ok(Self {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            vkey_hash: format!("0x{}", hex::encode(key_fingerprint(key)?)),
+            source_hash: format!("0x{}", hex::encode(keccak256(source.as_bytes()))),
+        })
+    }
+
+    /// Reads the fingerprint from the trailer of `contract`, returning it along with
+    /// the source it was computed over.
+    pub fn from_contract(contract: &str) -> Result<(Self, &str)> {
+        let start = contract
+            .rfind(TRAILER_PREFIX)
+            .filter(|&start| start == 0 || contract[..start].ends_with('\n'))
+            .ok_or_else(|| HuffvError::Fingerprint("the contract has no trailer".to_string()))?;
+        let fields = contract[start + TRAILER_PREFIX.len()..]
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .split(',')
+            .map(|field| field.trim().split_once(' ').unwrap_or_default())
+            .collect::<Vec<_>>();
+        let field = |name: &str| {
+            fields
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| value.to_string())
+                .ok_or_else(|| HuffvError::Fingerprint(format!("the trailer has no {}", name)))
+        };
+
+        let fingerprint = Self {
+            version: field("version")?,
+            vkey_hash: field("vkey")?,
+            source_hash: field("source")?,
+        };
+        Ok((fingerprint, &contract[..start]))
+    }
+
+    /// A key to cache what is built from the verifier under, e.g. its compiled code: the
+    /// keccak256 hash of the fingerprint, which changes with the key, with any option
+    /// that changes the source, and with the version of `huffv`.
+    pub fn cache_key(&self) -> [u8; 32] {
+        keccak256(self.trailer().as_bytes())
+    }
+
+    /// The trailer comment that records the fingerprint.
+    pub fn trailer(&self) -> String {
+        format!(
+            "{} version {}, vkey {}, source {}\n",
+            TRAILER_PREFIX, self.version, self.vkey_hash, self.source_hash
+        )
+    }
+}
+
+impl fmt::Display for Fingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "huffv version:     {}", self.version)?;
+        writeln!(f, "Key hash:          {}", self.vkey_hash)?;
+        write!(f, "Source hash:       {}", self.source_hash)
+    }
+}
+
+/// The keccak256 hash of `key` as canonical JSON: compact, with sorted object keys,
+/// and without any fields `huffv` doesn't read, so that reformatting the key file
+/// doesn't change it.
+pub fn key_fingerprint(key: &VerificationKey) -> Result<[u8; 32]> {
+    // `serde_json::Value` objects are ordered by key
+    let canonical = serde_json::to_value(key)?.to_string();
+    Ok(keccak256(canonical.as_bytes()))
+}
+
+/// Appends the fingerprint trailer to a verifier `contract` generated for `key`.
+pub fn append_trailer(mut contract: String, key: &VerificationKey) -> Result<String> {
+    if !contract.ends_with('\n') {
+        contract.push('\n');
+    }
+    let trailer = Fingerprint::new(key, &contract)?.trailer();
+    contract.push_str(&trailer);
+    Ok(contract)
+}
@@ -0,0 +1,141 @@
+use crate::codegen::{MemoryLayout, GROTH16_PAIRING_TERMS};
+use serde::Serialize;
+use std::fmt;
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The base cost of a transaction
+pub const TX_BASE_GAS: usize = 21000;
+
+/// The cost of a non-zero calldata byte (EIP-2028)
+pub const CALLDATA_NONZERO_BYTE_GAS: usize = 16;
+
+/// The cost of a zero calldata byte
+pub const CALLDATA_ZERO_BYTE_GAS: usize = 4;
+
+/// The cost of a `*copy` per 32 byte word copied
+pub const COPY_WORD_GAS: usize = 3;
+
+/// The cost of a `staticcall` to a warm precompile (EIP-2929)
+pub const WARM_CALL_GAS: usize = 100;
+
+/// The cost of an `ecAdd` precompile call (EIP-1108)
+pub const EC_ADD_GAS: usize = 150;
+
+/// The cost of an `ecMul` precompile call (EIP-1108)
+pub const EC_MUL_GAS: usize = 6000;
+
+/// The base cost of an `ecPairing` precompile call (EIP-1108)
+pub const EC_PAIRING_BASE_GAS: usize = 45000;
+
+/// The cost of each pair of an `ecPairing` precompile call (EIP-1108)
+pub const EC_PAIRING_PAIR_GAS: usize = 34000;
+
+////////////////////////////////////////////////////////////////
+//                         GAS REPORT                         //
+////////////////////////////////////////////////////////////////
+
+/// The expected gas of a verification, computed from the verifier's memory layout.
+///
+/// Only the costs that scale with the verification key are counted, so the
+/// execution of the verifier's own opcodes (a few hundred gas) is not included.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GasReport {
+    /// The number of public inputs.
+    pub n_inputs: usize,
+    /// An upper bound on the calldata cost, assuming every proof and input byte is non-zero.
+    pub calldata: usize,
+    /// Memory expansion, plus copying the proof, inputs, and key into memory.
+    pub memory: usize,
+    /// The `ecMul` and `ecAdd` calls of the linear combination `vk_x`.
+    pub linear_combination: usize,
+    /// The `ecPairing` call.
+    pub pairing: usize,
+    /// The gas of executing the verifier: memory, the linear combination, and the pairing.
+    pub execution: usize,
+    /// The base cost of the transaction, see [`TX_BASE_GAS`].
+    pub base: usize,
+    /// The gas of a transaction that calls the verifier directly: the base cost,
+    /// calldata, and execution.
+    pub transaction: usize,
+}
+
+/// GasReport implementation
+impl GasReport {
+    /// The gas report of the verifier with memory `layout`.
+    pub fn new(layout: &MemoryLayout) -> Self {
+        let n_inputs = layout.n_ics.saturating_sub(1);
+
+        // Selector, 8 proof words, and the inputs are counted as non-zero, while the
+        // offset and length words have a single non-zero byte each.
+        let calldata = (4 + (8 + n_inputs) * 0x20) * CALLDATA_NONZERO_BYTE_GAS
+            + 2 * (CALLDATA_NONZERO_BYTE_GAS + 31 * CALLDATA_ZERO_BYTE_GAS);
+
+        // The verifier touches memory up to the end of the public inputs
+        let memory_words = (layout.pub_input_ptr() + n_inputs * 0x20).div_ceil(0x20);
+        let expansion = 3 * memory_words + memory_words * memory_words / 512;
+        // The proof and inputs (with their length) are copied from calldata, and the
+        // ICs, alpha, beta, gamma, and delta from the verification key.
+        let copied_words = 8 + n_inputs + 1 + layout.ic_bytes() / 0x20 + 0x1c0 / 0x20;
+        let memory = expansion + copied_words * COPY_WORD_GAS;
+
+        let linear_combination =
+            n_inputs * (EC_MUL_GAS + WARM_CALL_GAS) + (n_inputs + 1) * (EC_ADD_GAS + WARM_CALL_GAS);
+
+        let pairing =
+            EC_PAIRING_BASE_GAS + GROTH16_PAIRING_TERMS.len() * EC_PAIRING_PAIR_GAS + WARM_CALL_GAS;
+
+        let execution = memory + linear_combination + pairing;
+        Self {
+            n_inputs,
+            calldata,
+            memory,
+            linear_combination,
+            pairing,
+            execution,
+            base: TX_BASE_GAS,
+            transaction: TX_BASE_GAS + calldata + execution,
+        }
+    }
+}
+
+impl fmt::Display for GasReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Public inputs:       {}", self.n_inputs)?;
+        writeln!(f)?;
+        writeln!(f, "| STAGE              | GAS      |")?;
+        writeln!(f, "| ------------------ | -------- |")?;
+        writeln!(f, "| Memory             | {:>8} |", self.memory)?;
+        writeln!(f, "| Linear combination | {:>8} |", self.linear_combination)?;
+        writeln!(f, "| Pairing            | {:>8} |", self.pairing)?;
+        writeln!(f, "| Execution          | {:>8} |", self.execution)?;
+        writeln!(f, "| Calldata (max)     | {:>8} |", self.calldata)?;
+        writeln!(f, "| Transaction base   | {:>8} |", self.base)?;
+        write!(f, "| Transaction (max)  | {:>8} |", self.transaction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stages_sum_to_the_totals() {
+        for n_ics in [1, 2, 17] {
+            let report = GasReport::new(&MemoryLayout::new(n_ics));
+            let json = serde_json::to_value(report).unwrap();
+            let field = |name: &str| json[name].as_u64().unwrap();
+            assert_eq!(
+                field("memory") + field("linearCombination") + field("pairing"),
+                field("execution")
+            );
+            assert_eq!(
+                field("base") + field("calldata") + field("execution"),
+                field("transaction")
+            );
+        }
+    }
+}
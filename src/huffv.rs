@@ -1,23 +1,70 @@
-#![doc = include_str!("../README.md")]
-
-use clap::Parser;
-use ibig::IBig;
+use clap::{ArgAction, Args, Parser, Subcommand, ValueEnum};
+use huff_snark_verifier::artifact::CONTRACT_NAME;
+use huff_snark_verifier::codegen::{DEFAULT_EVENT_SIGNATURE, DEFAULT_INPUTS_HASH_EVENT_SIGNATURE};
+use huff_snark_verifier::compare::SNARKJS_CONTRACT_NAME;
+use huff_snark_verifier::deploy::{
+    checksum_address, create2_address, mine_create2_salt, DETERMINISTIC_DEPLOYER,
+};
+use huff_snark_verifier::package::MANIFEST_FILE;
+use huff_snark_verifier::utils::{encode_num, keccak256};
+use huff_snark_verifier::vkey::Curve;
+use huff_snark_verifier::{
+    annotated_disassembly, assemble_runtime_code, bench_initcode, bench_verifier, deploy_verifier,
+    difftest_verifier, encode_batch_calldata, encode_calldata_for_circuit, encode_calldata_with,
+    encode_calldata_with_key, encode_deploy_clone_calldata, encode_register_calldata,
+    encode_set_vkey_calldata, generate_aggregator, generate_batch_verifier, generate_factory,
+    generate_multi_verifier, generate_registry, generate_snarkjs_verifier, generate_test_scaffold,
+    generate_test_vectors, generate_verifier_with, key_fingerprint, parse_r1cs, simulate_verifier,
+    synthetic_circuit, verify_proof, vkey_data_initcode, Abi, Artifact, Chain, ComparisonReport,
+    Emit, EvmVersion, Fingerprint, GasReport, HuffvError, IcConstantPosition, InputCommitment,
+    InputFormat, MemoryLayout, MsmStrategy, OnFailure, Optimization, Package, PrecompileAddresses,
+    Proof, PublicInputOrder, PublicSignals, R1csHeader, Selector, SelfTest, SizeReport, Target,
+    VerificationKey, VerifierOptions, VkeyStorage,
+};
 use serde::{Deserialize, Serialize};
+use std::env;
+use std::error::Error;
+use std::ffi::OsStr;
 use std::fmt;
 use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
 use std::path::Path;
+use std::process;
+use std::thread;
+use std::time::{Duration, SystemTime};
+use tracing::{debug, error, info, warn, Level};
+use tracing_subscriber::filter::Targets;
+use tracing_subscriber::prelude::*;
 
 ////////////////////////////////////////////////////////////////
 //                         CONSTANTS                          //
 ////////////////////////////////////////////////////////////////
 
-/// The Verifier template contract
-pub static HUFF_VERIFIER_CONTRACT: &str = include_str!("contracts/VerifierTemplate.huff");
+/// The name of the config file that is loaded from the current directory
+/// when no `--config` path is provided.
+pub static CONFIG_FILE_NAME: &str = "huffv.toml";
+
+/// The path that reads a verification key from stdin instead of a file.
+pub static STDIN_PATH: &str = "-";
+
+/// The circom compiler, run by `huffv pipeline`.
+pub static CIRCOM_BIN: &str = "circom";
+
+/// The snarkjs CLI, run by `huffv pipeline`.
+pub static SNARKJS_BIN: &str = "snarkjs";
 
-/// The offset bases for pairing inputs
-pub static PI_OFFSET_BASES: [usize; 13] = [
-    0x00, 0x20, 0x40, 0x60, 0x80, 0xA0, 0xC0, 0x180, 0x1A0, 0x1C0, 0x240, 0x260, 0x280,
-];
+/// The rapidsnark prover, run by `huffv fixtures --prover rapidsnark`.
+pub static RAPIDSNARK_BIN: &str = "prover";
+
+/// The Solidity compiler, run by `huffv compare` and `huffv difftest`.
+pub static SOLC_BIN: &str = "solc";
+
+/// The environment variable `huffv deploy` reads the private key from, unless
+/// `--private-key` is passed.
+pub static PRIVATE_KEY_VAR: &str = "HUFFV_PRIVATE_KEY";
+
+/// How often `huffv generate --watch` checks the watched files for changes.
+pub static WATCH_INTERVAL: Duration = Duration::from_millis(500);
 
 ////////////////////////////////////////////////////////////////
 //                  HUFF SNARK VERIFIER CLI                   //
@@ -25,173 +72,2562 @@ pub static PI_OFFSET_BASES: [usize; 13] = [
 
 /// Huff SNARK Verifier CLI Args
 #[derive(Parser, Debug)]
-#[clap(name = "huffv", version, about, long_about = None)]
+#[clap(name = "huffv", version, about, long_about = None, args_conflicts_with_subcommands = true)]
 pub struct HuffVerifier {
-    /// The path to the verification key json file generated by snarkjs.
-    pub path: Option<String>,
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+    /// With no subcommand, `huffv` generates a verifier, as `huffv generate` does.
+    #[clap(flatten)]
+    generate: GenerateArgs,
+
+    /// Log the values derived while generating, e.g. the memory layout. Pass twice
+    /// to log everything.
+    #[clap(short = 'v', long = "verbose", global = true, action = ArgAction::Count)]
+    verbose: u8,
+
+    /// Only log errors.
+    #[clap(short = 'q', long = "quiet", global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// The format of the verification keys read: `snarkjs` JSON keys and `.zkey` files,
+    /// or `arkworks` Groth16 verifying keys in their canonical serialization.
+    /// [default: snarkjs]
+    #[clap(long = "input-format", value_enum, global = true)]
+    input_format: Option<InputFormat>,
+}
+
+/// Huff SNARK Verifier CLI Subcommands
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Generate a verification contract from a verification key. (default)
+    Generate(Box<GenerateArgs>),
+
+    /// Build the calldata for verifying a snarkjs proof with the generated verifier.
+    Calldata(CalldataArgs),
+
+    /// Generate a Foundry test for the verifier, with a snarkjs proof as its fixture.
+    ScaffoldTest(ScaffoldTestArgs),
+
+    /// Write test vectors for the verifier from a snarkjs proof, as a JSON manifest of
+    /// calldata and expected outcomes, with malformed variants of the proof with
+    /// `--negative`.
+    Vectors(VectorsArgs),
+
+    /// Compile a circom circuit, run the Groth16 setup, and generate its verifier,
+    /// with `circom` and `snarkjs`.
+    Pipeline(PipelineArgs),
+
+    /// Prove a circuit's witnesses with `snarkjs` or `rapidsnark`, and write the proofs
+    /// and their public signals to a fixture directory, with the verification key.
+    Fixtures(FixturesArgs),
+
+    /// Verify a snarkjs proof against a verification key off-chain.
+    Verify {
+        /// The path to the verification key json (or `.zkey`) file generated by snarkjs,
+        /// or `-` to read it from stdin.
+        #[clap(long = "vk")]
+        vk: String,
+
+        /// The path to the proof json file generated by snarkjs.
+        #[clap(long = "proof")]
+        proof: String,
+
+        /// The path to the public signals json file generated by snarkjs.
+        #[clap(long = "public")]
+        public: String,
+    },
+
+    /// Print the keccak256 commitment to raw inputs that a verifier generated with
+    /// `--input-commitment keccak` computes, as the public signals json of the circuit.
+    CommitInputs {
+        /// The path to a json array of the raw inputs, in the format of snarkjs's
+        /// public signals.
+        #[clap(long = "inputs")]
+        inputs: String,
+    },
+
+    /// Generate a Huff registry mapping verification key hashes to deployed verifiers,
+    /// and the calldata for registering verifiers with it.
+    Registry(RegistryArgs),
+
+    /// Print the `CREATE2` address a verifier is deployed at, from its creation code, the
+    /// factory, and the salt, or mine a salt for an address with a vanity prefix.
+    Address(AddressArgs),
+
+    /// Build the calldata for replacing the verification key of a verifier generated
+    /// with `--vkey-storage storage`, with `setVerificationKey(bytes)`.
+    SetVkey {
+        /// The path to the verification key json (or `.zkey`) file generated by snarkjs,
+        /// or `-` to read it from stdin.
+        path: String,
+    },
+
+    /// Build the calldata for deploying a clone of a verifier for a verification key,
+    /// with `deployClone(bytes)` of the factory generated with `--factory`.
+    DeployClone {
+        /// The path to the verification key json (or `.zkey`) file generated by snarkjs,
+        /// or `-` to read it from stdin.
+        path: String,
+    },
+
+    /// Check a verifier generated with `--fingerprint` against its trailer, and
+    /// optionally against the verification key it was generated from.
+    Fingerprint {
+        /// The path to the verifier contract.
+        path: String,
+
+        /// The path to the verification key json (or `.zkey`) file the verifier is
+        /// expected to have been generated from.
+        #[clap(long = "vk")]
+        vk: Option<String>,
+    },
+
+    /// Print a summary of a verification key and the memory layout of its verifier.
+    Inspect {
+        /// The path to the verification key json (or `.zkey`) file generated by snarkjs,
+        /// or `-` to read it from stdin.
+        path: String,
+    },
+
+    /// Estimate the gas of verifying a proof with the verifier of a verification key.
+    GasReport {
+        /// The path to the verification key json (or `.zkey`) file generated by snarkjs,
+        /// or `-` to read it from stdin.
+        path: String,
+
+        /// Print the report as JSON instead of a table.
+        #[clap(long = "json")]
+        json: bool,
+    },
+
+    /// Measure the gas of verifying a proof by deploying the verifier and calling it
+    /// under an embedded EVM (revm), or chart it against the number of public inputs.
+    Bench(BenchArgs),
+
+    /// Deploy the verifier in an embedded EVM (revm), call it with a proof, and report
+    /// whether it verified the proof and the gas it used.
+    Simulate(SimulateArgs),
+
+    /// Deploy the verifier through a JSON-RPC node, and print its address and the hash
+    /// of the deployment transaction.
+    Deploy(DeployArgs),
+
+    /// Compare the gas and code size of the Huff verifier with those of the Solidity
+    /// verifier of snarkjs, compiled with `solc`, verifying the same proof under revm.
+    Compare(CompareArgs),
+
+    /// Run a proof and mutations of it through the Huff verifier and the Solidity
+    /// verifier of snarkjs, compiled with `solc`, under revm, and check that both accept
+    /// exactly the proofs that verify off-chain.
+    Difftest(DifftestArgs),
+
+    /// Assemble the Huff verifier of a verification key, and report its code size
+    /// against the EIP-170 limit.
+    Size {
+        /// The path to the verification key json (or `.zkey`) file generated by snarkjs,
+        /// or `-` to read it from stdin.
+        path: String,
+
+        /// The path to a Huff file with the `MAIN` macro to compile the verifier with,
+        /// e.g. your own wrapper. If not provided, the verifier's own `MAIN` (see
+        /// `--main`) is used.
+        #[clap(long = "wrapper")]
+        wrapper: Option<String>,
+
+        #[clap(flatten)]
+        verifier: VerifierArgs,
+
+        /// Print the report as JSON instead of a table.
+        #[clap(long = "json")]
+        json: bool,
+    },
+
+    /// Bundle the Huff verifier of a verification key with the key, its memory layout, and
+    /// a manifest of their hashes into a single file, sealed by a checksum.
+    Package(PackageArgs),
+
+    /// Check the integrity of a package written by `huffv package`, and extract its files
+    /// and manifest to a directory.
+    Unpackage {
+        /// The path to the package.
+        path: String,
+
+        /// The directory to extract the files to.
+        #[clap(short = 'o', long = "out-dir")]
+        out_dir: String,
+    },
+
+    /// Check the integrity of a package written by `huffv package`: its checksum, the
+    /// hash of every file, and the key against the manifest.
+    VerifyPackage {
+        /// The path to the package.
+        path: String,
+    },
+
+    /// Print the version and the inputs, outputs, and proof systems this build supports.
+    Capabilities {
+        /// Print the capabilities as JSON instead of human-readable text.
+        #[clap(long = "json")]
+        json: bool,
+    },
+}
+
+/// `huffv generate` Args
+#[derive(Args, Clone, Debug, Default)]
+struct GenerateArgs {
+    /// The path to the verification key json (or `.zkey`) file generated by snarkjs,
+    /// or `-` to read it from stdin.
+    path: Option<String>,
 
     /// If an output file is designated, the generator will save the verification
     /// contract to a file instead of sending it to stdout.
     #[clap(short = 'o', long = "output")]
     output: Option<String>,
+
+    /// Generate a verifier for every `*.json` (or `*.zkey`) verification key in this
+    /// directory, instead of a single key.
+    #[clap(
+        long = "input-dir",
+        conflicts_with_all = &["path", "output"],
+        requires = "out-dir"
+    )]
+    input_dir: Option<String>,
+
+    /// The directory to save the verifiers generated with `--input-dir` to, each
+    /// named after its verification key.
+    #[clap(long = "out-dir", requires = "input-dir")]
+    out_dir: Option<String>,
+
+    /// Print the result of each key of `--input-dir` to stdout as a line of JSON as soon
+    /// as its verifier is generated, for consumers that process them incrementally.
+    #[clap(long = "json-stream", requires = "input-dir")]
+    json_stream: bool,
+
+    /// If an artifacts directory is designated, a Hardhat-style artifact with the
+    /// verifier's ABI and metadata is saved to it as `Verifier.json`.
+    #[clap(long = "artifacts")]
+    artifacts: Option<String>,
+
+    /// If an aggregator path is designated, an ERC-4337 `IAggregator` whose aggregated
+    /// signature is a proof for the verifier is saved to it.
+    #[clap(long = "aggregator", conflicts_with = "input-dir")]
+    aggregator: Option<String>,
+
+    /// If a vkey data path is designated, the creation code of the data contract holding
+    /// the packed key, which verifiers with `--vkey-storage external` are deployed with,
+    /// is saved to it as hex.
+    #[clap(long = "vkey-data", conflicts_with = "input-dir")]
+    vkey_data: Option<String>,
+
+    /// If a factory path is designated, a Huff factory deploying EIP-1167 clones of the
+    /// verifier for other keys with as many public inputs is saved to it. The verifier
+    /// needs `--vkey-storage constructor` or `--vkey-storage storage`.
+    #[clap(long = "factory", conflicts_with = "input-dir")]
+    factory: Option<String>,
+
+    /// If a disassembly path is designated, the runtime code of the Huff verifier is
+    /// disassembled to it, annotated with the memory regions its loads, stores, and
+    /// copies touch and the precompiles it calls, for review.
+    #[clap(long = "disasm-out", conflicts_with = "input-dir")]
+    disasm_out: Option<String>,
+
+    /// The path to the verification key json (or `.zkey`) file of another circuit to
+    /// verify with the same Huff verifier. Repeat it for every circuit: the key at the
+    /// path is circuit 0, and the others follow in order, as circuits 1, 2, ... The
+    /// verifier's verification function then takes the circuit id first.
+    #[clap(
+        long = "circuit",
+        conflicts_with_all = &["input-dir", "artifacts", "aggregator", "vkey-data", "factory", "disasm-out", "r1cs", "watch"]
+    )]
+    circuits: Vec<String>,
+
+    /// Generate a batch verifier instead, whose `verifyBatch(uint256[8][],uint256[])`
+    /// verifies any number of proofs of the circuit with a single pairing check. Only
+    /// `--evm-version`, `--precompile-addresses`, and `--emit` apply to it.
+    #[clap(
+        long = "batch",
+        conflicts_with_all = &["circuits", "artifacts", "aggregator", "factory", "disasm-out"]
+    )]
+    batch: bool,
+
+    #[clap(flatten)]
+    verifier: VerifierArgs,
+
+    /// The path to the circom R1CS file of the circuit, to check that the
+    /// verification key's public signals match it before generating.
+    #[clap(long = "r1cs", conflicts_with = "input-dir")]
+    r1cs: Option<String>,
+
+    /// The paths to a known-good snarkjs proof of the circuit and its public signals,
+    /// verified off-chain against the key before the verifier is written. Generation
+    /// fails, writing nothing, if the key rejects the proof.
+    #[clap(
+        long = "verify-after-generate",
+        number_of_values = 2,
+        value_names = &["PROOF", "PUBLIC"],
+        conflicts_with_all = &["input-dir", "circuits"]
+    )]
+    verify_after_generate: Option<Vec<String>>,
+
+    /// The format of the output: the contract alone, or a JSON bundle of the
+    /// contract with its packed key and memory layout.
+    #[clap(long = "format", value_enum, default_value_t)]
+    format: OutputFormat,
+
+    /// Keep running, and regenerate the verifier whenever the verification key, the
+    /// custom template, the R1CS file, the self-test proof, or the config file changes.
+    #[clap(long = "watch", conflicts_with = "input-dir")]
+    watch: bool,
+
+    /// If a cache key path is designated, a key to cache the compiled verifier under is
+    /// saved to it as hex: the hash of the `huffv` version, the key, and the verifier
+    /// source, which changes with any option that changes the verifier.
+    #[clap(long = "cache-key-out", conflicts_with_all = &["input-dir", "circuits"])]
+    cache_key_out: Option<String>,
+
+    /// Render and check the verifier without writing it anywhere, exiting with the
+    /// problems found if it is broken (e.g. a custom template left tags unfilled).
+    #[clap(long = "check")]
+    check: bool,
 }
 
-/// A SNARK Verification Key.
-///
-/// Can be directly deserialized from a JSON key generated by
-/// [snarkjs](https://github.com/iden3/snarkjs).
-#[derive(Serialize, Deserialize, Debug)]
-struct VerificationKey {
-    #[serde(rename(deserialize = "nPublic", serialize = "nPublic"))]
-    pub n_public: u64,
-
-    pub vk_alpha_1: Vec<String>,
-
-    pub vk_beta_2: Vec<Vec<String>>,
-
-    pub vk_gamma_2: Vec<Vec<String>>,
-
-    pub vk_delta_2: Vec<Vec<String>>,
-
-    pub vk_alphabeta_12: Vec<Vec<Vec<String>>>,
-
-    #[serde(rename(deserialize = "IC", serialize = "IC"))]
-    pub ic: Vec<Vec<String>>,
-}
-
-/// Verification key implementation
-impl VerificationKey {
-    /// Produce a packed hex representation of the verification key
-    pub fn to_packed(&self) -> String {
-        // Add alpha, beta, gamma, and delta as the base.
-        let mut base = format!(
-            "0x{}{}{}{}{}{}{}{}{}{}{}{}{}{}",
-            encode_num(&self.vk_alpha_1[0]),
-            encode_num(&self.vk_alpha_1[1]),
-            encode_num(&self.vk_beta_2[0][1]),
-            encode_num(&self.vk_beta_2[0][0]),
-            encode_num(&self.vk_beta_2[1][1]),
-            encode_num(&self.vk_beta_2[1][0]),
-            encode_num(&self.vk_gamma_2[0][1]),
-            encode_num(&self.vk_gamma_2[0][0]),
-            encode_num(&self.vk_gamma_2[1][1]),
-            encode_num(&self.vk_gamma_2[1][0]),
-            encode_num(&self.vk_delta_2[0][1]),
-            encode_num(&self.vk_delta_2[0][0]),
-            encode_num(&self.vk_delta_2[1][1]),
-            encode_num(&self.vk_delta_2[1][0]),
-        );
+/// The options of a verifier, shared by `huffv generate` and every subcommand that
+/// generates one, and resolved against the config file by [`VerifierArgs::resolve`].
+#[derive(Args, Clone, Debug, Default)]
+struct VerifierArgs {
+    /// The path to a config file with default options. If not provided, `huffv.toml`
+    /// is loaded from the current directory if it exists.
+    #[clap(long = "config")]
+    config: Option<String>,
+
+    /// The language of the generated verifier. [default: huff]
+    #[clap(long = "target", value_enum)]
+    target: Option<Target>,
+
+    /// The path to a custom template to fill instead of the embedded template of
+    /// the target, e.g. a fork of `VerifierTemplate.huff`.
+    #[clap(long = "template")]
+    template: Option<String>,
+
+    /// The order in which the generated verifier consumes public inputs,
+    /// relative to the order of the ICs in the verification key. [default: forward]
+    #[clap(long = "public-input-order", value_enum)]
+    public_input_order: Option<PublicInputOrder>,
+
+    /// The position of the constant term within the verification key's ICs.
+    /// snarkjs places it first. [default: first]
+    #[clap(long = "ic-constant-position", value_enum)]
+    ic_constant_position: Option<IcConstantPosition>,
+
+    /// Emit an event with the verification key hash as an indexed topic when a proof
+    /// is successfully verified. Note that this makes verification non-`view`.
+    #[clap(long = "emit-event", overrides_with = "no-emit-event")]
+    emit_event: bool,
+
+    /// Don't emit an event, overriding the config file.
+    #[clap(long = "no-emit-event", overrides_with = "emit-event")]
+    no_emit_event: bool,
+
+    /// The signature of the event emitted with `--emit-event`.
+    /// [default: ProofVerified(bytes32), or ProofVerified(bytes32,bytes32) with
+    /// `--event-inputs-hash`]
+    #[clap(long = "event-signature")]
+    event_signature: Option<String>,
+
+    /// Log the keccak256 hash of the public inputs, as 32-byte words, as the data of the
+    /// event of `--emit-event`.
+    #[clap(long = "event-inputs-hash", overrides_with = "no-event-inputs-hash")]
+    event_inputs_hash: bool,
+
+    /// Don't log the hash of the public inputs, overriding the config file.
+    #[clap(long = "no-event-inputs-hash", overrides_with = "event-inputs-hash")]
+    no_event_inputs_hash: bool,
+
+    /// Whether to optimize the verifier for gas or for code size. [default: gas]
+    #[clap(long = "opt", value_enum)]
+    opt: Option<Optimization>,
+
+    /// Whether to compute the linear combination of the public inputs in a loop, or
+    /// unrolled for each input. [default: auto]
+    #[clap(long = "msm", value_enum)]
+    msm: Option<MsmStrategy>,
+
+    /// The EVM version to target, which decides whether the verifier uses `PUSH0`
+    /// (shanghai) and `MCOPY` (cancun). [default: shanghai]
+    #[clap(long = "evm-version", value_enum)]
+    evm_version: Option<EvmVersion>,
+
+    /// The addresses of the precompiles, for chains that relocate or wrap them, as
+    /// comma separated overrides of the standard ones, e.g.
+    /// `ec-add=0x0106,ec-mul=0x0107,ec-pairing=0x0108` (also `identity` and `modexp`).
+    #[clap(long = "precompile-addresses", value_parser)]
+    precompile_addresses: Option<PrecompileAddresses>,
+
+    /// The chain to deploy the verifier on. `zksync` adjusts the verifier for EraVM,
+    /// and warns about what won't work there. [default: ethereum]
+    #[clap(long = "chain", value_enum)]
+    chain: Option<Chain>,
+
+    /// The ABI of the verification function: `huffv`'s `verify` with a dynamic public
+    /// inputs array, or snarkjs's `verifyProof` with a fixed size one, as a drop-in
+    /// replacement for snarkjs verifiers. [default: huffv]
+    #[clap(long = "abi", value_enum)]
+    abi: Option<Abi>,
+
+    /// What the verifier does with invalid proofs: `return` false, or `revert` with
+    /// `InvalidProof()`, `InvalidPublicInput(uint256)`, and `InvalidCalldata()`.
+    /// [default: return]
+    #[clap(long = "on-failure", value_enum)]
+    on_failure: Option<OnFailure>,
+
+    /// Take any number of raw inputs, and verify the proof with their `keccak` commitment
+    /// as the circuit's only public input. See `huffv commit-inputs`. [default: none]
+    #[clap(long = "input-commitment", value_enum)]
+    input_commitment: Option<InputCommitment>,
+
+    /// Take the proof with its points compressed to their `x` coordinates, halving its
+    /// calldata at the cost of decompressing it on-chain. See `huffv calldata`.
+    #[clap(long = "compressed-proof", overrides_with = "no-compressed-proof")]
+    compressed_proof: bool,
+
+    /// Take the proof uncompressed, overriding the config file.
+    #[clap(long = "no-compressed-proof", overrides_with = "compressed-proof")]
+    no_compressed_proof: bool,
+
+    /// The name of the verification function, to match an interface the verifier has
+    /// to implement. [default: the name of `--abi`]
+    #[clap(long = "fn-name")]
+    fn_name: Option<String>,
+
+    /// The `0x` prefixed selector the verifier dispatches to the verification function,
+    /// instead of the selector of its signature. Huff and Yul only.
+    #[clap(long = "selector", value_parser)]
+    selector: Option<Selector>,
+
+    /// End the Huff verifier with a `MAIN` macro that dispatches the verification
+    /// function and `VKEY_HASH()`, so that it can be compiled and deployed as is.
+    #[clap(long = "main", overrides_with = "no-main")]
+    main: bool,
+
+    /// Don't end the Huff verifier with a `MAIN` macro, overriding the config file.
+    #[clap(long = "no-main", overrides_with = "main")]
+    no_main: bool,
+
+    /// What to emit: a verifier `contract`, a Huff `library` whose `VERIFY_PROOF()`
+    /// macro verifies a proof inline, for `#include` into a larger Huff contract, only
+    /// the packed key and memory layout `constants`, or the verifier's `initcode`, its
+    /// creation code as hex, assembled without `huffc`. [default: contract]
+    #[clap(long = "emit", value_enum)]
+    emit: Option<Emit>,
+
+    /// Where the Huff verifier reads the verification key from: the `VKEY` table of its
+    /// `code`, the end of its runtime code, appended by its `CONSTRUCTOR` from the
+    /// packed key it is deployed with (`constructor`), a data contract it is deployed
+    /// with the address of (`external`, see `--vkey-data`), the calldata of each call
+    /// (`calldata`, see `huffv calldata --vk`), or a data contract whose address is in
+    /// storage, replaced by the owner (`storage`, see `huffv set-vkey`). [default: code]
+    #[clap(long = "vkey-storage", value_enum)]
+    vkey_storage: Option<VkeyStorage>,
+
+    /// Convert G1 points with a Jacobian `z` coordinate other than 1 to affine
+    /// coordinates before packing.
+    #[clap(long = "normalize-points", overrides_with = "no-normalize-points")]
+    normalize_points: bool,
 
-        // Push ICs to base verification key
-        let n_ics = self.ic.len();
-        let mut ics = encode_num(&n_ics.to_string());
-        (0..n_ics).for_each(|i| {
-            ics.push_str(&encode_num(&self.ic[i][0]));
-            ics.push_str(&encode_num(&self.ic[i][1]));
-        });
-        base.push_str(&ics);
+    /// Don't convert Jacobian G1 points, overriding the config file.
+    #[clap(long = "no-normalize-points", overrides_with = "normalize-points")]
+    no_normalize_points: bool,
 
-        base
+    /// End the verifier with a trailer comment recording the `huffv` version and the
+    /// hashes of the key and the source, to be checked with `huffv fingerprint`.
+    #[clap(long = "fingerprint", overrides_with = "no-fingerprint")]
+    fingerprint: bool,
+
+    /// Don't end the verifier with a trailer comment, overriding the config file.
+    #[clap(long = "no-fingerprint", overrides_with = "fingerprint")]
+    no_fingerprint: bool,
+
+    /// Answer ERC-165's `supportsInterface(bytes4)` for the verifier's interface id, the
+    /// XOR of the selectors of its verification function and `VKEY_HASH()`.
+    #[clap(long = "erc165", overrides_with = "no-erc165")]
+    erc165: bool,
+
+    /// Don't answer ERC-165's `supportsInterface(bytes4)`, overriding the config file.
+    #[clap(long = "no-erc165", overrides_with = "erc165")]
+    no_erc165: bool,
+
+    /// Check that the proof's G2 point is in the subgroup before the pairing, rather than
+    /// relying on the `ecPairing` precompile to reject it. Costs ~470k gas per proof.
+    #[clap(
+        long = "strict-subgroup-checks",
+        overrides_with = "no-strict-subgroup-checks"
+    )]
+    strict_subgroup_checks: bool,
+
+    /// Leave the subgroup check to the `ecPairing` precompile, overriding the config file.
+    #[clap(
+        long = "no-strict-subgroup-checks",
+        overrides_with = "strict-subgroup-checks"
+    )]
+    no_strict_subgroup_checks: bool,
+
+    /// Check that the proof's points aren't the point at infinity and that their
+    /// coordinates are in the base field before the linear combination, so that invalid
+    /// points revert early rather than in the pairing. Costs ~220 gas per proof.
+    #[clap(long = "check-proof-points", overrides_with = "no-check-proof-points")]
+    check_proof_points: bool,
+
+    /// Don't check the proof's points early, overriding the config file.
+    #[clap(long = "no-check-proof-points", overrides_with = "check-proof-points")]
+    no_check_proof_points: bool,
+
+    /// The path to a known-good snarkjs proof of the circuit, baked into a `SELF_TEST()`
+    /// function that verifies it, as a smoke check of the deployed verifier.
+    #[clap(long = "selftest-proof", requires = "selftest-public")]
+    selftest_proof: Option<String>,
+
+    /// The path to the public signals of the proof of `--selftest-proof`.
+    #[clap(long = "selftest-public", requires = "selftest-proof")]
+    selftest_public: Option<String>,
+}
+
+/// `VerifierArgs` implementation
+impl VerifierArgs {
+    /// Resolves the options of the verifier, loading defaults from the config file and
+    /// letting explicit CLI flags win, with how its key is normalized first.
+    fn resolve(self) -> Result<(VerifierOptions, KeyNormalization), Box<dyn Error>> {
+        // Load defaults from the config file, letting explicit CLI flags win.
+        let config = Config::load(self.config.as_deref())?;
+        let target = self.target.or(config.target).unwrap_or_default();
+        let template = self
+            .template
+            .or(config.template)
+            .map(|path| fs::read_to_string(&path).map_err(|e| HuffvError::io(&path, e)))
+            .transpose()?;
+        let public_input_order = self
+            .public_input_order
+            .or(config.public_input_order)
+            .unwrap_or_default();
+        let ic_constant_position = self
+            .ic_constant_position
+            .or(config.ic_constant_position)
+            .unwrap_or_default();
+        let emit_event = flag(self.emit_event, self.no_emit_event)
+            .or(config.emit_event)
+            .unwrap_or_default();
+        let event_inputs_hash = flag(self.event_inputs_hash, self.no_event_inputs_hash)
+            .or(config.event_inputs_hash)
+            .unwrap_or_default();
+        let event_signature = self
+            .event_signature
+            .or(config.event_signature)
+            .unwrap_or_else(|| {
+                if event_inputs_hash {
+                    DEFAULT_INPUTS_HASH_EVENT_SIGNATURE.to_string()
+                } else {
+                    DEFAULT_EVENT_SIGNATURE.to_string()
+                }
+            });
+        let normalize_points = flag(self.normalize_points, self.no_normalize_points)
+            .or(config.normalize_points)
+            .unwrap_or_default();
+        let optimization = self.opt.or(config.opt).unwrap_or_default();
+        let msm = self.msm.or(config.msm).unwrap_or_default();
+        let evm_version = self.evm_version.or(config.evm_version).unwrap_or_default();
+        let precompiles = self
+            .precompile_addresses
+            .or(config.precompile_addresses)
+            .unwrap_or_default();
+        let chain = self.chain.or(config.chain).unwrap_or_default();
+        let abi = self.abi.or(config.abi).unwrap_or_default();
+        let on_failure = self.on_failure.or(config.on_failure).unwrap_or_default();
+        let input_commitment = self
+            .input_commitment
+            .or(config.input_commitment)
+            .unwrap_or_default();
+        let compressed_proof = flag(self.compressed_proof, self.no_compressed_proof)
+            .or(config.compressed_proof)
+            .unwrap_or_default();
+        let fn_name = self.fn_name.or(config.fn_name);
+        let selector = self.selector.or(config.selector);
+        let main = flag(self.main, self.no_main)
+            .or(config.main)
+            .unwrap_or_default();
+        let emit = self.emit.or(config.emit).unwrap_or_default();
+        let vkey_storage = self
+            .vkey_storage
+            .or(config.vkey_storage)
+            .unwrap_or_default();
+        let fingerprint = flag(self.fingerprint, self.no_fingerprint)
+            .or(config.fingerprint)
+            .unwrap_or_default();
+        let erc165 = flag(self.erc165, self.no_erc165)
+            .or(config.erc165)
+            .unwrap_or_default();
+        let strict_subgroup_checks =
+            flag(self.strict_subgroup_checks, self.no_strict_subgroup_checks)
+                .or(config.strict_subgroup_checks)
+                .unwrap_or_default();
+        let check_proof_points = flag(self.check_proof_points, self.no_check_proof_points)
+            .or(config.check_proof_points)
+            .unwrap_or_default();
+        let self_test = match (&self.selftest_proof, &self.selftest_public) {
+            (Some(proof), Some(public)) => Some(SelfTest {
+                proof: Proof::from_file(Path::new(proof))?,
+                public: PublicSignals::from_file(Path::new(public))?,
+            }),
+            _ => None,
+        };
+
+        let options = VerifierOptions {
+            target,
+            template,
+            public_input_order,
+            event_signature: emit_event.then_some(event_signature),
+            event_inputs_hash,
+            optimization,
+            msm,
+            evm_version,
+            precompiles,
+            chain,
+            abi,
+            on_failure,
+            input_commitment,
+            compressed_proof,
+            fn_name,
+            selector,
+            main,
+            emit,
+            vkey_storage,
+            fingerprint,
+            erc165,
+            strict_subgroup_checks,
+            check_proof_points,
+            self_test,
+        };
+        let normalization = KeyNormalization {
+            ic_constant_position,
+            points: normalize_points,
+        };
+        Ok((options, normalization))
+    }
+}
+
+/// How a verification key is normalized before a verifier is generated for it.
+#[derive(Clone, Copy, Debug)]
+struct KeyNormalization {
+    /// The position of the constant term within the verification key's ICs.
+    ic_constant_position: IcConstantPosition,
+    /// Whether to convert Jacobian G1 points to affine coordinates.
+    points: bool,
+}
+
+/// `KeyNormalization` implementation
+impl KeyNormalization {
+    /// Moves the constant term of `key` to the front of its ICs, and converts its
+    /// Jacobian G1 points to affine coordinates if asked to.
+    fn apply(self, key: &mut VerificationKey) -> Result<(), Box<dyn Error>> {
+        key.normalize_ic_constant(self.ic_constant_position);
+        if self.points {
+            key.normalize_points()?;
+        }
+        Ok(())
     }
+
+    /// Reads the verification key at `path` in `input_format`, and normalizes it.
+    fn read_key(
+        self,
+        path: &Path,
+        input_format: InputFormat,
+    ) -> Result<VerificationKey, Box<dyn Error>> {
+        let mut key = read_verification_key(path, input_format)?;
+        self.apply(&mut key)?;
+        Ok(key)
+    }
+}
+
+/// The output format of `huffv generate`.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+enum OutputFormat {
+    /// The contract source.
+    #[default]
+    Text,
+    /// A JSON [`Bundle`] of the contract source and the values it was generated with.
+    Json,
 }
 
-impl fmt::Display for VerificationKey {
+/// The result of generating the verifier of a key of `huffv generate --input-dir`, as
+/// printed with `--json-stream`.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GenerationResult {
+    /// The path to the verification key.
+    key: String,
+    /// The path the verifier is saved to.
+    output: String,
+    /// Why the verifier couldn't be generated, if it couldn't.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// The resolved options of `huffv generate`, shared by every key it generates a verifier for.
+struct GenerateOptions {
+    /// The options of the generated verifier.
+    verifier: VerifierOptions,
+    /// How every key is normalized before its verifier is generated.
+    normalization: KeyNormalization,
+    /// The directory to save artifacts to, if any.
+    artifacts: Option<String>,
+    /// The path to save the ERC-4337 aggregator to, if any.
+    aggregator: Option<String>,
+    /// The path to save the creation code of the key's data contract to, if any.
+    vkey_data: Option<String>,
+    /// The path to save the clone factory to, if any.
+    factory: Option<String>,
+    /// The path to save the annotated disassembly of the verifier to, if any.
+    disasm_out: Option<String>,
+    /// Whether to generate a batch verifier instead.
+    batch: bool,
+    /// The path to save the cache key of the verifier to, if any.
+    cache_key_out: Option<String>,
+    /// The header of the circuit's R1CS, to check the key against, if any.
+    r1cs: Option<R1csHeader>,
+    /// The output format.
+    format: OutputFormat,
+    /// Whether to only check the verifiers, without writing them.
+    check: bool,
+    /// The format of the verification keys read.
+    input_format: InputFormat,
+    /// A known-good proof and its public signals, that the key must verify before the
+    /// verifier is written, if any.
+    verify_after_generate: Option<(Proof, PublicSignals)>,
+}
+
+/// `huffv calldata` Args
+#[derive(Args, Debug)]
+struct CalldataArgs {
+    /// The path to the proof json file generated by snarkjs.
+    #[clap(long = "proof", required_unless_present = "batch")]
+    proof: Option<String>,
+
+    /// The path to the public signals json file generated by snarkjs.
+    #[clap(long = "public", required_unless_present = "batch")]
+    public: Option<String>,
+
+    /// The paths to a proof and its public signals of a batch, for a batch verifier
+    /// generated with `--batch`. Repeat it for every proof of the batch.
+    #[clap(
+        long = "batch",
+        number_of_values = 2,
+        value_names = &["PROOF", "PUBLIC"],
+        multiple_occurrences = true,
+        conflicts_with_all = &["proof", "public", "vk", "circuit-id"]
+    )]
+    batch: Vec<String>,
+
+    #[clap(flatten)]
+    verifier: VerifierArgs,
+
+    /// The path to the verification key json (or `.zkey`) file to pass with the proof,
+    /// for a verifier generated with `--vkey-storage calldata`.
+    #[clap(long = "vk")]
+    vk: Option<String>,
+
+    /// The id of the proof's circuit, for a multi-circuit verifier generated with
+    /// `--circuit`.
+    #[clap(long = "circuit-id", conflicts_with = "vk")]
+    circuit_id: Option<usize>,
+}
+
+/// `huffv bench` Args
+#[derive(Args, Debug)]
+struct BenchArgs {
+    /// The path to the verification key json (or `.zkey`) file generated by snarkjs,
+    /// or `-` to read it from stdin.
+    #[clap(required_unless_present = "sweep")]
+    path: Option<String>,
+
+    /// The path to the proof json file generated by snarkjs.
+    #[clap(long = "proof", required_unless_present = "sweep")]
+    proof: Option<String>,
+
+    /// The path to the public signals json file generated by snarkjs.
+    #[clap(long = "public", required_unless_present = "sweep")]
+    public: Option<String>,
+
+    /// Instead of a key and proof, benchmark synthetic keys with 1 to this many public
+    /// inputs, each with a valid proof, and chart the gas against the number of inputs.
+    #[clap(long = "sweep", conflicts_with_all = &["path", "proof", "public"])]
+    sweep: Option<usize>,
+
+    /// The number of calls to the verifier, whose min and median gas are reported.
+    #[clap(long = "runs", default_value_t = 5)]
+    runs: usize,
+
+    #[clap(flatten)]
+    verifier: VerifierArgs,
+
+    /// Print the report as JSON instead of a table.
+    #[clap(long = "json")]
+    json: bool,
+}
+
+/// `huffv simulate` Args
+#[derive(Args, Debug)]
+struct SimulateArgs {
+    /// The path to the verification key json (or `.zkey`) file generated by snarkjs,
+    /// or `-` to read it from stdin.
+    #[clap(long = "vk")]
+    vk: String,
+
+    /// The path to the proof json file generated by snarkjs.
+    #[clap(long = "proof")]
+    proof: String,
+
+    /// The path to the public signals json file generated by snarkjs.
+    #[clap(long = "public")]
+    public: String,
+
+    #[clap(flatten)]
+    verifier: VerifierArgs,
+
+    /// Print the result as JSON instead of text.
+    #[clap(long = "json")]
+    json: bool,
+}
+
+/// `huffv deploy` Args
+#[derive(Args, Debug)]
+struct DeployArgs {
+    /// The path to the verification key json (or `.zkey`) file generated by snarkjs,
+    /// or `-` to read it from stdin.
+    path: String,
+
+    /// The URL of the JSON-RPC node to send the deployment to.
+    #[clap(long = "rpc-url")]
+    rpc_url: String,
+
+    /// The hex private key to sign the deployment with. If not provided, it is read
+    /// from the `HUFFV_PRIVATE_KEY` environment variable, which keeps it out of the
+    /// shell history.
+    #[clap(long = "private-key")]
+    private_key: Option<String>,
+
+    /// The paths to a snarkjs proof of the circuit and its public signals, verified
+    /// with a call to the verifier right after it is deployed. Fails unless the
+    /// verifier verifies it.
+    #[clap(
+        long = "verify-after",
+        number_of_values = 2,
+        value_names = &["PROOF", "PUBLIC"]
+    )]
+    verify_after: Option<Vec<String>>,
+
+    #[clap(flatten)]
+    verifier: VerifierArgs,
+
+    /// Print the deployment as JSON instead of text.
+    #[clap(long = "json")]
+    json: bool,
+}
+
+/// `huffv difftest` Args
+#[derive(Args, Debug)]
+struct DifftestArgs {
+    /// The path to the verification key json (or `.zkey`) file generated by snarkjs,
+    /// or `-` to read it from stdin.
+    path: String,
+
+    /// The path to the proof json file generated by snarkjs.
+    #[clap(long = "proof")]
+    proof: String,
+
+    /// The path to the public signals json file generated by snarkjs.
+    #[clap(long = "public")]
+    public: String,
+
+    #[clap(flatten)]
+    verifier: VerifierArgs,
+
+    /// Print the report as JSON instead of a table.
+    #[clap(long = "json")]
+    json: bool,
+}
+
+/// `huffv package` Args
+#[derive(Args, Debug)]
+struct PackageArgs {
+    /// The path to the verification key json (or `.zkey`) file generated by snarkjs,
+    /// or `-` to read it from stdin.
+    path: String,
+
+    /// The path to save the package to.
+    #[clap(short = 'o', long = "output")]
+    output: String,
+
+    #[clap(flatten)]
+    verifier: VerifierArgs,
+}
+
+/// `huffv compare` Args
+#[derive(Args, Debug)]
+struct CompareArgs {
+    /// The path to the verification key json (or `.zkey`) file generated by snarkjs,
+    /// or `-` to read it from stdin.
+    path: String,
+
+    /// The path to the proof json file generated by snarkjs.
+    #[clap(long = "proof")]
+    proof: String,
+
+    /// The path to the public signals json file generated by snarkjs.
+    #[clap(long = "public")]
+    public: String,
+
+    /// The number of calls to each verifier, whose median gas is compared.
+    #[clap(long = "runs", default_value_t = 5)]
+    runs: usize,
+
+    #[clap(flatten)]
+    verifier: VerifierArgs,
+
+    /// Print the report as JSON instead of a table.
+    #[clap(long = "json")]
+    json: bool,
+}
+
+/// `huffv scaffold-test` Args
+#[derive(Args, Debug)]
+struct ScaffoldTestArgs {
+    /// The path to the verification key json (or `.zkey`) file generated by snarkjs,
+    /// or `-` to read it from stdin.
+    #[clap(long = "vk")]
+    vk: String,
+
+    /// The path to the proof json file generated by snarkjs.
+    #[clap(long = "proof")]
+    proof: String,
+
+    /// The path to the public signals json file generated by snarkjs.
+    #[clap(long = "public")]
+    public: String,
+
+    /// The path to the generated verifier, relative to `src/` and without the
+    /// `.huff` extension, as `HuffDeployer` expects it.
+    #[clap(long = "verifier", default_value = "Verifier")]
+    verifier_path: String,
+
+    /// Also generate fuzz tests, which mutate each calldata word of the proof and
+    /// check that the verifier rejects it, and invariant tests, which check that it
+    /// never accepts the proof with other public inputs.
+    #[clap(long = "fuzz")]
+    fuzz: bool,
+
+    /// If an output file is designated, the test will be saved to a file instead
+    /// of sent to stdout.
+    #[clap(short = 'o', long = "output")]
+    output: Option<String>,
+
+    #[clap(flatten)]
+    verifier: VerifierArgs,
+}
+
+/// `huffv vectors` Args
+#[derive(Args, Debug)]
+struct VectorsArgs {
+    /// The path to the proof json file generated by snarkjs.
+    #[clap(long = "proof")]
+    proof: String,
+
+    /// The path to the public signals json file generated by snarkjs.
+    #[clap(long = "public")]
+    public: String,
+
+    /// Also write malformed variants of the proof: swapped G2 limbs, off-curve points,
+    /// public inputs not less than r, and truncated or extra calldata.
+    #[clap(long = "negative")]
+    negative: bool,
+
+    /// If an output file is designated, the manifest is saved to it instead of being
+    /// sent to stdout.
+    #[clap(short = 'o', long = "output")]
+    output: Option<String>,
+
+    #[clap(flatten)]
+    verifier: VerifierArgs,
+
+    /// The path to the verification key json (or `.zkey`) file to pass with the proof,
+    /// for a verifier generated with `--vkey-storage calldata`.
+    #[clap(long = "vk")]
+    vk: Option<String>,
+}
+
+/// `huffv registry` Args
+#[derive(Args, Debug)]
+struct RegistryArgs {
+    /// If an output file is designated, the registry is saved to it instead of being
+    /// sent to stdout.
+    #[clap(short = 'o', long = "output")]
+    output: Option<String>,
+
+    /// A verification key json (or `.zkey`) file and the address of its deployed
+    /// verifier, as `<PATH>=<ADDRESS>`, to print the calldata for registering the
+    /// verifier with. Repeat it for every verifier. The calldata is printed one call per
+    /// line, instead of the registry unless `--output` is designated.
+    #[clap(long = "register", value_parser)]
+    register: Vec<Registration>,
+}
+
+/// `huffv address` Args
+#[derive(Args, Debug)]
+struct AddressArgs {
+    /// The path to the creation code to deploy, as hex, or `-` to read it from stdin.
+    #[clap(long = "initcode")]
+    initcode: String,
+
+    /// The address of the `CREATE2` factory. [default: the deterministic deployment
+    /// proxy that Foundry deploys with, 0x4e59b44847b379578588920cA78FbF26c0B4956C]
+    #[clap(long = "factory", value_parser = parse_address)]
+    factory: Option<[u8; 20]>,
+
+    /// The salt, as a decimal or 0x prefixed hex number. With `--vanity`, the salt to
+    /// start mining from. [default: 0]
+    #[clap(long = "salt")]
+    salt: Option<String>,
+
+    /// Mine a salt for an address starting with these hex digits, and print it with
+    /// the address. Each digit takes 16 times longer to mine.
+    #[clap(long = "vanity")]
+    vanity: Option<String>,
+}
+
+/// A verifier to register with `huffv registry --register`.
+#[derive(Clone, Debug)]
+struct Registration {
+    /// The path to the verification key of the verifier.
+    vk: String,
+    /// The address of the deployed verifier.
+    verifier: [u8; 20],
+}
+
+impl std::str::FromStr for Registration {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (vk, verifier) = s
+            .rsplit_once('=')
+            .ok_or_else(|| format!("`{}` is not `<PATH>=<ADDRESS>`.", s))?;
+        Ok(Self {
+            vk: vk.to_string(),
+            verifier: parse_address(verifier)?,
+        })
+    }
+}
+
+/// Parses a 0x prefixed 20 byte address.
+fn parse_address(s: &str) -> Result<[u8; 20], String> {
+    s.strip_prefix("0x")
+        .and_then(|digits| hex::decode(digits).ok())
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| format!("`{}` is not a 0x prefixed 20 byte address.", s))
+}
+
+/// `huffv pipeline` Args
+#[derive(Args, Debug)]
+struct PipelineArgs {
+    /// The path to the circom circuit.
+    #[clap(long = "circuit")]
+    circuit: String,
+
+    /// The path to the powers of tau file to run the Groth16 setup with.
+    #[clap(long = "ptau")]
+    ptau: String,
+
+    /// The directory to save the R1CS, zkey, and verification key to.
+    #[clap(long = "build-dir", default_value = "build")]
+    build_dir: String,
+
+    /// If an output file is designated, the generator will save the verification
+    /// contract to a file instead of sending it to stdout.
+    #[clap(short = 'o', long = "output")]
+    output: Option<String>,
+
+    #[clap(flatten)]
+    verifier: VerifierArgs,
+}
+
+/// `huffv fixtures` Args
+#[derive(Args, Debug)]
+struct FixturesArgs {
+    /// The path to the circuit's zkey file.
+    #[clap(long = "zkey")]
+    zkey: String,
+
+    /// The path to a witness (`.wtns`) file of the circuit. Repeat it to prove several
+    /// witnesses, which are proven in turn.
+    #[clap(long = "witness", required = true)]
+    witness: Vec<String>,
+
+    /// The number of proofs to generate. Groth16 proofs are randomized, so proving the
+    /// same witness again yields another valid proof.
+    #[clap(long = "count", default_value = "1")]
+    count: usize,
+
+    /// The prover to run.
+    #[clap(long = "prover", value_enum, default_value = "snarkjs")]
+    prover: Prover,
+
+    /// The directory to write the fixtures to: `verification_key.json`, and
+    /// `proof_<i>.json` and `public_<i>.json` for each proof.
+    #[clap(short = 'o', long = "output-dir", default_value = "fixtures")]
+    output_dir: String,
+}
+
+/// The prover run by `huffv fixtures`.
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+enum Prover {
+    /// `snarkjs groth16 prove`.
+    Snarkjs,
+    /// rapidsnark's `prover`, which takes the same arguments.
+    Rapidsnark,
+}
+
+/// The capabilities of this build of `huffv`, for tooling that shells out to it.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Capabilities {
+    /// The crate version.
+    pub version: &'static str,
+    /// Supported verification key input formats.
+    pub input_formats: Vec<&'static str>,
+    /// Supported output languages.
+    pub output_langs: Vec<&'static str>,
+    /// Supported proof systems.
+    pub protocols: Vec<&'static str>,
+    /// Supported curves.
+    pub curves: Vec<&'static str>,
+    /// Supported EVM versions.
+    pub evm_versions: Vec<&'static str>,
+    /// Cargo features enabled at build time.
+    pub features: Vec<&'static str>,
+}
+
+impl Capabilities {
+    /// The capabilities of the running binary.
+    pub fn current() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION"),
+            input_formats: vec!["snarkjs", "arkworks"],
+            output_langs: vec!["huff", "solidity", "yul", "bytecode"],
+            protocols: vec!["groth16"],
+            curves: vec!["bn128"],
+            evm_versions: EvmVersion::value_variants()
+                .iter()
+                .map(EvmVersion::name)
+                .collect(),
+            features: [
+                ("ffi", cfg!(feature = "ffi")),
+                ("wasm", cfg!(feature = "wasm")),
+            ]
+            .into_iter()
+            .filter_map(|(feature, enabled)| enabled.then_some(feature))
+            .collect(),
+        }
+    }
+}
+
+impl fmt::Display for Capabilities {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "{}",
-            serde_json::to_string_pretty(self).expect("Failed to serialize verification key.")
-        )
+        writeln!(f, "huffv {}", self.version)?;
+        writeln!(f, "input formats: {}", self.input_formats.join(", "))?;
+        writeln!(f, "output langs:  {}", self.output_langs.join(", "))?;
+        writeln!(f, "protocols:     {}", self.protocols.join(", "))?;
+        writeln!(f, "curves:        {}", self.curves.join(", "))?;
+        writeln!(f, "evm versions:  {}", self.evm_versions.join(", "))?;
+        if self.features.is_empty() {
+            write!(f, "features:      none")
+        } else {
+            write!(f, "features:      {}", self.features.join(", "))
+        }
+    }
+}
+
+/// A generated verifier with the values it was generated with, for scripts that
+/// would otherwise scrape them from the contract.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Bundle {
+    /// The contract source.
+    pub contract: String,
+    /// The language of the contract.
+    pub target: Target,
+    /// The EVM version the contract targets.
+    pub evm_version: EvmVersion,
+    /// The packed verification key, as `0x`-prefixed hex.
+    pub packed_vkey: String,
+    /// The keccak256 hash of the packed verification key.
+    pub vkey_hash: String,
+    /// The memory layout of the verifier.
+    pub layout: BundleLayout,
+    /// The number of public inputs the verifier expects.
+    pub n_public: usize,
+    /// The curve the verifier operates on.
+    pub curve: Curve,
+    /// The crate version.
+    pub version: &'static str,
+}
+
+/// The memory layout constants of a [`Bundle`]'s verifier.
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleLayout {
+    /// The number of ICs in the verification key.
+    pub n_ics: usize,
+    /// The location of the first IC in memory.
+    pub ic_ptr: usize,
+    /// The location of the last IC in memory.
+    pub ic_ptr_last: usize,
+    /// The location of the `ecPairing` precompile inputs in memory.
+    pub pairing_input_ptr: usize,
+    /// The location of the proof in memory.
+    pub input_ptr: usize,
+    /// The location of the length of the public inputs in memory.
+    pub pub_input_len_ptr: usize,
+    /// The location of the public inputs in memory.
+    pub pub_input_ptr: usize,
+}
+
+impl Bundle {
+    /// Bundles the `contract` generated for `key` with `options`.
+    pub fn new(
+        key: &VerificationKey,
+        options: &VerifierOptions,
+        contract: String,
+    ) -> Result<Self, HuffvError> {
+        let layout = MemoryLayout::new(key.ic.len());
+        Ok(Self {
+            contract,
+            target: options.target,
+            evm_version: options.evm_version,
+            packed_vkey: key.to_packed()?,
+            vkey_hash: format!("0x{}", hex::encode(key.hash()?)),
+            layout: BundleLayout {
+                n_ics: layout.n_ics,
+                ic_ptr: MemoryLayout::IC_PTR,
+                ic_ptr_last: layout.ic_ptr_last(),
+                pairing_input_ptr: layout.pairing_input_ptr(),
+                input_ptr: layout.input_ptr(),
+                pub_input_len_ptr: layout.pub_input_len_ptr(),
+                pub_input_ptr: layout.pub_input_ptr(),
+            },
+            n_public: key.ic.len().saturating_sub(1),
+            curve: key.curve,
+            version: env!("CARGO_PKG_VERSION"),
+        })
+    }
+}
+
+/// Default options, loaded from a `huffv.toml` config file.
+///
+/// Every field mirrors a CLI flag of the same name. Flags passed explicitly on
+/// the command line always take precedence over values in the config file.
+#[derive(Deserialize, Debug, Default)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct Config {
+    /// The default for `--target`.
+    pub target: Option<Target>,
+
+    /// The default for `--template`.
+    pub template: Option<String>,
+
+    /// The default for `--public-input-order`.
+    pub public_input_order: Option<PublicInputOrder>,
+
+    /// The default for `--ic-constant-position`.
+    pub ic_constant_position: Option<IcConstantPosition>,
+
+    /// The default for `--emit-event`.
+    pub emit_event: Option<bool>,
+
+    /// The default for `--event-signature`.
+    pub event_signature: Option<String>,
+
+    /// The default for `--event-inputs-hash`.
+    pub event_inputs_hash: Option<bool>,
+
+    /// The default for `--opt`.
+    pub opt: Option<Optimization>,
+
+    /// The default for `--msm`.
+    pub msm: Option<MsmStrategy>,
+
+    /// The default for `--evm-version`.
+    pub evm_version: Option<EvmVersion>,
+
+    /// The default for `--precompile-addresses`.
+    pub precompile_addresses: Option<PrecompileAddresses>,
+
+    /// The default for `--chain`.
+    pub chain: Option<Chain>,
+
+    /// The default for `--abi`, also used by `huffv calldata`.
+    pub abi: Option<Abi>,
+
+    /// The default for `--on-failure`.
+    pub on_failure: Option<OnFailure>,
+
+    /// The default for `--input-commitment`.
+    pub input_commitment: Option<InputCommitment>,
+
+    /// The default for `--compressed-proof`, also used by `huffv calldata`.
+    pub compressed_proof: Option<bool>,
+
+    /// The default for `--fn-name`, also used by `huffv calldata`.
+    pub fn_name: Option<String>,
+
+    /// The default for `--selector`, also used by `huffv calldata`.
+    pub selector: Option<Selector>,
+
+    /// The default for `--main`.
+    pub main: Option<bool>,
+
+    /// The default for `--emit`.
+    pub emit: Option<Emit>,
+
+    /// The default for `--vkey-storage`.
+    pub vkey_storage: Option<VkeyStorage>,
+
+    /// The default for `--normalize-points`.
+    pub normalize_points: Option<bool>,
+
+    /// The default for `--fingerprint`.
+    pub fingerprint: Option<bool>,
+
+    /// The default for `--erc165`.
+    pub erc165: Option<bool>,
+
+    /// The default for `--strict-subgroup-checks`.
+    pub strict_subgroup_checks: Option<bool>,
+
+    /// The default for `--check-proof-points`.
+    pub check_proof_points: Option<bool>,
+}
+
+impl Config {
+    /// Loads the config file at `path`, or `huffv.toml` in the current directory
+    /// if no path is given. A missing default config file is not an error.
+    pub fn load(path: Option<&str>) -> Result<Config, String> {
+        let path = match path {
+            Some(path) => Path::new(path),
+            None if Path::new(CONFIG_FILE_NAME).exists() => Path::new(CONFIG_FILE_NAME),
+            None => return Ok(Config::default()),
+        };
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Error reading config file {}: {}", path.display(), e))?;
+        toml::from_str(&contents)
+            .map_err(|e| format!("Error parsing config file {}: {}", path.display(), e))
     }
 }
 
 fn main() {
     let args = HuffVerifier::parse();
+    let level = match (args.quiet, args.verbose) {
+        (true, _) => Level::ERROR,
+        (false, 0) => Level::INFO,
+        (false, 1) => Level::DEBUG,
+        (false, _) => Level::TRACE,
+    };
+    // Logs go to stderr, so that a contract printed to stdout can still be piped.
+    // Dependencies (e.g. handlebars) only get to log warnings.
+    tracing_subscriber::fmt()
+        .with_max_level(Level::TRACE)
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal())
+        .with_target(false)
+        .without_time()
+        .finish()
+        .with(
+            Targets::new()
+                .with_target("huffv", level)
+                .with_target("huff_snark_verifier", level)
+                .with_default(Level::WARN.min(level)),
+        )
+        .init();
 
-    if let Some(path) = args.path {
-        let path = Path::new(&path);
-        if path.exists() {
-            match parse_verification_key(path) {
-                Ok(key) => {
-                    // Get number of ICs in the verification key
-                    let n_ics = key.ic.len();
-
-                    // Fill vkey table with packed verification key
-                    let mut contract =
-                        HUFF_VERIFIER_CONTRACT.replace("{{PACKED_VKEY}}", &key.to_packed());
-                    // Fill n_ics constant
-                    contract = contract.replace("{{N_ICS}}", &format!("0x{:02x}", n_ics));
-                    // Fill ic_bytes
-                    contract = contract.replace("{{IC_BYTES}}", &format!("0x{:02x}", n_ics * 0x40));
-
-                    // Fill pairing input offsets
-                    let pairing_input_offset = 0xC0 + n_ics * 0x40;
-                    (0..PI_OFFSET_BASES.len()).for_each(|i| {
-                        let tag = format!("{{{{pi_{}}}}}", i);
-                        contract = contract.replace(
-                            &tag,
-                            &format!("0x{:02x}", pairing_input_offset + PI_OFFSET_BASES[i]),
-                        );
-                    });
-
-                    // Fill public input offsets
-                    let input_ptr = pairing_input_offset + 0x300;
-                    // Fill pub_input_len_ptr constant
-                    contract = contract.replace(
-                        "{{PUB_INPUT_LEN_PTR}}",
-                        &format!("0x{:02x}", input_ptr + 0x100),
-                    );
-                    // Fill pub_input_ptr constant
-                    contract = contract
-                        .replace("{{PUB_INPUT_PTR}}", &format!("0x{:02x}", input_ptr + 0x120));
-                    (0..8).for_each(|i| {
-                        let tag = format!("{{{{in_{}}}}}", i);
-                        contract =
-                            contract.replace(&tag, &format!("0x{:02x}", input_ptr + i * 0x20));
-                    });
-
-                    // Write output to file or print it to stdout
-                    if let Some(output) = args.output {
-                        fs::write(&output, contract).expect("Failed to write contract to file.");
-                        println!("Saved generated contract to {}", output);
-                    } else {
-                        println!("{}", contract);
-                    }
-                }
-                Err(e) => eprintln!("{}", e),
+    if let Err(e) = run(args) {
+        error!("{}", e);
+        process::exit(1);
+    }
+}
+
+/// Runs the CLI with the parsed `args`.
+fn run(args: HuffVerifier) -> Result<(), Box<dyn Error>> {
+    let input_format = args.input_format.unwrap_or_default();
+    match args.command {
+        None => generate(args.generate, input_format),
+        Some(Command::Generate(generate_args)) => generate(*generate_args, input_format),
+        Some(Command::Calldata(calldata_args)) => calldata(calldata_args, input_format),
+        Some(Command::ScaffoldTest(scaffold_test_args)) => {
+            scaffold_test(scaffold_test_args, input_format)
+        }
+        Some(Command::Vectors(vectors_args)) => vectors(vectors_args, input_format),
+        Some(Command::Pipeline(pipeline_args)) => pipeline(pipeline_args),
+        Some(Command::Fixtures(fixtures_args)) => fixtures(fixtures_args),
+        Some(Command::Verify { vk, proof, public }) => verify(
+            Path::new(&vk),
+            Path::new(&proof),
+            Path::new(&public),
+            input_format,
+        ),
+        Some(Command::CommitInputs { inputs }) => commit_inputs(Path::new(&inputs)),
+        Some(Command::Registry(registry_args)) => registry(registry_args, input_format),
+        Some(Command::Address(address_args)) => address(address_args),
+        Some(Command::SetVkey { path }) => set_vkey(Path::new(&path), input_format),
+        Some(Command::DeployClone { path }) => deploy_clone(Path::new(&path), input_format),
+        Some(Command::Fingerprint { path, vk }) => {
+            fingerprint(Path::new(&path), vk.as_deref().map(Path::new), input_format)
+        }
+        Some(Command::Inspect { path }) => inspect(Path::new(&path), input_format),
+        Some(Command::GasReport { path, json }) => gas_report(Path::new(&path), json, input_format),
+        Some(Command::Bench(bench_args)) => bench(bench_args, input_format),
+        Some(Command::Simulate(simulate_args)) => simulate(simulate_args, input_format),
+        Some(Command::Deploy(deploy_args)) => deploy(deploy_args, input_format),
+        Some(Command::Compare(compare_args)) => compare(compare_args, input_format),
+        Some(Command::Difftest(difftest_args)) => difftest(difftest_args, input_format),
+        Some(Command::Size {
+            path,
+            wrapper,
+            verifier,
+            json,
+        }) => size(
+            Path::new(&path),
+            wrapper.as_deref().map(Path::new),
+            verifier,
+            json,
+            input_format,
+        ),
+        Some(Command::Package(package_args)) => package(package_args, input_format),
+        Some(Command::Unpackage { path, out_dir }) => {
+            unpackage(Path::new(&path), Path::new(&out_dir))
+        }
+        Some(Command::VerifyPackage { path }) => verify_package(Path::new(&path)).map(|_| ()),
+        Some(Command::Capabilities { json }) => capabilities(json),
+    }
+}
+
+/// `huffv generate`: generates a verifier and writes it to a file or stdout.
+fn generate(args: GenerateArgs, input_format: InputFormat) -> Result<(), Box<dyn Error>> {
+    if args.watch {
+        return watch(args, input_format);
+    }
+
+    let (verifier, normalization) = args.verifier.resolve()?;
+    let verify_after_generate = match args.verify_after_generate.as_deref() {
+        Some([proof, public]) => Some((
+            Proof::from_file(Path::new(proof))?,
+            PublicSignals::from_file(Path::new(public))?,
+        )),
+        _ => None,
+    };
+
+    let options = GenerateOptions {
+        verifier,
+        normalization,
+        artifacts: args.artifacts,
+        aggregator: args.aggregator,
+        vkey_data: args.vkey_data,
+        factory: args.factory,
+        disasm_out: args.disasm_out,
+        batch: args.batch,
+        cache_key_out: args.cache_key_out,
+        r1cs: args
+            .r1cs
+            .map(|path| parse_r1cs(Path::new(&path)))
+            .transpose()?,
+        format: args.format,
+        check: args.check,
+        input_format,
+        verify_after_generate,
+    };
+
+    if !args.circuits.is_empty() {
+        let path = args.path.ok_or("No file path provided!")?;
+        let paths = [vec![path], args.circuits].concat();
+        return generate_multi(&paths, args.output.as_deref(), &options);
+    }
+
+    // clap lets `--out-dir` through without `--input-dir` when a key path is given, as
+    // the path conflicts with `--input-dir`
+    match (args.input_dir, args.out_dir) {
+        (Some(input_dir), Some(out_dir)) => {
+            return generate_dir(
+                Path::new(&input_dir),
+                Path::new(&out_dir),
+                args.json_stream,
+                &options,
+            )
+        }
+        (None, Some(_)) => return Err("`--out-dir` requires `--input-dir`!".into()),
+        _ => {}
+    }
+
+    let path = args.path.ok_or("No file path provided!")?;
+    generate_one(
+        Path::new(&path),
+        args.output.as_deref(),
+        CONTRACT_NAME,
+        &options,
+    )
+}
+
+/// `huffv generate --watch`: generates a verifier, then regenerates it every time one of
+/// its inputs is modified. Failures are reported without exiting, so that a key caught
+/// mid-write by snarkjs is simply picked up again on its next change.
+fn watch(args: GenerateArgs, input_format: InputFormat) -> Result<(), Box<dyn Error>> {
+    let path = args.path.clone().ok_or("No file path provided!")?;
+    if path == STDIN_PATH {
+        return Err("Cannot watch a verification key read from stdin!".into());
+    }
+
+    // The template may come from the config file, so the config is resolved up front.
+    let config = Config::load(args.verifier.config.as_deref())?;
+    let mut paths = vec![path];
+    paths.extend(args.verifier.template.clone().or(config.template));
+    paths.extend(args.r1cs.clone());
+    paths.extend(args.verifier.selftest_proof.clone());
+    paths.extend(args.verifier.selftest_public.clone());
+    paths.extend(args.verify_after_generate.clone().unwrap_or_default());
+    paths.push(
+        args.verifier
+            .config
+            .clone()
+            .unwrap_or_else(|| CONFIG_FILE_NAME.to_string()),
+    );
+
+    let mut last_modified: Option<Vec<Option<SystemTime>>> = None;
+    loop {
+        // A missing file has no modification time, so it changes when it appears or vanishes
+        let modified = paths
+            .iter()
+            .map(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+            .collect::<Vec<_>>();
+        if last_modified.as_ref() != Some(&modified) {
+            if let Err(e) = generate(
+                GenerateArgs {
+                    watch: false,
+                    ..args.clone()
+                },
+                input_format,
+            ) {
+                error!("{}", e);
             }
+            info!("Watching {} for changes...", paths.join(", "));
+            last_modified = Some(modified);
+        }
+        thread::sleep(WATCH_INTERVAL);
+    }
+}
+
+/// `huffv generate --input-dir`: generates a verifier for every `*.json` / `*.zkey`
+/// verification key in `input_dir`, named after the key, and prints a summary. With
+/// `json_stream`, the result of each key is also printed as a line of JSON.
+fn generate_dir(
+    input_dir: &Path,
+    out_dir: &Path,
+    json_stream: bool,
+    options: &GenerateOptions,
+) -> Result<(), Box<dyn Error>> {
+    let mut paths = fs::read_dir(input_dir)
+        .map_err(|e| HuffvError::io(input_dir, e))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext == "json" || ext == "zkey")
+        })
+        .collect::<Vec<_>>();
+    paths.sort();
+    fs::create_dir_all(out_dir).map_err(|e| HuffvError::io(out_dir, e))?;
+
+    let mut failures = 0;
+    for path in &paths {
+        let name = path.file_stem().unwrap_or_default().to_string_lossy();
+        let extension = match options.verifier.emit {
+            Emit::Initcode => "hex",
+            _ => options.verifier.target.extension(),
+        };
+        let extension = match options.format {
+            OutputFormat::Text => extension.to_string(),
+            // Keep bundles apart from the keys, in case `out_dir` is `input_dir`
+            OutputFormat::Json => format!("{}.json", extension),
+        };
+        let output = out_dir.join(format!("{}.{}", name, extension));
+        let result = generate_one(path, output.to_str(), &name, options);
+        if let Err(e) = &result {
+            error!(
+                "Failed to generate a verifier for {}: {}",
+                path.display(),
+                e
+            );
+            failures += 1;
+        }
+        if json_stream {
+            let result = GenerationResult {
+                key: path.display().to_string(),
+                output: output.display().to_string(),
+                error: result.err().map(|e| e.to_string()),
+            };
+            // Flushed line by line, so that each result reaches the consumer right away
+            let mut stdout = io::stdout().lock();
+            writeln!(stdout, "{}", serde_json::to_string(&result)?)?;
+            stdout.flush()?;
+        }
+    }
+
+    info!(
+        "Generated {} of {} verifiers.",
+        paths.len() - failures,
+        paths.len()
+    );
+    if failures > 0 {
+        return Err(format!("{} verification key(s) failed!", failures).into());
+    }
+    Ok(())
+}
+
+/// `huffv generate --circuit`: generates a single verifier for the verification keys at
+/// `paths`, by circuit id, and writes it to `output` or stdout.
+fn generate_multi(
+    paths: &[String],
+    output: Option<&str>,
+    options: &GenerateOptions,
+) -> Result<(), Box<dyn Error>> {
+    if options.format == OutputFormat::Json {
+        return Err("A multi-circuit verifier has no JSON bundle, as it has no single key.".into());
+    }
+    let mut keys = Vec::with_capacity(paths.len());
+    for path in paths {
+        let mut key = read_verification_key(Path::new(path), options.input_format)?;
+        debug!(
+            "Read circuit {} with {} public inputs from {}",
+            keys.len(),
+            key.n_public,
+            path
+        );
+        options.normalization.apply(&mut key)?;
+        keys.push(key);
+    }
+
+    let contract = generate_multi_verifier(&keys, &options.verifier)?;
+    if options.check {
+        info!(
+            "The verifier for {} circuits passed all checks.",
+            keys.len()
+        );
+        return Ok(());
+    }
+    if let Some(output) = output {
+        fs::write(output, contract).map_err(|e| HuffvError::io(output, e))?;
+        info!("Saved generated contract to {}", output);
+    } else {
+        println!("{}", contract);
+    }
+    Ok(())
+}
+
+/// Generates a verifier for the verification key at `path`, and writes it to `output`
+/// or stdout. Its artifact, if requested, is saved as `<artifact_name>.json`.
+fn generate_one(
+    path: &Path,
+    output: Option<&str>,
+    artifact_name: &str,
+    options: &GenerateOptions,
+) -> Result<(), Box<dyn Error>> {
+    let mut key = read_verification_key(path, options.input_format)?;
+    debug!(
+        "Read a {:?} / {:?} verification key with {} public inputs from {}",
+        key.protocol,
+        key.curve,
+        key.n_public,
+        path.display()
+    );
+    if let Some(r1cs) = &options.r1cs {
+        debug!("Checking the key against {:?}", r1cs);
+        r1cs.check_key(&key)?;
+    }
+
+    // Move the constant term to the front of the ICs, and make the G1 points affine
+    options.normalization.apply(&mut key)?;
+
+    // Generation fails on a broken contract, so reaching this point passes the checks
+    let mut contract = match options.batch {
+        true => generate_batch_verifier(&key, &options.verifier)?,
+        false => generate_verifier_with(&key, &options.verifier)?,
+    };
+
+    // Nothing is written unless the key verifies the known-good proof
+    if let Some((proof, public)) = &options.verify_after_generate {
+        if !verify_proof(&key, proof, public)? {
+            return Err(format!(
+                "The verification key of {} rejects the proof of `--verify-after-generate`!",
+                path.display()
+            )
+            .into());
+        }
+        info!("The verification key verifies the proof of `--verify-after-generate`.");
+    }
+    if options.check {
+        info!("The verifier for {} passed all checks.", path.display());
+        return Ok(());
+    }
+
+    // Write the artifact next to the contract
+    if let Some(dir) = &options.artifacts {
+        let source_name = match output {
+            Some(output) => output.to_string(),
+            None => format!("{}.{}", CONTRACT_NAME, options.verifier.target.extension()),
+        };
+        let artifact = Artifact::new(&key, &options.verifier, &source_name)?;
+        let path = Path::new(dir).join(format!("{}.json", artifact_name));
+        fs::create_dir_all(dir).map_err(|e| HuffvError::io(dir, e))?;
+        fs::write(&path, serde_json::to_string_pretty(&artifact)?)
+            .map_err(|e| HuffvError::io(&path, e))?;
+        info!("Saved artifact to {}", path.display());
+    }
+
+    // Write the aggregator next to the contract
+    if let Some(aggregator) = &options.aggregator {
+        if options.verifier.target == Target::Huff && !options.verifier.main {
+            warn!("The aggregator calls the verifier directly, so the Huff verifier needs `--main` to be deployed on its own.");
+        }
+        fs::write(aggregator, generate_aggregator(&key, &options.verifier)?)
+            .map_err(|e| HuffvError::io(aggregator, e))?;
+        info!("Saved aggregator to {}", aggregator);
+    }
+
+    // Write the creation code of the key's data contract next to the contract
+    if let Some(vkey_data) = &options.vkey_data {
+        let initcode = format!("0x{}", hex::encode(vkey_data_initcode(&key)?));
+        fs::write(vkey_data, initcode).map_err(|e| HuffvError::io(vkey_data, e))?;
+        info!("Saved the key's data contract to {}", vkey_data);
+    } else if options.verifier.vkey_storage == VkeyStorage::External {
+        warn!("The verifier is deployed with the address of the key's data contract. Pass `--vkey-data` to save its creation code.");
+    }
+
+    // Write the clone factory next to the contract
+    if let Some(factory) = &options.factory {
+        fs::write(factory, generate_factory(&key, &options.verifier)?)
+            .map_err(|e| HuffvError::io(factory, e))?;
+        info!("Saved clone factory to {}", factory);
+    }
+
+    // Write the disassembly of the runtime code next to the contract
+    if let Some(disasm_out) = &options.disasm_out {
+        fs::write(disasm_out, annotated_disassembly(&key, &options.verifier)?)
+            .map_err(|e| HuffvError::io(disasm_out, e))?;
+        info!("Saved the annotated disassembly to {}", disasm_out);
+    }
+
+    // Write the cache key of the verifier next to the contract
+    if let Some(cache_key_out) = &options.cache_key_out {
+        let cache_key = Fingerprint::new(&key, &contract)?.cache_key();
+        fs::write(cache_key_out, format!("0x{}\n", hex::encode(cache_key)))
+            .map_err(|e| HuffvError::io(cache_key_out, e))?;
+        info!("Saved the cache key to {}", cache_key_out);
+    }
+
+    if options.format == OutputFormat::Json {
+        if options.batch {
+            return Err("A batch verifier has no JSON bundle, as it has its own ABI.".into());
+        }
+        let bundle = Bundle::new(&key, &options.verifier, contract)?;
+        contract = serde_json::to_string_pretty(&bundle)?;
+    }
+
+    // Write output to file or print it to stdout
+    if let Some(output) = output {
+        fs::write(output, contract).map_err(|e| HuffvError::io(output, e))?;
+        info!("Saved generated contract to {}", output);
+    } else {
+        println!("{}", contract);
+    }
+
+    Ok(())
+}
+
+/// `huffv calldata`: prints the calldata for verifying a proof with the generated verifier.
+fn calldata(args: CalldataArgs, input_format: InputFormat) -> Result<(), Box<dyn Error>> {
+    let (options, normalization) = args.verifier.resolve()?;
+
+    if !args.batch.is_empty() {
+        let proofs = args
+            .batch
+            .chunks(2)
+            .map(|paths| {
+                Ok((
+                    Proof::from_file(Path::new(&paths[0]))?,
+                    PublicSignals::from_file(Path::new(&paths[1]))?,
+                ))
+            })
+            .collect::<Result<Vec<_>, HuffvError>>()?;
+        println!("{}", encode_batch_calldata(&proofs)?);
+        return Ok(());
+    }
+
+    let proof = Proof::from_file(Path::new(&args.proof.ok_or("No proof path provided!")?))?;
+    let public = PublicSignals::from_file(Path::new(
+        &args.public.ok_or("No public signals path provided!")?,
+    ))?;
+    let calldata = match (args.vk, args.circuit_id) {
+        (Some(vk), _) => {
+            let key = normalization.read_key(Path::new(&vk), input_format)?;
+            encode_calldata_with_key(&key, &proof, &public, &options)?
+        }
+        (None, Some(circuit_id)) => {
+            encode_calldata_for_circuit(circuit_id, &proof, &public, &options)?
+        }
+        (None, None) => encode_calldata_with(&proof, &public, &options)?,
+    };
+    println!("{}", calldata);
+
+    Ok(())
+}
+
+/// `huffv scaffold-test`: generates a Foundry test and writes it to a file or stdout.
+fn scaffold_test(args: ScaffoldTestArgs, input_format: InputFormat) -> Result<(), Box<dyn Error>> {
+    let (options, normalization) = args.verifier.resolve()?;
+
+    let key = normalization.read_key(Path::new(&args.vk), input_format)?;
+    let proof = Proof::from_file(Path::new(&args.proof))?;
+    let public = PublicSignals::from_file(Path::new(&args.public))?;
+    let test = generate_test_scaffold(
+        &key,
+        &proof,
+        &public,
+        options.public_input_order,
+        &args.verifier_path,
+        args.fuzz,
+    )?;
+
+    // Write output to file or print it to stdout
+    if let Some(output) = args.output {
+        fs::write(&output, test).map_err(|e| HuffvError::io(&output, e))?;
+        info!("Saved generated test to {}", output);
+    } else {
+        println!("{}", test);
+    }
+
+    Ok(())
+}
+
+/// `huffv vectors`: generates test vectors and writes their manifest to a file or stdout.
+fn vectors(args: VectorsArgs, input_format: InputFormat) -> Result<(), Box<dyn Error>> {
+    let (options, normalization) = args.verifier.resolve()?;
+
+    let proof = Proof::from_file(Path::new(&args.proof))?;
+    let public = PublicSignals::from_file(Path::new(&args.public))?;
+    let key = args
+        .vk
+        .map(|vk| normalization.read_key(Path::new(&vk), input_format))
+        .transpose()?;
+    let vectors = generate_test_vectors(&proof, &public, &options, key.as_ref(), args.negative)?;
+    let manifest = serde_json::to_string_pretty(&vectors)?;
+
+    // Write output to file or print it to stdout
+    if let Some(output) = args.output {
+        fs::write(&output, manifest).map_err(|e| HuffvError::io(&output, e))?;
+        info!("Saved {} test vectors to {}", vectors.vectors.len(), output);
+    } else {
+        println!("{}", manifest);
+    }
+
+    Ok(())
+}
+
+/// `huffv pipeline`: compiles a circuit with circom, runs the Groth16 setup and
+/// exports its verification key with snarkjs, then generates its verifier.
+fn pipeline(args: PipelineArgs) -> Result<(), Box<dyn Error>> {
+    let circuit = Path::new(&args.circuit);
+    let name = circuit
+        .file_stem()
+        .ok_or("The circuit path has no file name!")?
+        .to_string_lossy();
+    let build_dir = Path::new(&args.build_dir);
+    fs::create_dir_all(build_dir).map_err(|e| HuffvError::io(build_dir, e))?;
+
+    let r1cs = build_dir.join(format!("{}.r1cs", name));
+    let zkey = build_dir.join(format!("{}_final.zkey", name));
+    let vk = build_dir.join("verification_key.json");
+
+    run_tool(
+        CIRCOM_BIN,
+        &[
+            circuit.as_os_str(),
+            "--r1cs".as_ref(),
+            "-o".as_ref(),
+            build_dir.as_os_str(),
+        ],
+    )?;
+    run_tool(
+        SNARKJS_BIN,
+        &[
+            "groth16".as_ref(),
+            "setup".as_ref(),
+            r1cs.as_os_str(),
+            args.ptau.as_ref(),
+            zkey.as_os_str(),
+        ],
+    )?;
+    run_tool(
+        SNARKJS_BIN,
+        &[
+            "zkey".as_ref(),
+            "export".as_ref(),
+            "verificationkey".as_ref(),
+            zkey.as_os_str(),
+            vk.as_os_str(),
+        ],
+    )?;
+
+    // Generate the verifier, checking the key against the circuit it came from
+    generate(
+        GenerateArgs {
+            path: Some(vk.to_string_lossy().into_owned()),
+            output: args.output,
+            r1cs: Some(r1cs.to_string_lossy().into_owned()),
+            verifier: args.verifier,
+            ..GenerateArgs::default()
+        },
+        InputFormat::Snarkjs,
+    )
+}
+
+/// `huffv fixtures`: proves the witnesses `count` times, and writes the proofs, their
+/// public signals, and the verification key to the output directory. Each proof is
+/// verified off-chain before it is kept.
+fn fixtures(args: FixturesArgs) -> Result<(), Box<dyn Error>> {
+    let zkey = Path::new(&args.zkey);
+    let output_dir = Path::new(&args.output_dir);
+    fs::create_dir_all(output_dir).map_err(|e| HuffvError::io(output_dir, e))?;
+
+    let key = read_verification_key(zkey, InputFormat::Snarkjs)?;
+    let vk = output_dir.join("verification_key.json");
+    fs::write(&vk, key.to_string()).map_err(|e| HuffvError::io(&vk, e))?;
+
+    for (i, witness) in args.witness.iter().cycle().take(args.count).enumerate() {
+        let proof = output_dir.join(format!("proof_{}.json", i));
+        let public = output_dir.join(format!("public_{}.json", i));
+        let (bin, command): (&str, &[&OsStr]) = match args.prover {
+            Prover::Snarkjs => (SNARKJS_BIN, &["groth16".as_ref(), "prove".as_ref()]),
+            Prover::Rapidsnark => (RAPIDSNARK_BIN, &[]),
+        };
+        let mut prover_args = command.to_vec();
+        prover_args.extend([
+            zkey.as_os_str(),
+            witness.as_ref(),
+            proof.as_os_str(),
+            public.as_os_str(),
+        ]);
+        run_tool(bin, &prover_args)?;
+
+        if !verify_proof(
+            &key,
+            &Proof::from_file(&proof)?,
+            &PublicSignals::from_file(&public)?,
+        )? {
+            return Err(format!("`{}` generated an invalid proof for {}!", bin, witness).into());
+        }
+    }
+    info!(
+        "Saved {} proofs and their verification key to {}",
+        args.count,
+        output_dir.display()
+    );
+
+    Ok(())
+}
+
+/// `huffv verify`: verifies a proof off-chain, failing if it is invalid.
+fn verify(
+    vk: &Path,
+    proof: &Path,
+    public: &Path,
+    input_format: InputFormat,
+) -> Result<(), Box<dyn Error>> {
+    let key = read_verification_key(vk, input_format)?;
+    let proof = Proof::from_file(proof)?;
+    let public = PublicSignals::from_file(public)?;
+
+    if verify_proof(&key, &proof, &public)? {
+        println!("Proof is valid.");
+        Ok(())
+    } else {
+        Err("Proof is invalid!".into())
+    }
+}
+
+/// `huffv commit-inputs`: prints the keccak256 commitment to the raw inputs at `inputs`.
+fn commit_inputs(inputs: &Path) -> Result<(), Box<dyn Error>> {
+    let commitment = PublicSignals::from_file(inputs)?.keccak_commitment()?;
+    println!("{}", serde_json::to_string_pretty(&commitment)?);
+    Ok(())
+}
+
+/// `huffv registry`: generates the verifier registry and writes it to a file or stdout,
+/// or prints the calldata for registering verifiers with it.
+fn registry(args: RegistryArgs, input_format: InputFormat) -> Result<(), Box<dyn Error>> {
+    let registry = generate_registry()?;
+    if let Some(output) = &args.output {
+        fs::write(output, &registry).map_err(|e| HuffvError::io(output, e))?;
+        info!("Saved generated registry to {}", output);
+    } else if args.register.is_empty() {
+        println!("{}", registry);
+    }
+
+    for registration in &args.register {
+        let key = read_verification_key(Path::new(&registration.vk), input_format)?;
+        println!(
+            "{}",
+            encode_register_calldata(&key, &registration.verifier)?
+        );
+    }
+    Ok(())
+}
+
+/// `huffv address`: prints the `CREATE2` address of the creation code at `args.initcode`,
+/// or mines a salt for a vanity address and prints both.
+fn address(args: AddressArgs) -> Result<(), Box<dyn Error>> {
+    let path = Path::new(&args.initcode);
+    let hex_code = if path == Path::new(STDIN_PATH) {
+        let mut data = String::new();
+        io::stdin()
+            .read_to_string(&mut data)
+            .map_err(|e| HuffvError::io("stdin", e))?;
+        data
+    } else {
+        fs::read_to_string(path).map_err(|e| HuffvError::io(path, e))?
+    };
+    let hex_code = hex_code.trim();
+    let initcode = hex::decode(hex_code.strip_prefix("0x").unwrap_or(hex_code))
+        .map_err(|e| format!("The initcode is not hex: {}.", e))?;
+
+    let factory = args.factory.unwrap_or(DETERMINISTIC_DEPLOYER);
+    let salt: [u8; 32] = hex::decode(encode_num(args.salt.as_deref().unwrap_or("0"))?)?
+        .try_into()
+        .expect("A word is 32 bytes.");
+    match args.vanity {
+        Some(prefix) => {
+            info!("Mining a salt for an address starting with {}...", prefix);
+            let (salt, address) = mine_create2_salt(&factory, &salt, &initcode, &prefix)?;
+            println!("Salt:    0x{}", hex::encode(salt));
+            println!("Address: {}", checksum_address(&address));
+        }
+        None => println!(
+            "{}",
+            checksum_address(&create2_address(&factory, &salt, &initcode))
+        ),
+    }
+    Ok(())
+}
+
+/// `huffv set-vkey`: prints the calldata for setting the key at `path` as the verification
+/// key of a verifier generated with `--vkey-storage storage`.
+fn set_vkey(path: &Path, input_format: InputFormat) -> Result<(), Box<dyn Error>> {
+    println!(
+        "{}",
+        encode_set_vkey_calldata(&read_verification_key(path, input_format)?)?
+    );
+    Ok(())
+}
+
+/// `huffv deploy-clone`: prints the calldata for deploying a clone of a verifier for the
+/// key at `path` with the factory generated with `--factory`.
+fn deploy_clone(path: &Path, input_format: InputFormat) -> Result<(), Box<dyn Error>> {
+    println!(
+        "{}",
+        encode_deploy_clone_calldata(&read_verification_key(path, input_format)?)?
+    );
+    Ok(())
+}
+
+/// `huffv fingerprint`: prints the fingerprint recorded in the trailer of the verifier at
+/// `path`, failing if the verifier or the key at `vk` don't match it.
+fn fingerprint(
+    path: &Path,
+    vk: Option<&Path>,
+    input_format: InputFormat,
+) -> Result<(), Box<dyn Error>> {
+    let contract = fs::read_to_string(path).map_err(|e| HuffvError::io(path, e))?;
+    let (recorded, source) = Fingerprint::from_contract(&contract)?;
+    println!("{}", recorded);
+
+    let source_hash = format!("0x{}", hex::encode(keccak256(source.as_bytes())));
+    if source_hash != recorded.source_hash {
+        return Err(format!(
+            "The verifier was modified after it was generated: its source hash is {}.",
+            source_hash
+        )
+        .into());
+    }
+    if let Some(vk) = vk {
+        let vkey_hash = format!(
+            "0x{}",
+            hex::encode(key_fingerprint(&read_verification_key(vk, input_format)?)?)
+        );
+        if vkey_hash != recorded.vkey_hash {
+            return Err(format!(
+                "The verifier was not generated from {}: its key hash is {}.",
+                vk.display(),
+                vkey_hash
+            )
+            .into());
+        }
+    }
+
+    println!();
+    match vk {
+        Some(vk) => println!(
+            "The verifier is unmodified, and was generated from {}.",
+            vk.display()
+        ),
+        None => println!("The verifier is unmodified."),
+    }
+    Ok(())
+}
+
+/// `huffv inspect`: prints a summary of a verification key and its verifier's memory layout.
+fn inspect(path: &Path, input_format: InputFormat) -> Result<(), Box<dyn Error>> {
+    let key = read_verification_key(path, input_format)?;
+    let packed = key.to_packed()?;
+    let layout = MemoryLayout::new(key.ic.len());
+
+    println!("nPublic:           {}", key.n_public);
+    println!("ICs:               {}", layout.n_ics);
+    println!("Packed key size:   0x{:x} bytes", (packed.len() - 2) / 2);
+    println!("Packed key hash:   0x{}", hex::encode(key.hash()?));
+    println!();
+    println!("Memory layout:");
+    println!(
+        "  ICs:             [0x{:02x}:0x{:02x}]",
+        MemoryLayout::IC_PTR,
+        layout.pairing_input_ptr()
+    );
+    println!(
+        "  Pairing inputs:  [0x{:02x}:0x{:02x}]",
+        layout.pairing_input_ptr(),
+        layout.input_ptr()
+    );
+    println!(
+        "  Proof:           [0x{:02x}:0x{:02x}]",
+        layout.input_ptr(),
+        layout.pub_input_len_ptr()
+    );
+    println!(
+        "  Public inputs:   0x{:02x} (length), 0x{:02x} (inputs)",
+        layout.pub_input_len_ptr(),
+        layout.pub_input_ptr()
+    );
+
+    Ok(())
+}
+
+/// `huffv gas-report`: prints the expected gas of verification, per stage.
+fn gas_report(path: &Path, json: bool, input_format: InputFormat) -> Result<(), Box<dyn Error>> {
+    let key = read_verification_key(path, input_format)?;
+    let report = GasReport::new(&MemoryLayout::new(key.ic.len()));
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{}", report);
+    }
+    Ok(())
+}
+
+/// `huffv bench`: deploys the verifier under revm and prints the gas of verifying the
+/// proof with it, or of verifying the proofs of synthetic keys with `--sweep`.
+fn bench(args: BenchArgs, input_format: InputFormat) -> Result<(), Box<dyn Error>> {
+    let (options, normalization) = args.verifier.resolve()?;
+
+    let Some(max_inputs) = args.sweep else {
+        let key =
+            normalization.read_key(Path::new(&args.path.unwrap_or_default()), input_format)?;
+        let proof = Proof::from_file(Path::new(&args.proof.unwrap_or_default()))?;
+        let public = PublicSignals::from_file(Path::new(&args.public.unwrap_or_default()))?;
+        let report = bench_verifier(&key, &options, &proof, &public, args.runs)?;
+        if args.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
         } else {
-            eprintln!("File does not exist!");
+            println!("{}", report);
         }
+        return Ok(());
+    };
+
+    let reports = (1..=max_inputs)
+        .map(|n_inputs| {
+            let (key, proof, public) = synthetic_circuit(n_inputs);
+            info!(
+                "Benchmarking a synthetic key with {} public inputs",
+                n_inputs
+            );
+            bench_verifier(&key, &options, &proof, &public, args.runs)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&reports)?);
+        return Ok(());
+    }
+    println!("| INPUTS | CODE SIZE | MIN      | MEDIAN   | EXECUTION | PER INPUT |");
+    println!("| ------ | --------- | -------- | -------- | --------- | --------- |");
+    let mut previous = None;
+    for report in &reports {
+        let per_input = previous
+            .map(|previous| (report.median as i64 - previous as i64).to_string())
+            .unwrap_or_default();
+        println!(
+            "| {:>6} | {:>9} | {:>8} | {:>8} | {:>9} | {:>9} |",
+            report.n_inputs,
+            report.code_size,
+            report.min,
+            report.median,
+            report.execution(),
+            per_input
+        );
+        previous = Some(report.median);
+    }
+    Ok(())
+}
+
+/// `huffv simulate`: deploys the verifier under revm, calls it once with the proof, and
+/// prints whether it verified it and the gas of the call. Fails unless it verified it.
+fn simulate(args: SimulateArgs, input_format: InputFormat) -> Result<(), Box<dyn Error>> {
+    let (options, normalization) = args.verifier.resolve()?;
+    let key = normalization.read_key(Path::new(&args.vk), input_format)?;
+    let proof = Proof::from_file(Path::new(&args.proof))?;
+    let public = PublicSignals::from_file(Path::new(&args.public))?;
+
+    let simulation = simulate_verifier(&key, &options, &proof, &public)?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&simulation)?);
+    } else {
+        println!("{}", simulation);
+    }
+    if !simulation.verified() {
+        return Err("The verifier did not verify the proof!".into());
+    }
+    Ok(())
+}
+
+/// `huffv deploy`: deploys the verifier through a JSON-RPC node, optionally verifying a
+/// proof with it right after.
+fn deploy(args: DeployArgs, input_format: InputFormat) -> Result<(), Box<dyn Error>> {
+    let (options, normalization) = args.verifier.resolve()?;
+    let private_key = match args.private_key {
+        Some(private_key) => private_key,
+        None => env::var(PRIVATE_KEY_VAR).map_err(|_| {
+            format!(
+                "Pass `--private-key`, or set the `{}` environment variable!",
+                PRIVATE_KEY_VAR
+            )
+        })?,
+    };
+    let key = normalization.read_key(Path::new(&args.path), input_format)?;
+    let test_proof = match args.verify_after.as_deref() {
+        Some([proof, public]) => Some((
+            Proof::from_file(Path::new(proof))?,
+            PublicSignals::from_file(Path::new(public))?,
+        )),
+        _ => None,
+    };
+
+    let deployment = deploy_verifier(
+        &args.rpc_url,
+        &private_key,
+        &key,
+        &options,
+        test_proof.as_ref().map(|(proof, public)| (proof, public)),
+    )?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&deployment)?);
+    } else {
+        println!("{}", deployment);
+    }
+    if deployment.verified == Some(false) {
+        return Err("The deployed verifier did not verify the proof of `--verify-after`!".into());
+    }
+    Ok(())
+}
+
+/// `huffv compare`: measures the Huff verifier and the snarkjs Solidity verifier of a
+/// key under revm, and prints their gas and code sizes side by side.
+fn compare(args: CompareArgs, input_format: InputFormat) -> Result<(), Box<dyn Error>> {
+    let (options, normalization) = args.verifier.resolve()?;
+
+    let key = normalization.read_key(Path::new(&args.path), input_format)?;
+    let proof = Proof::from_file(Path::new(&args.proof))?;
+    let public = PublicSignals::from_file(Path::new(&args.public))?;
+    let huff = bench_verifier(&key, &options, &proof, &public, args.runs)?;
+
+    let initcode = compile_snarkjs_verifier(&key, options.evm_version)?;
+
+    let calldata = encode_calldata_with(
+        &proof,
+        &public,
+        &VerifierOptions {
+            abi: Abi::Snarkjs,
+            ..VerifierOptions::default()
+        },
+    )?;
+    let solidity = bench_initcode(
+        &initcode,
+        &hex::decode(&calldata[2..])?,
+        options.evm_version,
+        huff.n_inputs,
+        args.runs,
+    )?;
+
+    let report = ComparisonReport { huff, solidity };
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{}", report);
+    }
+    Ok(())
+}
+
+/// `huffv size`: compiles the Huff verifier of the key at `path` for `evm_version` with
+/// `wrapper` (or its own `MAIN`), and prints its code size report.
+fn size(
+    path: &Path,
+    wrapper: Option<&Path>,
+    verifier: VerifierArgs,
+    json: bool,
+    input_format: InputFormat,
+) -> Result<(), Box<dyn Error>> {
+    let (options, normalization) = verifier.resolve()?;
+    let key = normalization.read_key(path, input_format)?;
+    let layout = MemoryLayout::new(key.ic.len());
+    let wrapper = wrapper
+        .map(|wrapper| fs::read_to_string(wrapper).map_err(|e| HuffvError::io(wrapper, e)))
+        .transpose()?;
+    // Without a wrapper, the verifier's own `MAIN` dispatches `verify` and `VKEY_HASH`
+    let options = VerifierOptions {
+        main: wrapper.is_none(),
+        emit: Emit::Contract,
+        ..options
+    };
+    let contract = generate_verifier_with(&key, &options)? + &wrapper.unwrap_or_default();
+
+    let runtime = assemble_runtime_code(&contract, options.evm_version)?;
+    debug!("Assembled the verifier to 0x{}", hex::encode(&runtime));
+
+    let report = SizeReport::new(&layout, runtime.len());
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("{}", report);
+    }
+    if report.headroom() < 0 {
+        return Err(format!(
+            "The verifier is {} bytes over the EIP-170 limit!",
+            -report.headroom()
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// `huffv package`: bundles the Huff verifier of a key with the key into a package file.
+fn package(args: PackageArgs, input_format: InputFormat) -> Result<(), Box<dyn Error>> {
+    let (options, normalization) = args.verifier.resolve()?;
+    let key = normalization.read_key(Path::new(&args.path), input_format)?;
+
+    let package = Package::new(&key, &options)?;
+    fs::write(&args.output, serde_json::to_string_pretty(&package)?)
+        .map_err(|e| HuffvError::io(&args.output, e))?;
+    println!("{}", package);
+    info!("Saved the package to {}", args.output);
+    Ok(())
+}
+
+/// `huffv unpackage`: checks the integrity of a package, and extracts its files and
+/// manifest to `out_dir`.
+fn unpackage(path: &Path, out_dir: &Path) -> Result<(), Box<dyn Error>> {
+    let package = verify_package(path)?;
+    fs::create_dir_all(out_dir).map_err(|e| HuffvError::io(out_dir, e))?;
+    let manifest = serde_json::to_string_pretty(&package.manifest)?;
+    for (name, contents) in package
+        .files
+        .iter()
+        .chain([(&MANIFEST_FILE.to_string(), &manifest)])
+    {
+        // Names come from the package, so keep them inside `out_dir`
+        if Path::new(name).file_name() != Some(name.as_ref()) {
+            return Err(
+                format!("The package has a file outside its directory: `{}`!", name).into(),
+            );
+        }
+        let file = out_dir.join(name);
+        fs::write(&file, contents).map_err(|e| HuffvError::io(&file, e))?;
+        info!("Extracted {}", file.display());
+    }
+    Ok(())
+}
+
+/// `huffv verify-package`: checks the integrity of a package, failing with its problems
+/// if it isn't intact.
+fn verify_package(path: &Path) -> Result<Package, Box<dyn Error>> {
+    let data = fs::read(path).map_err(|e| HuffvError::io(path, e))?;
+    let package = Package::from_bytes(&data)?;
+    println!("{}", package);
+    println!();
+    let problems = package.problems();
+    if !problems.is_empty() {
+        return Err(format!(
+            "The package was modified after it was written:\n{}",
+            problems.join("\n")
+        )
+        .into());
+    }
+    println!("The package is intact.");
+    Ok(package)
+}
+
+/// `huffv capabilities`: prints what this build of `huffv` supports.
+fn capabilities(json: bool) -> Result<(), Box<dyn Error>> {
+    let capabilities = Capabilities::current();
+    if json {
+        println!("{}", serde_json::to_string_pretty(&capabilities)?);
+    } else {
+        println!("{}", capabilities);
+    }
+    Ok(())
+}
+
+/// `huffv difftest`: runs a proof and its mutations through the Huff verifier and the
+/// snarkjs Solidity verifier of a key under revm, prints what each did with them, and
+/// fails if either disagrees with the off-chain verification.
+fn difftest(args: DifftestArgs, input_format: InputFormat) -> Result<(), Box<dyn Error>> {
+    let (options, normalization) = args.verifier.resolve()?;
+
+    let key = normalization.read_key(Path::new(&args.path), input_format)?;
+    let proof = Proof::from_file(Path::new(&args.proof))?;
+    let public = PublicSignals::from_file(Path::new(&args.public))?;
+    let initcode = compile_snarkjs_verifier(&key, options.evm_version)?;
+
+    let report = difftest_verifier(&key, &options, &proof, &public, &initcode)?;
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
     } else {
-        eprintln!("No file path provided!");
+        println!("{}", report);
+    }
+    let disagreements = report.disagreements();
+    if !disagreements.is_empty() {
+        return Err(format!(
+            "The verifiers disagreed with the off-chain verification on {} case(s)!",
+            disagreements.len()
+        )
+        .into());
     }
+    Ok(())
 }
 
 ////////////////////////////////////////////////////////////////
 //                      HELPER FUNCTIONS                      //
 ////////////////////////////////////////////////////////////////
 
-/// Parses a verification key from a file path.
-fn parse_verification_key(path: &Path) -> Result<VerificationKey, &'static str> {
-    if let Ok(contents) = fs::File::open(path) {
-        Ok(serde_json::from_reader(contents)
-            .expect("Error while deserializing verification key JSON."))
-    } else {
-        Err("Error reading file contents!")
+/// Reads a verification key in `format` from `path`, or from stdin if `path` is `-`,
+/// failing early if the file does not exist.
+fn read_verification_key(
+    path: &Path,
+    format: InputFormat,
+) -> Result<VerificationKey, Box<dyn Error>> {
+    if path == Path::new(STDIN_PATH) {
+        let mut data = Vec::new();
+        io::stdin()
+            .read_to_end(&mut data)
+            .map_err(|e| HuffvError::io("stdin", e))?;
+        return Ok(VerificationKey::from_bytes_as(&data, format)?);
     }
+    if !path.exists() {
+        return Err("File does not exist!".into());
+    }
+    let data = fs::read(path).map_err(|e| HuffvError::io(path, e))?;
+    Ok(VerificationKey::from_bytes_as(&data, format)?)
 }
 
-/// Encodes a string that contains a 256 bit decimal number as a 32 byte hex string
-fn encode_num(n: &str) -> String {
-    let num = IBig::from_str_radix(n, 10).expect("Failed to parse verification key.");
-    let mut encoded = num.in_radix(16).to_string();
-
-    // If the encoded hex isn't 32 bytes in length, pad the beginning with
-    // zero bytes.
-    if encoded.len() != 64 {
-        encoded = format!("{}{}", "0".repeat(64 - encoded.len()), encoded);
+/// The value of a boolean option from its `--<flag>` and `--no-<flag>` flags, or `None`
+/// if neither was passed, so that it falls back to the config file.
+fn flag(yes: bool, no: bool) -> Option<bool> {
+    match (yes, no) {
+        (true, _) => Some(true),
+        (_, true) => Some(false),
+        _ => None,
     }
+}
+
+/// Renders the snarkjs Solidity verifier of `key` and compiles it with `solc` for
+/// `evm_version`, returning its creation code.
+fn compile_snarkjs_verifier(
+    key: &VerificationKey,
+    evm_version: EvmVersion,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    // solc only compiles files, so the verifier goes through a temporary one
+    let file = env::temp_dir().join(format!("huffv-snarkjs-{}.sol", process::id()));
+    fs::write(&file, generate_snarkjs_verifier(key)?).map_err(|e| HuffvError::io(&file, e))?;
+    let output = run_tool(
+        SOLC_BIN,
+        &[
+            file.as_os_str(),
+            "--bin".as_ref(),
+            "--optimize".as_ref(),
+            "--evm-version".as_ref(),
+            evm_version.name().as_ref(),
+        ],
+    );
+    fs::remove_file(&file).map_err(|e| HuffvError::io(&file, e))?;
 
-    encoded
+    // The creation code follows the `Binary:` line
+    let bytecode = output?
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| *line != "Binary:")
+        .nth(1)
+        .map(str::to_string)
+        .unwrap_or_default();
+    let initcode = hex::decode(&bytecode).map_err(|_| {
+        format!(
+            "`{}` did not output the creation code of `{}`.",
+            SOLC_BIN, SNARKJS_CONTRACT_NAME
+        )
+    })?;
+    debug!("Compiled the snarkjs verifier to 0x{}", bytecode);
+    Ok(initcode)
+}
+
+/// Runs an external tool to completion and returns its stdout, with its output in the
+/// error if it fails.
+fn run_tool(bin: &str, args: &[&OsStr]) -> Result<String, Box<dyn Error>> {
+    info!(
+        "Running `{} {}`",
+        bin,
+        args.iter()
+            .map(|arg| arg.to_string_lossy())
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+    let output = process::Command::new(bin)
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run `{}`, is it installed? {}", bin, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "`{}` failed with {}:\n{}{}",
+            bin,
+            output.status,
+            String::from_utf8_lossy(&output.stdout),
+            String::from_utf8_lossy(&output.stderr)
+        )
+        .into());
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
 }
@@ -0,0 +1,83 @@
+#![doc = include_str!("../README.md")]
+
+pub mod aggregator;
+pub mod arkworks;
+pub mod artifact;
+pub mod assembler;
+pub mod batch;
+pub mod bench;
+mod binfile;
+pub mod builder;
+pub mod calldata;
+pub mod check;
+pub mod codegen;
+pub mod compare;
+pub mod deploy;
+pub mod difftest;
+pub mod disasm;
+pub mod error;
+pub mod factory;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fingerprint;
+pub mod gas;
+pub mod multi;
+pub mod package;
+pub mod proof;
+pub mod r1cs;
+pub mod registry;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod rpc;
+pub mod scaffold;
+pub mod simulate;
+pub mod size;
+#[cfg(test)]
+mod test_utils;
+pub mod utils;
+pub mod vectors;
+pub mod verify;
+pub mod vkey;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+pub mod zkey;
+
+pub use aggregator::generate_aggregator;
+pub use arkworks::read_arkworks_vkey;
+pub use artifact::Artifact;
+pub use assembler::{assemble_creation_code, assemble_runtime_code};
+pub use batch::{encode_batch_calldata, generate_batch_verifier};
+pub use bench::{bench_initcode, bench_verifier, synthetic_circuit, BenchReport};
+pub use builder::{GeneratedVerifier, VerifierBuilder};
+pub use calldata::{
+    encode_calldata, encode_calldata_for_circuit, encode_calldata_with, encode_calldata_with_key,
+    encode_set_vkey_calldata,
+};
+pub use check::check_contract;
+pub use codegen::{
+    generate_verifier, generate_verifier_with, Abi, Chain, Emit, EvmVersion, InputCommitment,
+    MemoryLayout, MsmStrategy, OnFailure, Optimization, PrecompileAddresses, PublicInputOrder,
+    Selector, SelfTest, Target, VerifierOptions, VkeyStorage,
+};
+pub use compare::{generate_snarkjs_verifier, ComparisonReport};
+pub use deploy::{create2_address, vkey_data_initcode};
+pub use difftest::{difftest_verifier, DiffCase, DiffReport};
+pub use disasm::{annotated_disassembly, disassemble, Instruction};
+pub use error::{HuffvError, Result};
+pub use factory::{encode_deploy_clone_calldata, generate_factory};
+pub use fingerprint::{key_fingerprint, Fingerprint};
+pub use gas::GasReport;
+pub use multi::generate_multi_verifier;
+pub use package::{Manifest, Package};
+pub use proof::{Proof, PublicSignals};
+pub use r1cs::{parse_r1cs, R1csHeader};
+pub use registry::{encode_register_calldata, generate_registry};
+#[cfg(not(target_arch = "wasm32"))]
+pub use rpc::{deploy_initcode, deploy_verifier, Deployment};
+pub use scaffold::generate_test_scaffold;
+pub use simulate::{simulate_initcode, simulate_verifier, Simulation};
+pub use size::SizeReport;
+pub use utils::{encode_num, parse_num};
+pub use vectors::{generate_test_vectors, Outcome, TestVector, TestVectors};
+pub use verify::{validate_verification_key, verify_proof};
+pub use vkey::{parse_verification_key, IcConstantPosition, InputFormat, VerificationKey};
+pub use zkey::read_zkey;
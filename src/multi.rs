@@ -0,0 +1,316 @@
+use crate::check::check_contract;
+use crate::codegen::{
+    Abi, Emit, Erc165Context, MemoryLayout, OnFailure, Target, TemplateContext, VerifierOptions,
+    VkeyStorage, ERC165_INTERFACE_ID, HUFF_VERIFIER_CONTRACT, INVALID_CALLDATA_ERROR,
+};
+use crate::error::{HuffvError, Result};
+use crate::utils::{render_template, signature_selector};
+use crate::verify::validate_verification_key;
+use crate::vkey::VerificationKey;
+use serde::Serialize;
+use std::collections::HashSet;
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The multi-circuit verifier template contract
+pub static MULTI_VERIFIER_CONTRACT: &str = include_str!("contracts/MultiVerifierTemplate.huff");
+
+/// The signature of the function returning the hash of a circuit's verification key,
+/// by circuit id.
+pub static MULTI_VKEY_HASH_SIGNATURE: &str = "VKEY_HASH(uint256)";
+
+/// The calldata location of the proof words, after the circuit id.
+const PROOF_PTR: usize = 0x24;
+
+/// The size of alpha, beta, and gamma, which start a packed verification key.
+const VKEY_BASE_BYTES: usize = 0x140;
+
+////////////////////////////////////////////////////////////////
+//                   MULTI-CIRCUIT VERIFIER                   //
+////////////////////////////////////////////////////////////////
+
+/// Generates a single Huff verifier for several circuits, one per key of `keys`, with
+/// `options`. Proofs are verified with `verify(uint256 circuitId, ...)`, where the
+/// circuit id is the index of the circuit's key in `keys`, and `VKEY_HASH(uint256)`
+/// returns the hash of a circuit's key.
+///
+/// Each circuit's verifier is rendered as with [`generate_verifier_with`], with its
+/// constants, macros, and tables prefixed with `C<circuit id>_`, and the generated
+/// `MAIN` dispatches calls to them by circuit id. If the keys have the same alpha,
+/// beta, and gamma, as keys from the same trusted setup do, they are kept once, in a
+/// `VKEY_BASE` table, and each circuit's `VKEY` table holds only its delta and ICs.
+/// The circuits share the verifier's options, which must be the defaults of
+/// `--target huff`, `--emit contract`, `--abi huffv`, and `--vkey-storage code`,
+/// without `--compressed-proof`.
+///
+/// [`generate_verifier_with`]: crate::codegen::generate_verifier_with
+pub fn generate_multi_verifier(
+    keys: &[VerificationKey],
+    options: &VerifierOptions,
+) -> Result<String> {
+    if keys.is_empty() {
+        return Err(HuffvError::InvalidOptions(
+            "A multi-circuit verifier needs the key of at least one circuit.".to_string(),
+        ));
+    }
+    options.check_multi()?;
+    let template = options
+        .template
+        .as_deref()
+        .unwrap_or(HUFF_VERIFIER_CONTRACT);
+
+    let mut contexts = Vec::with_capacity(keys.len());
+    for key in keys {
+        validate_verification_key(key)?;
+        options.check_input_commitment(key.ic.len().saturating_sub(1))?;
+
+        // The circuit id comes before the proof words
+        let mut context = TemplateContext::new(key, options)?;
+        context.main = false;
+        context.erc165 = None;
+        context.abi.signature = options.multi_verify_signature();
+        context.abi.selector = hex::encode(options.multi_verify_selector());
+        context.abi.proof_ptr = format!("0x{:02x}", PROOF_PTR);
+        context.abi.offset_ptr = format!("0x{:02x}", PROOF_PTR + 0x100);
+        contexts.push(context);
+    }
+
+    // Circuits of the same setup share alpha, beta, and gamma, so they are kept once
+    let base = contexts[0].packed_vkey[..2 * VKEY_BASE_BYTES].to_string();
+    let shared_base = contexts.len() > 1
+        && contexts
+            .iter()
+            .all(|context| context.packed_vkey.starts_with(&base));
+
+    let mut circuits = Vec::with_capacity(keys.len());
+    for (id, (key, mut context)) in keys.iter().zip(contexts).enumerate() {
+        if shared_base {
+            context.vkey_storage.shared_base = true;
+            context.packed_vkey = context.packed_vkey[base.len()..].to_string();
+        }
+        let source = render_template(template, &context)?;
+        check_contract(&source, &MemoryLayout::new(key.ic.len()))?;
+
+        let prefix = format!("C{}_", id);
+        circuits.push(CircuitContext {
+            id,
+            id_hex: format!("0x{:02x}", id),
+            vkey_hash: context.vkey_hash,
+            source: prefix_definitions(strip_header(&source), &prefix),
+            prefix,
+        });
+    }
+
+    let context = MultiVerifierContext {
+        signature: options.multi_verify_signature(),
+        selector: hex::encode(options.multi_verify_selector()),
+        vkey_hash_selector: hex::encode(signature_selector(MULTI_VKEY_HASH_SIGNATURE)),
+        invalid_calldata: (options.on_failure == OnFailure::Revert)
+            .then(|| hex::encode(signature_selector(INVALID_CALLDATA_ERROR))),
+        erc165: options.erc165.then(|| Erc165Context {
+            selector: hex::encode(ERC165_INTERFACE_ID),
+            interface_id: hex::encode(options.multi_interface_id()),
+        }),
+        vkey_base: shared_base.then_some(base),
+        n_circuits: circuits.len(),
+        circuits,
+    };
+    render_template(MULTI_VERIFIER_CONTRACT, &context)
+}
+
+/// VerifierOptions implementation for multi-circuit verifiers
+impl VerifierOptions {
+    /// The signature of the verification function of a multi-circuit verifier: the
+    /// circuit id, then the parameters of `--abi huffv`.
+    pub fn multi_verify_signature(&self) -> String {
+        format!("{}(uint256,{})", self.verify_name(), Abi::Huffv.params(0))
+    }
+
+    /// The selector a multi-circuit verifier dispatches to its verification function.
+    pub fn multi_verify_selector(&self) -> [u8; 4] {
+        match self.selector {
+            Some(selector) => selector.0,
+            None => signature_selector(&self.multi_verify_signature()),
+        }
+    }
+
+    /// The ERC-165 interface id of a multi-circuit verifier: the XOR of the selectors
+    /// of its verification function and `VKEY_HASH(uint256)`.
+    pub fn multi_interface_id(&self) -> [u8; 4] {
+        let mut interface_id = self.multi_verify_selector();
+        interface_id
+            .iter_mut()
+            .zip(signature_selector(MULTI_VKEY_HASH_SIGNATURE))
+            .for_each(|(byte, vkey_hash_byte)| *byte ^= vkey_hash_byte);
+        interface_id
+    }
+
+    /// Checks that the options can be shared by the circuits of a multi-circuit verifier.
+    fn check_multi(&self) -> Result<()> {
+        let problem = if self.target != Target::Huff {
+            "A multi-circuit verifier needs `--target huff`."
+        } else if self.emit != Emit::Contract {
+            "A multi-circuit verifier dispatches to its circuits from its `MAIN`, so it needs `--emit contract`."
+        } else if self.abi != Abi::Huffv {
+            "A multi-circuit verifier needs the dynamic public inputs array of `--abi huffv`."
+        } else if self.compressed_proof {
+            "A multi-circuit verifier takes the circuit id before the proof, which `--compressed-proof` doesn't support."
+        } else if self.vkey_storage != VkeyStorage::Code {
+            "A multi-circuit verifier keeps the keys of its circuits in its code, so it needs `--vkey-storage code`."
+        } else if self.fingerprint {
+            "A fingerprint trailer records a single verification key, so it can't be added to a multi-circuit verifier."
+        } else if self.self_test.is_some() {
+            "The proof of `--selftest-proof` is a single circuit's, so it can't be baked into a multi-circuit verifier."
+        } else {
+            return Ok(());
+        };
+        Err(HuffvError::InvalidOptions(problem.to_string()))
+    }
+}
+
+////////////////////////////////////////////////////////////////
+//                      TEMPLATE CONTEXT                      //
+////////////////////////////////////////////////////////////////
+
+/// The values the multi-circuit verifier template is rendered with.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct MultiVerifierContext {
+    /// The signature of the verification function.
+    signature: String,
+    /// The selector of the verification function, as hex without a `0x` prefix.
+    selector: String,
+    /// The selector of `VKEY_HASH(uint256)`, as hex without a `0x` prefix.
+    vkey_hash_selector: String,
+    /// The selector of `InvalidCalldata()`, if the verifier reverts on failure.
+    invalid_calldata: Option<String>,
+    /// ERC-165 interface detection, if the verifier answers it.
+    erc165: Option<Erc165Context>,
+    /// The packed alpha, beta, and gamma the circuits share, as hex without a `0x`
+    /// prefix, if they all have the same.
+    vkey_base: Option<String>,
+    /// The number of circuits.
+    n_circuits: usize,
+    /// The circuits, by id.
+    circuits: Vec<CircuitContext>,
+}
+
+/// A circuit of the multi-circuit verifier.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct CircuitContext {
+    /// The circuit id, in decimal.
+    id: usize,
+    /// The circuit id, as a hex literal.
+    id_hex: String,
+    /// The keccak256 hash of the circuit's packed key, as hex without a `0x` prefix.
+    vkey_hash: String,
+    /// The prefix of the circuit's constants, macros, and tables.
+    prefix: String,
+    /// The circuit's verifier, from its first section on, with its definitions prefixed.
+    source: String,
+}
+
+////////////////////////////////////////////////////////////////
+//                      HELPER FUNCTIONS                      //
+////////////////////////////////////////////////////////////////
+
+/// The rendered verifier `source` without its header comments, from the banner of its
+/// first section on.
+fn strip_header(source: &str) -> &str {
+    let start = source.find("\n////////").map_or(0, |i| i + 1);
+    source[start..].trim_end()
+}
+
+/// Prefixes the names of the constants, macros, and tables defined in `source` with
+/// `prefix`, both in their definitions and everywhere they are used. Comments are
+/// left as they are.
+fn prefix_definitions(source: &str, prefix: &str) -> String {
+    let names = source
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("#define "))
+        .filter_map(|definition| definition.split_whitespace().nth(1))
+        .map(|name| name.split('(').next().unwrap_or(name))
+        .collect::<HashSet<_>>();
+
+    source
+        .lines()
+        .map(|line| {
+            let (code, comment) = line.split_at(line.find("//").unwrap_or(line.len()));
+            let mut prefixed = String::with_capacity(line.len());
+            let mut rest = code;
+            while let Some(start) = rest.find(|c: char| c.is_ascii_alphabetic() || c == '_') {
+                let end = rest[start..]
+                    .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+                    .map_or(rest.len(), |end| start + end);
+                let word = &rest[start..end];
+                // Hex literals are words too, so only whole names are prefixed
+                let in_literal = rest[..start].ends_with(|c: char| c.is_ascii_digit());
+                prefixed.push_str(&rest[..start]);
+                if names.contains(word) && !in_literal {
+                    prefixed.push_str(prefix);
+                }
+                prefixed.push_str(word);
+                rest = &rest[end..];
+            }
+            prefixed.push_str(rest);
+
+            // Keep trailing comments aligned, as far as the padding before them allows
+            if !comment.is_empty() {
+                let added = prefixed.len() - code.len();
+                let padding = prefixed.len() - prefixed.trim_end().len();
+                prefixed.truncate(prefixed.len() - added.min(padding.saturating_sub(1)));
+            }
+            prefixed.push_str(comment);
+            prefixed
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assembler::assemble_creation_code;
+    use crate::bench::{decode_hex, deploy, synthetic_circuit, transact};
+    use crate::calldata::encode_calldata_for_circuit;
+    use revm::primitives::{TxKind, U256};
+
+    #[test]
+    fn circuits_of_the_same_setup_share_a_single_base_region() {
+        let circuits = [synthetic_circuit(1), synthetic_circuit(2)];
+        let keys = circuits
+            .iter()
+            .map(|(key, _, _)| key.clone())
+            .collect::<Vec<_>>();
+        let options = VerifierOptions::default();
+        let contract = generate_multi_verifier(&keys, &options).unwrap();
+        assert_eq!(contract.matches("#define table VKEY_BASE").count(), 1);
+        assert_eq!(contract.matches("__tablestart(VKEY_BASE)").count(), 2);
+
+        // Both circuits still verify their proofs, reading the shared base
+        let initcode = assemble_creation_code(&contract, options.evm_version).unwrap();
+        for (id, (_, proof, public)) in circuits.iter().enumerate() {
+            let (db, verifier, _, _) = deploy(&initcode, options.evm_version).unwrap();
+            let calldata = encode_calldata_for_circuit(id, proof, public, &options).unwrap();
+            let calldata = decode_hex(&calldata).unwrap();
+            let (result, _) =
+                transact(db, options.evm_version, TxKind::Call(verifier), calldata).unwrap();
+            let output = result.into_output().unwrap();
+            assert_eq!(
+                U256::from_be_slice(&output),
+                U256::from(1),
+                "circuit {}",
+                id
+            );
+        }
+
+        // Keys of different setups keep their own
+        let mut other = keys[1].clone();
+        other.vk_alpha_1 = other.ic[0].clone();
+        let contract = generate_multi_verifier(&[keys[0].clone(), other], &options).unwrap();
+        assert!(!contract.contains("#define table VKEY_BASE"));
+    }
+}
@@ -0,0 +1,307 @@
+use crate::artifact::CONTRACT_NAME;
+use crate::codegen::{
+    generate_verifier_with, Emit, EvmVersion, MemoryLayout, Target, TemplateContext,
+    VerifierOptions,
+};
+use crate::error::{HuffvError, Result};
+use crate::fingerprint::key_fingerprint;
+use crate::utils::keccak256;
+use crate::vkey::VerificationKey;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fmt;
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The version of the package format, bumped whenever its layout changes.
+pub const PACKAGE_FORMAT: u32 = 1;
+
+/// The name of the canonical verification key JSON in a package.
+pub static KEY_FILE: &str = "verification_key.json";
+
+/// The name of the memory layout and template offsets JSON in a package.
+pub static OFFSETS_FILE: &str = "offsets.json";
+
+/// The name of the manifest, as `huffv unpackage` extracts it.
+pub static MANIFEST_FILE: &str = "manifest.json";
+
+////////////////////////////////////////////////////////////////
+//                          PACKAGE                           //
+////////////////////////////////////////////////////////////////
+
+/// What a [`Package`] holds, with the keccak256 hash of each of its files.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    /// The version of the package format, see [`PACKAGE_FORMAT`].
+    pub format: u32,
+    /// The version of `huffv` that packaged the verifier.
+    pub version: String,
+    /// The number of public inputs of the verifier.
+    pub n_public: usize,
+    /// The EVM version the verifier targets.
+    pub evm_version: EvmVersion,
+    /// The keccak256 hash of the canonical verification key JSON, see [`key_fingerprint`].
+    pub vkey_hash: String,
+    /// The keccak256 hash of each file, by its name.
+    pub files: BTreeMap<String, String>,
+}
+
+/// A Huff verifier and its verification key in a single file, for distribution, as
+/// `huffv package` writes it: the canonical key JSON, the verifier with a `MAIN` macro,
+/// its memory layout and template offsets, and a manifest of them, sealed by a checksum.
+///
+/// The checksum is the keccak256 hash of the manifest as canonical JSON, and the
+/// manifest holds the hash of every file, so that changing any byte of the package
+/// breaks one or the other. It detects corruption and careless edits, not forgery: it
+/// isn't a signature.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Package {
+    /// The manifest of the files.
+    pub manifest: Manifest,
+    /// The contents of each file, by its name.
+    pub files: BTreeMap<String, String>,
+    /// The keccak256 hash of the manifest, as `0x` prefixed hex.
+    pub checksum: String,
+}
+
+/// Package implementation
+impl Package {
+    /// Packages the Huff verifier of `key` generated with `options`, with `--main`.
+    pub fn new(key: &VerificationKey, options: &VerifierOptions) -> Result<Self> {
+        let options = VerifierOptions {
+            target: Target::Huff,
+            main: true,
+            emit: Emit::Contract,
+            ..options.clone()
+        };
+        let contract = generate_verifier_with(key, &options)?;
+        let layout = MemoryLayout::new(key.ic.len());
+        let offsets = json!({
+            "layout": {
+                "nIcs": layout.n_ics,
+                "icPtr": MemoryLayout::IC_PTR,
+                "icPtrLast": layout.ic_ptr_last(),
+                "pairingInputPtr": layout.pairing_input_ptr(),
+                "inputPtr": layout.input_ptr(),
+                "pubInputLenPtr": layout.pub_input_len_ptr(),
+                "pubInputPtr": layout.pub_input_ptr(),
+            },
+            "offsets": TemplateContext::new(key, &options)?.offsets,
+        });
+
+        let files = BTreeMap::from([
+            (
+                KEY_FILE.to_string(),
+                serde_json::to_string_pretty(&serde_json::to_value(key)?)?,
+            ),
+            (format!("{}.huff", CONTRACT_NAME), contract),
+            (
+                OFFSETS_FILE.to_string(),
+                serde_json::to_string_pretty(&offsets)?,
+            ),
+        ]);
+        let manifest = Manifest {
+            format: PACKAGE_FORMAT,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            n_public: key.ic.len().saturating_sub(1),
+            evm_version: options.evm_version,
+            vkey_hash: format!("0x{}", hex::encode(key_fingerprint(key)?)),
+            files: files
+                .iter()
+                .map(|(name, contents)| (name.clone(), hash(contents)))
+                .collect(),
+        };
+        Ok(Self {
+            checksum: checksum(&manifest)?,
+            manifest,
+            files,
+        })
+    }
+
+    /// Reads a package from the contents of its file.
+    pub fn from_bytes(data: &[u8]) -> Result<Self> {
+        Ok(serde_json::from_slice(data)?)
+    }
+
+    /// The problems with the integrity of the package, empty if it is intact: a
+    /// checksum that doesn't match the manifest, files that are missing, extra, or don't
+    /// match their hashes, or a key that doesn't match the manifest.
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+        if self.manifest.format != PACKAGE_FORMAT {
+            problems.push(format!(
+                "The package format is {}, but this huffv reads format {}.",
+                self.manifest.format, PACKAGE_FORMAT
+            ));
+        }
+        match checksum(&self.manifest) {
+            Ok(checksum) if checksum == self.checksum => {}
+            _ => problems.push("The checksum doesn't match the manifest.".to_string()),
+        }
+        for (name, expected) in &self.manifest.files {
+            match self.files.get(name) {
+                None => problems.push(format!("`{}` is missing.", name)),
+                Some(contents) if hash(contents) != *expected => problems.push(format!(
+                    "`{}` doesn't match its hash in the manifest.",
+                    name
+                )),
+                Some(_) => {}
+            }
+        }
+        for name in self.files.keys() {
+            if !self.manifest.files.contains_key(name) {
+                problems.push(format!("`{}` isn't in the manifest.", name));
+            }
+        }
+        match self.key() {
+            Ok(key) => {
+                let vkey_hash =
+                    key_fingerprint(&key).map(|hash| format!("0x{}", hex::encode(hash)));
+                if vkey_hash.ok().as_ref() != Some(&self.manifest.vkey_hash) {
+                    problems.push(format!(
+                        "`{}` doesn't match the key hash in the manifest.",
+                        KEY_FILE
+                    ));
+                }
+                if key.ic.len().saturating_sub(1) != self.manifest.n_public {
+                    problems.push(format!(
+                        "`{}` doesn't have the manifest's {} public inputs.",
+                        KEY_FILE, self.manifest.n_public
+                    ));
+                }
+            }
+            Err(e) => problems.push(format!("`{}` isn't a verification key: {}", KEY_FILE, e)),
+        }
+        problems
+    }
+
+    /// The verification key of the package.
+    pub fn key(&self) -> Result<VerificationKey> {
+        let json = self
+            .files
+            .get(KEY_FILE)
+            .ok_or_else(|| HuffvError::InvalidKey(format!("The package has no `{}`.", KEY_FILE)))?;
+        VerificationKey::from_json(json)
+    }
+}
+
+impl fmt::Display for Package {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "huffv version:       {}", self.manifest.version)?;
+        writeln!(f, "Public inputs:       {}", self.manifest.n_public)?;
+        writeln!(
+            f,
+            "EVM version:         {}",
+            self.manifest.evm_version.name()
+        )?;
+        writeln!(f, "Key hash:            {}", self.manifest.vkey_hash)?;
+        write!(f, "Checksum:            {}", self.checksum)?;
+        for (name, hash) in &self.manifest.files {
+            write!(f, "\n{:<20} {}", name, hash)?;
+        }
+        Ok(())
+    }
+}
+
+////////////////////////////////////////////////////////////////
+//                      HELPER FUNCTIONS                      //
+////////////////////////////////////////////////////////////////
+
+/// The keccak256 hash of the `contents` of a file, as `0x` prefixed hex.
+fn hash(contents: &str) -> String {
+    format!("0x{}", hex::encode(keccak256(contents.as_bytes())))
+}
+
+/// The checksum of a package with `manifest`: the keccak256 hash of the manifest as
+/// compact JSON with sorted object keys.
+fn checksum(manifest: &Manifest) -> Result<String> {
+    // `serde_json::Value` objects are ordered by key
+    Ok(hash(&serde_json::to_value(manifest)?.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::sample_key;
+
+    /// The sample key's package, written and read back.
+    fn round_trip() -> Package {
+        let package = Package::new(&sample_key(), &VerifierOptions::default()).unwrap();
+        Package::from_bytes(serde_json::to_string_pretty(&package).unwrap().as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn packaged_verifiers_are_intact() {
+        let package = round_trip();
+        assert_eq!(package.problems(), Vec::<String>::new());
+        assert_eq!(package.manifest.n_public, 2);
+        assert!(package.files["Verifier.huff"].contains("#define macro MAIN()"));
+        let key = package.key().unwrap();
+        assert_eq!(
+            key_fingerprint(&key).unwrap(),
+            key_fingerprint(&sample_key()).unwrap()
+        );
+    }
+
+    #[test]
+    fn tampering_is_detected() {
+        let mut package = round_trip();
+        let contract = package.files.get_mut("Verifier.huff").unwrap();
+        *contract = contract.replacen("0x", "0x0", 1);
+        assert_eq!(
+            package.problems(),
+            ["`Verifier.huff` doesn't match its hash in the manifest."]
+        );
+
+        // Rehashing the file in the manifest breaks the checksum instead
+        let mut package = round_trip();
+        let contract = package.files.get_mut("Verifier.huff").unwrap();
+        *contract = contract.replacen("0x", "0x0", 1);
+        let rehashed = hash(&package.files["Verifier.huff"]);
+        package
+            .manifest
+            .files
+            .insert("Verifier.huff".to_string(), rehashed);
+        assert_eq!(
+            package.problems(),
+            ["The checksum doesn't match the manifest."]
+        );
+
+        let mut package = round_trip();
+        package.files.remove(OFFSETS_FILE);
+        package.files.insert("extra.txt".to_string(), String::new());
+        assert_eq!(
+            package.problems(),
+            [
+                "`offsets.json` is missing.",
+                "`extra.txt` isn't in the manifest."
+            ]
+        );
+    }
+
+    #[test]
+    fn a_swapped_key_is_detected() {
+        let mut package = round_trip();
+        let mut key = sample_key();
+        key.ic.pop();
+        let json = serde_json::to_string_pretty(&serde_json::to_value(&key).unwrap()).unwrap();
+        package
+            .manifest
+            .files
+            .insert(KEY_FILE.to_string(), hash(&json));
+        package.files.insert(KEY_FILE.to_string(), json);
+        package.checksum = checksum(&package.manifest).unwrap();
+
+        assert_eq!(
+            package.problems(),
+            [
+                "`verification_key.json` doesn't match the key hash in the manifest.",
+                "`verification_key.json` doesn't have the manifest's 2 public inputs."
+            ]
+        );
+    }
+}
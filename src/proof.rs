@@ -0,0 +1,226 @@
+use crate::calldata::encode_calldata_with;
+use crate::codegen::{PublicInputOrder, VerifierOptions};
+use crate::error::{HuffvError, Result};
+use crate::utils::{encode_num, keccak256, parse_num, read_json};
+use ibig::UBig;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// A Groth16 proof.
+///
+/// Can be directly deserialized from a `proof.json` generated by
+/// [snarkjs](https://github.com/iden3/snarkjs).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Proof {
+    /// The projective coordinates `[x, y, z]` of `proof_a`, a G1 point.
+    pub pi_a: Vec<String>,
+
+    /// The projective coordinates `[[x.c0, x.c1], [y.c0, y.c1], [z.c0, z.c1]]` of
+    /// `proof_b`, a G2 point.
+    pub pi_b: Vec<Vec<String>>,
+
+    /// The projective coordinates `[x, y, z]` of `proof_c`, a G1 point.
+    pub pi_c: Vec<String>,
+}
+
+/// The public signals of a proof.
+///
+/// Can be directly deserialized from a `public.json` generated by
+/// [snarkjs](https://github.com/iden3/snarkjs).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(transparent)]
+pub struct PublicSignals(pub Vec<String>);
+
+/// Proof implementation
+impl Proof {
+    /// Parses a proof from a file path.
+    pub fn from_file(path: &Path) -> Result<Proof> {
+        let proof: Proof = read_json(path)?;
+        proof.check_shape()?;
+        Ok(proof)
+    }
+
+    /// Parses a proof from a JSON string.
+    pub fn from_json(json: &str) -> Result<Proof> {
+        let proof: Proof = serde_json::from_str(json)?;
+        proof.check_shape()?;
+        Ok(proof)
+    }
+
+    /// The eight proof words in the order the verifier expects them:
+    /// `a_0, a_1, b_0_1, b_0_0, b_1_1, b_1_0, c_0, c_1`.
+    ///
+    /// Like the verification key's G2 points, the limbs of `b`'s coordinates
+    /// are swapped relative to snarkjs.
+    pub fn words(&self) -> [&str; 8] {
+        [
+            &self.pi_a[0],
+            &self.pi_a[1],
+            &self.pi_b[0][1],
+            &self.pi_b[0][0],
+            &self.pi_b[1][1],
+            &self.pi_b[1][0],
+            &self.pi_c[0],
+            &self.pi_c[1],
+        ]
+    }
+
+    /// ABI-encodes the eight proof words of [`Proof::words`] as `uint256[8]`, the
+    /// layout the verifier copies the proof from calldata in, as hex without a `0x`
+    /// prefix.
+    pub fn encode(&self) -> Result<String> {
+        self.words().into_iter().map(encode_num).collect()
+    }
+
+    /// ABI-encodes a call to the verification function of a verifier generated with
+    /// `options`, for the proof and its `public` signals, as [`encode_calldata_with`]
+    /// does.
+    pub fn to_calldata(&self, public: &PublicSignals, options: &VerifierOptions) -> Result<String> {
+        encode_calldata_with(self, public, options)
+    }
+
+    /// The four proof words of a compressed proof, for a verifier generated with
+    /// `--compressed-proof`: `a_0, b_0_1, b_0_0, c_0`, each point reduced to its `x`
+    /// coordinate with the parity of its `y` coordinate in the top bit. For `b`, the
+    /// parity is that of `b_1_1`, as only `b_0_1` has a free top bit.
+    ///
+    /// The verifier recovers the `y` coordinates with square roots in the base field.
+    pub fn compressed_words(&self) -> Result<[String; 4]> {
+        let flag = UBig::from(1u8) << 255;
+        let compress = |name: &str, x: &str, y: &str| -> Result<String> {
+            let (x, y) = (parse_num(x)?, parse_num(y)?);
+            if y == UBig::from(0u8) {
+                return Err(HuffvError::InvalidProof(format!(
+                    "{} can't be compressed, its y coordinate is 0.",
+                    name
+                )));
+            }
+            Ok(if y.bit(0) { x | &flag } else { x }.to_string())
+        };
+
+        Ok([
+            compress("pi_a", &self.pi_a[0], &self.pi_a[1])?,
+            compress("pi_b", &self.pi_b[0][1], &self.pi_b[1][1])?,
+            parse_num(&self.pi_b[0][0])?.to_string(),
+            compress("pi_c", &self.pi_c[0], &self.pi_c[1])?,
+        ])
+    }
+
+    /// Checks that every point of the proof has enough coordinates.
+    fn check_shape(&self) -> Result<()> {
+        if self.pi_a.len() < 2
+            || self.pi_c.len() < 2
+            || self.pi_b.len() < 2
+            || self.pi_b[..2].iter().any(|c| c.len() != 2)
+        {
+            return Err(HuffvError::InvalidProof(
+                "pi_a and pi_c must have 2 coordinates and pi_b 2 coordinates of 2 limbs each."
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Public signals implementation
+impl PublicSignals {
+    /// Parses public signals from a file path.
+    pub fn from_file(path: &Path) -> Result<PublicSignals> {
+        read_json(path)
+    }
+
+    /// Parses public signals from a JSON string.
+    pub fn from_json(json: &str) -> Result<PublicSignals> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// ABI-encodes the signals as 32 byte words, in the order a verifier generated
+    /// with `order` reads them, as hex without a `0x` prefix.
+    pub fn encode(&self, order: PublicInputOrder) -> Result<String> {
+        let mut signals: Vec<&String> = self.0.iter().collect();
+        if order == PublicInputOrder::Reverse {
+            signals.reverse();
+        }
+        signals
+            .into_iter()
+            .map(|signal| encode_num(signal))
+            .collect()
+    }
+
+    /// The keccak256 commitment to the signals that a verifier generated with
+    /// `--input-commitment keccak` computes on-chain: the hash of the signals as
+    /// 32 byte words, with its top 3 bits cleared so that it is in the scalar field.
+    ///
+    /// The circuit exposes the commitment as its only public signal.
+    pub fn keccak_commitment(&self) -> Result<PublicSignals> {
+        let words = self.encode(PublicInputOrder::Forward)?;
+        let mut hash = keccak256(&hex::decode(words).expect("encoded signals are hex"));
+        hash[0] &= 0x1f;
+        Ok(PublicSignals(vec![UBig::from_be_bytes(&hash).to_string()]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ark_bn254::{Fq, Fq2, G1Affine, G2Affine};
+    use ark_ff::{BigInteger, PrimeField};
+
+    /// The base field element `n`.
+    fn fq(n: &UBig) -> Fq {
+        Fq::from_be_bytes_mod_order(&n.to_be_bytes())
+    }
+
+    /// `x` in decimal.
+    fn decimal(x: &Fq) -> String {
+        x.into_bigint().to_string()
+    }
+
+    /// The `x` coordinate of a compressed `word`, and the parity of its `y` coordinate.
+    fn split(word: &str) -> (UBig, bool) {
+        let word = parse_num(word).unwrap();
+        let flag = UBig::from(1u8) << 255;
+        (&word & ((&flag) - UBig::from(1u8)), word.bit(255))
+    }
+
+    /// Decompresses the words of [`Proof::compressed_words`] into the affine
+    /// coordinates of the points, as the verifier does on-chain.
+    fn decompress(words: &[String; 4]) -> (Vec<String>, Vec<Vec<String>>, Vec<String>) {
+        let g1 = |word: &str| {
+            let (x, odd) = split(word);
+            let x = fq(&x);
+            let (y, neg_y) = G1Affine::get_ys_from_x_unchecked(x).unwrap();
+            let y = if y.into_bigint().is_odd() == odd {
+                y
+            } else {
+                neg_y
+            };
+            vec![decimal(&x), decimal(&y)]
+        };
+        let (x_c1, odd) = split(&words[1]);
+        let x = Fq2::new(fq(&parse_num(&words[2]).unwrap()), fq(&x_c1));
+        let (y, neg_y) = G2Affine::get_ys_from_x_unchecked(x).unwrap();
+        let y = if y.c1.into_bigint().is_odd() == odd {
+            y
+        } else {
+            neg_y
+        };
+        let b = vec![
+            vec![decimal(&x.c0), decimal(&x.c1)],
+            vec![decimal(&y.c0), decimal(&y.c1)],
+        ];
+        (g1(&words[0]), b, g1(&words[3]))
+    }
+
+    #[test]
+    fn compressed_words_decompress_to_the_proof() {
+        for sample in ["single-input", "multi-input"] {
+            let proof =
+                Proof::from_file(Path::new(&format!("test/{}/sample_proof.json", sample))).unwrap();
+            let (a, b, c) = decompress(&proof.compressed_words().unwrap());
+            assert_eq!(a, proof.pi_a[..2]);
+            assert_eq!(b, proof.pi_b[..2]);
+            assert_eq!(c, proof.pi_c[..2]);
+        }
+    }
+}
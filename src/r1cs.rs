@@ -0,0 +1,189 @@
+//! A reader for the header of circom's binary `.r1cs` files, to check that a
+//! verification key belongs to a circuit before a verifier is generated for it.
+//!
+//! An r1cs is a [`binfile`](crate::binfile) of sections. Only the header section
+//! is read; the constraints themselves are not needed.
+
+use crate::binfile::Sections;
+use crate::error::{HuffvError, Result};
+use crate::vkey::VerificationKey;
+use ark_bn254::Fr;
+use ark_ff::{BigInteger, PrimeField};
+use std::fs;
+use std::path::Path;
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The magic bytes at the start of every r1cs file
+pub const R1CS_MAGIC: &[u8; 4] = b"r1cs";
+
+/// The section with the field and the sizes of the circuit
+const HEADER_SECTION: u32 = 1;
+
+////////////////////////////////////////////////////////////////
+//                           HEADER                           //
+////////////////////////////////////////////////////////////////
+
+/// The header of a circom R1CS file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct R1csHeader {
+    /// The number of wires, including the constant `1` wire.
+    pub n_wires: u32,
+    /// The number of public outputs.
+    pub n_pub_out: u32,
+    /// The number of public inputs.
+    pub n_pub_in: u32,
+    /// The number of private inputs.
+    pub n_prv_in: u32,
+    /// The number of signal labels.
+    pub n_labels: u64,
+    /// The number of constraints.
+    pub n_constraints: u32,
+}
+
+/// R1csHeader implementation
+impl R1csHeader {
+    /// The number of public signals of the circuit: its public outputs, then its
+    /// public inputs, as in snarkjs's `public.json`.
+    pub fn n_public(&self) -> u64 {
+        u64::from(self.n_pub_out) + u64::from(self.n_pub_in)
+    }
+
+    /// Checks that `key` was set up for this circuit, as far as the header tells:
+    /// its `nPublic` matches the circuit, and it has an IC for each public signal
+    /// plus the constant term.
+    pub fn check_key(&self, key: &VerificationKey) -> Result<()> {
+        if key.n_public != self.n_public() {
+            return Err(HuffvError::InvalidKey(format!(
+                "nPublic is {}, but the circuit has {} public signals.",
+                key.n_public,
+                self.n_public()
+            )));
+        }
+        if key.ic.len() as u64 != self.n_public() + 1 {
+            return Err(HuffvError::InvalidKey(format!(
+                "Expected {} ICs for a circuit with {} public signals, found {}.",
+                self.n_public() + 1,
+                self.n_public(),
+                key.ic.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Reads the header of a circom R1CS file on BN254.
+pub fn read_r1cs(data: &[u8]) -> Result<R1csHeader> {
+    let sections = Sections::read(data, R1CS_MAGIC)?;
+    let mut header = sections.section(HEADER_SECTION)?;
+
+    // The header starts with the scalar field, which must be BN254's
+    let n8 = header.u32()? as usize;
+    let prime = header.bytes(n8)?;
+    if prime != Fr::MODULUS.to_bytes_le() {
+        return Err(HuffvError::UnsupportedKey(
+            "Found an r1cs on another curve, but only bn128 circuits are supported.".to_string(),
+        ));
+    }
+
+    Ok(R1csHeader {
+        n_wires: header.u32()?,
+        n_pub_out: header.u32()?,
+        n_pub_in: header.u32()?,
+        n_prv_in: header.u32()?,
+        n_labels: header.u64()?,
+        n_constraints: header.u32()?,
+    })
+}
+
+/// Reads the header of the circom R1CS file at `path`.
+pub fn parse_r1cs(path: &Path) -> Result<R1csHeader> {
+    let data = fs::read(path).map_err(|e| HuffvError::io(path, e))?;
+    read_r1cs(&data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::synthetic_circuit;
+
+    /// Encodes an r1cs with the header section of `header` on `prime`.
+    fn r1cs(header: &R1csHeader, prime: &[u8]) -> Vec<u8> {
+        let mut section = (prime.len() as u32).to_le_bytes().to_vec();
+        section.extend(prime);
+        for n in [
+            header.n_wires,
+            header.n_pub_out,
+            header.n_pub_in,
+            header.n_prv_in,
+        ] {
+            section.extend(n.to_le_bytes());
+        }
+        section.extend(header.n_labels.to_le_bytes());
+        section.extend(header.n_constraints.to_le_bytes());
+
+        let mut data = R1CS_MAGIC.to_vec();
+        data.extend(1u32.to_le_bytes());
+        data.extend(1u32.to_le_bytes());
+        data.extend(HEADER_SECTION.to_le_bytes());
+        data.extend((section.len() as u64).to_le_bytes());
+        data.extend(section);
+        data
+    }
+
+    /// The header of a circuit with one public output and `n_pub_in` public inputs.
+    fn header(n_pub_in: u32) -> R1csHeader {
+        R1csHeader {
+            n_wires: 10,
+            n_pub_out: 1,
+            n_pub_in,
+            n_prv_in: 3,
+            n_labels: 12,
+            n_constraints: 7,
+        }
+    }
+
+    #[test]
+    fn headers_are_read_and_checked_against_keys() {
+        let (key, _, _) = synthetic_circuit(3);
+        let read = read_r1cs(&r1cs(&header(2), &Fr::MODULUS.to_bytes_le())).unwrap();
+        assert_eq!(read, header(2));
+        assert_eq!(read.n_public(), 3);
+        read.check_key(&key).unwrap();
+
+        // A circuit with another number of public signals
+        let read = read_r1cs(&r1cs(&header(3), &Fr::MODULUS.to_bytes_le())).unwrap();
+        let error = read.check_key(&key).unwrap_err().to_string();
+        assert!(
+            error.contains("nPublic is 3, but the circuit has 4 public signals."),
+            "{}",
+            error
+        );
+
+        // A key whose nPublic matches, but whose ICs don't
+        let mut key = key;
+        key.ic.pop();
+        let read = read_r1cs(&r1cs(&header(2), &Fr::MODULUS.to_bytes_le())).unwrap();
+        assert!(matches!(
+            read.check_key(&key),
+            Err(HuffvError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn headers_on_other_curves_are_rejected() {
+        let data = r1cs(&header(2), &ark_bn254::Fq::MODULUS.to_bytes_le());
+        assert!(matches!(
+            read_r1cs(&data),
+            Err(HuffvError::UnsupportedKey(_))
+        ));
+
+        let data = r1cs(&header(2), &Fr::MODULUS.to_bytes_le());
+        assert!(matches!(
+            read_r1cs(&data[..data.len() - 1]),
+            Err(HuffvError::BinaryFile { .. })
+        ));
+    }
+}
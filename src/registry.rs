@@ -0,0 +1,85 @@
+use crate::codegen::{OWNER_SIGNATURE, VKEY_HASH_SELECTOR};
+use crate::error::Result;
+use crate::utils::{keccak256, render_template, signature_selector};
+use crate::vkey::VerificationKey;
+use serde::Serialize;
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The verifier registry template contract
+pub static REGISTRY_CONTRACT: &str = include_str!("contracts/RegistryTemplate.huff");
+
+/// The signature of the owner's function registering a verifier under its key hash
+pub static REGISTER_SIGNATURE: &str = "register(bytes32,address)";
+
+/// The signature of the getter of the verifier registered under a key hash
+pub static VERIFIER_OF_SIGNATURE: &str = "verifierOf(bytes32)";
+
+/// The signature of the owner's function handing the registry over
+pub static TRANSFER_OWNERSHIP_SIGNATURE: &str = "transferOwnership(address)";
+
+/// The signature of the event emitted when a verifier is registered, with the key hash
+/// and the verifier as indexed topics
+pub static VERIFIER_REGISTERED_EVENT_SIGNATURE: &str = "VerifierRegistered(bytes32,address)";
+
+/// The signature of the event emitted when ownership of the registry is transferred
+pub static OWNERSHIP_TRANSFERRED_EVENT_SIGNATURE: &str = "OwnershipTransferred(address,address)";
+
+////////////////////////////////////////////////////////////////
+//                          REGISTRY                          //
+////////////////////////////////////////////////////////////////
+
+/// Generates a Huff registry mapping the keccak256 hashes of packed verification keys
+/// to the verifiers of their circuits. It is owned by its deployer, who registers each
+/// verifier with the calldata of [`encode_register_calldata`].
+pub fn generate_registry() -> Result<String> {
+    let context = RegistryContext {
+        register_selector: hex::encode(signature_selector(REGISTER_SIGNATURE)),
+        verifier_of_selector: hex::encode(signature_selector(VERIFIER_OF_SIGNATURE)),
+        owner_selector: hex::encode(signature_selector(OWNER_SIGNATURE)),
+        transfer_ownership_selector: hex::encode(signature_selector(TRANSFER_OWNERSHIP_SIGNATURE)),
+        vkey_hash_selector: hex::encode(VKEY_HASH_SELECTOR),
+        verifier_registered_topic: hex::encode(keccak256(
+            VERIFIER_REGISTERED_EVENT_SIGNATURE.as_bytes(),
+        )),
+        ownership_transferred_topic: hex::encode(keccak256(
+            OWNERSHIP_TRANSFERRED_EVENT_SIGNATURE.as_bytes(),
+        )),
+    };
+    render_template(REGISTRY_CONTRACT, &context)
+}
+
+/// ABI-encodes a call to `register(bytes32,address)` of the registry, registering the
+/// deployed `verifier` of `key` under the hash of the key. The registry checks the hash
+/// against the verifier's `VKEY_HASH()`.
+pub fn encode_register_calldata(key: &VerificationKey, verifier: &[u8; 20]) -> Result<String> {
+    Ok(format!(
+        "0x{}{}{:0>64}",
+        hex::encode(signature_selector(REGISTER_SIGNATURE)),
+        hex::encode(key.hash()?),
+        hex::encode(verifier)
+    ))
+}
+
+/// The values the registry template is rendered with. Each selector and topic is hex
+/// without a `0x` prefix.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct RegistryContext {
+    /// `register(bytes32,address)`
+    register_selector: String,
+    /// `verifierOf(bytes32)`
+    verifier_of_selector: String,
+    /// `owner()`
+    owner_selector: String,
+    /// `transferOwnership(address)`
+    transfer_ownership_selector: String,
+    /// The verifiers' `VKEY_HASH()`
+    vkey_hash_selector: String,
+    /// The topic of `VerifierRegistered(bytes32,address)`.
+    verifier_registered_topic: String,
+    /// The topic of `OwnershipTransferred(address,address)`.
+    ownership_transferred_topic: String,
+}
@@ -0,0 +1,355 @@
+use crate::bench::{verifier_calldata, verifier_initcode};
+use crate::codegen::VerifierOptions;
+use crate::error::{HuffvError, Result};
+use crate::proof::{Proof, PublicSignals};
+use crate::vkey::VerificationKey;
+use alloy::network::{EthereumWallet, TransactionBuilder};
+use alloy::primitives::U256;
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::rpc::types::TransactionRequest;
+use alloy::signers::local::PrivateKeySigner;
+use serde::Serialize;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// How long to wait for the deployment transaction to be mined.
+pub const RECEIPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How often to ask the node for the receipt of the deployment transaction.
+pub const RECEIPT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+////////////////////////////////////////////////////////////////
+//                         DEPLOYMENT                         //
+////////////////////////////////////////////////////////////////
+
+/// A verifier deployed over JSON-RPC, as `huffv deploy` reports it.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Deployment {
+    /// The address of the verifier, checksummed.
+    pub address: String,
+    /// The hash of the deployment transaction.
+    pub tx_hash: String,
+    /// The gas of the deployment transaction.
+    pub gas_used: u128,
+    /// Whether the deployed verifier verified the test proof, if one was called.
+    pub verified: Option<bool>,
+}
+
+impl fmt::Display for Deployment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Address:             {}", self.address)?;
+        writeln!(f, "Transaction:         {}", self.tx_hash)?;
+        write!(f, "Gas used:            {}", self.gas_used)?;
+        if let Some(verified) = self.verified {
+            write!(f, "\nVerified:            {}", verified)?;
+        }
+        Ok(())
+    }
+}
+
+/// Deploys the verifier of `key` generated with `options` through the JSON-RPC node at
+/// `rpc_url`, in a transaction signed with the hex `private_key`, and waits for it to be
+/// mined. The verifier is deployed as `huffv bench` deploys it, so it needs a key it can
+/// be deployed with alone.
+///
+/// With a test `proof` of the `public` signals, the deployed verifier is then called
+/// with it (`eth_call`), and whether it returned true is reported.
+pub fn deploy_verifier(
+    rpc_url: &str,
+    private_key: &str,
+    key: &VerificationKey,
+    options: &VerifierOptions,
+    test_proof: Option<(&Proof, &PublicSignals)>,
+) -> Result<Deployment> {
+    let initcode = verifier_initcode(key, options)?;
+    let calldata = test_proof
+        .map(|(proof, public)| verifier_calldata(key, options, proof, public))
+        .transpose()?;
+    deploy_initcode(rpc_url, private_key, &initcode, calldata.as_deref())
+}
+
+/// Deploys a contract with `initcode` through the JSON-RPC node at `rpc_url`, see
+/// [`deploy_verifier`], then calls it with `calldata`, if any. The contract verified
+/// the call if the first word of its output is 1.
+pub fn deploy_initcode(
+    rpc_url: &str,
+    private_key: &str,
+    initcode: &[u8],
+    calldata: Option<&[u8]>,
+) -> Result<Deployment> {
+    // Never echo the key back, not even in an error
+    let signer: PrivateKeySigner = private_key.trim().parse().map_err(|_| {
+        HuffvError::InvalidOptions("The private key is not 32 bytes of hex.".to_string())
+    })?;
+    let url = rpc_url
+        .parse()
+        .map_err(|e| HuffvError::InvalidOptions(format!("Invalid RPC URL `{}`: {}", rpc_url, e)))?;
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|e| HuffvError::Rpc(e.to_string()))?;
+
+    runtime.block_on(async {
+        let provider = ProviderBuilder::new()
+            .with_recommended_fillers()
+            .wallet(EthereumWallet::from(signer))
+            .on_http(url);
+        let deployment = TransactionRequest::default().with_deploy_code(initcode.to_vec());
+        let pending = provider
+            .send_transaction(deployment)
+            .await
+            .map_err(rpc_error)?;
+        let tx_hash = *pending.tx_hash();
+
+        let started = Instant::now();
+        let receipt = loop {
+            if let Some(receipt) = provider
+                .get_transaction_receipt(tx_hash)
+                .await
+                .map_err(rpc_error)?
+            {
+                break receipt;
+            }
+            if started.elapsed() > RECEIPT_TIMEOUT {
+                return Err(HuffvError::Rpc(format!(
+                    "The deployment {} wasn't mined within {}s.",
+                    tx_hash,
+                    RECEIPT_TIMEOUT.as_secs()
+                )));
+            }
+            tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+        };
+        let address = match (receipt.status(), receipt.contract_address) {
+            (true, Some(address)) => address,
+            _ => {
+                return Err(HuffvError::Execution(format!(
+                    "The deployment {} reverted.",
+                    tx_hash
+                )))
+            }
+        };
+
+        let verified = match calldata {
+            Some(calldata) => {
+                let call = TransactionRequest::default()
+                    .with_to(address)
+                    .with_input(calldata.to_vec());
+                let output = provider.call(&call).await.map_err(rpc_error)?;
+                Some(output.len() >= 0x20 && U256::from_be_slice(&output[..0x20]) == U256::from(1))
+            }
+            None => None,
+        };
+        Ok(Deployment {
+            address: address.to_checksum(None),
+            tx_hash: tx_hash.to_string(),
+            gas_used: receipt.gas_used,
+            verified,
+        })
+    })
+}
+
+/// Wraps an error of a JSON-RPC request.
+fn rpc_error(e: impl fmt::Display) -> HuffvError {
+    HuffvError::Rpc(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::{deploy, synthetic_circuit, transact};
+    use crate::codegen::EvmVersion;
+    use crate::utils::keccak256;
+    use alloy::consensus::{Transaction, TxEnvelope};
+    use alloy::eips::eip2718::Decodable2718;
+    use revm::db::{CacheDB, EmptyDB};
+    use revm::primitives::{Address, ExecutionResult, Output, TxKind};
+    use serde_json::{json, Value};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    /// The first development account of anvil and hardhat.
+    const PRIVATE_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    /// The contract deployed on a [`mock_node`], with the hash of its deployment.
+    type Deployed = Option<(CacheDB<EmptyDB>, Address, String)>;
+
+    /// Serves the JSON-RPC methods a deployment uses on a local port, running the
+    /// deployment and calls under revm, and returns the URL of the node.
+    fn mock_node() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let deployed: Arc<Mutex<Deployed>> = Arc::default();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let deployed = deployed.clone();
+                std::thread::spawn(move || {
+                    let mut stream = stream.unwrap();
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    // Keep-alive: serve requests until the client hangs up
+                    loop {
+                        let mut length = 0;
+                        loop {
+                            let mut line = String::new();
+                            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                                return;
+                            }
+                            let lower = line.to_lowercase();
+                            if let Some(value) = lower.strip_prefix("content-length:") {
+                                length = value.trim().parse().unwrap();
+                            }
+                            if line == "\r\n" {
+                                break;
+                            }
+                        }
+                        let mut body = vec![0; length];
+                        reader.read_exact(&mut body).unwrap();
+                        let request: Value = serde_json::from_slice(&body).unwrap();
+                        let result = respond(&request, &mut deployed.lock().unwrap());
+                        let response =
+                            json!({"jsonrpc": "2.0", "id": request["id"], "result": result})
+                                .to_string();
+                        write!(
+                            stream,
+                            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                            response.len(),
+                            response
+                        )
+                        .unwrap();
+                    }
+                });
+            }
+        });
+        url
+    }
+
+    /// The result of a JSON-RPC `request` to a [`mock_node`].
+    fn respond(request: &Value, deployed: &mut Deployed) -> Value {
+        let params = &request["params"];
+        match request["method"].as_str().unwrap() {
+            "eth_chainId" => json!("0x7a69"),
+            "eth_getTransactionCount" | "eth_maxPriorityFeePerGas" => json!("0x0"),
+            "eth_estimateGas" => json!("0x1c9c380"),
+            "eth_gasPrice" => json!("0x1"),
+            "eth_blockNumber" => json!("0x1"),
+            "eth_feeHistory" => json!({
+                "oldestBlock": "0x1",
+                "baseFeePerGas": ["0x1", "0x1"],
+                "gasUsedRatio": [0.5],
+                "reward": [["0x0"]]
+            }),
+            "eth_getBlockByNumber" => json!({
+                "number": "0x1",
+                "hash": format!("0x{}", "11".repeat(32)),
+                "parentHash": format!("0x{}", "00".repeat(32)),
+                "baseFeePerGas": "0x1",
+                "gasLimit": "0x1c9c380",
+                "gasUsed": "0x0",
+                "timestamp": "0x1",
+                "transactions": []
+            }),
+            "eth_sendRawTransaction" => {
+                let raw =
+                    hex::decode(params[0].as_str().unwrap().trim_start_matches("0x")).unwrap();
+                let tx = TxEnvelope::decode_2718(&mut raw.as_slice()).unwrap();
+                assert!(tx.to().is_create());
+                let (db, address, _, _) = deploy(tx.input(), EvmVersion::default()).unwrap();
+                let hash = format!("0x{}", hex::encode(keccak256(&raw)));
+                *deployed = Some((db, address, hash.clone()));
+                json!(hash)
+            }
+            "eth_getTransactionReceipt" => {
+                let (_, address, hash) = deployed.as_ref().unwrap();
+                assert_eq!(params[0], json!(hash));
+                json!({
+                    "type": "0x2",
+                    "status": "0x1",
+                    "transactionHash": hash,
+                    "transactionIndex": "0x0",
+                    "blockHash": format!("0x{}", "11".repeat(32)),
+                    "blockNumber": "0x1",
+                    "from": "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+                    "to": null,
+                    "contractAddress": address.to_string(),
+                    "gasUsed": "0x5208",
+                    "cumulativeGasUsed": "0x5208",
+                    "effectiveGasPrice": "0x1",
+                    "logs": [],
+                    "logsBloom": format!("0x{}", "00".repeat(256))
+                })
+            }
+            "eth_call" => {
+                let (db, address, _) = deployed.as_ref().unwrap();
+                assert_eq!(params[0]["to"], json!(address.to_string().to_lowercase()));
+                let input = params[0]["input"].as_str().or(params[0]["data"].as_str());
+                let input = hex::decode(input.unwrap().trim_start_matches("0x")).unwrap();
+                let (result, _) = transact(
+                    db.clone(),
+                    EvmVersion::default(),
+                    TxKind::Call(*address),
+                    input,
+                )
+                .unwrap();
+                match result {
+                    ExecutionResult::Success {
+                        output: Output::Call(output),
+                        ..
+                    } => json!(format!("0x{}", hex::encode(output))),
+                    result => panic!("The call failed: {:?}", result),
+                }
+            }
+            method => panic!("Unexpected JSON-RPC method {}", method),
+        }
+    }
+
+    #[test]
+    fn verifiers_are_deployed_and_called_over_json_rpc() {
+        let (key, proof, public) = synthetic_circuit(2);
+        let mut wrong = public.clone();
+        wrong.0[1] = "1".to_string();
+        let options = VerifierOptions::default();
+
+        let deployment = deploy_verifier(
+            &mock_node(),
+            PRIVATE_KEY,
+            &key,
+            &options,
+            Some((&proof, &public)),
+        )
+        .unwrap();
+        assert!(deployment.address.starts_with("0x") && deployment.address.len() == 42);
+        assert_eq!(deployment.tx_hash.len(), 66);
+        assert_eq!(deployment.verified, Some(true));
+
+        let deployment = deploy_verifier(
+            &mock_node(),
+            PRIVATE_KEY,
+            &key,
+            &options,
+            Some((&proof, &wrong)),
+        )
+        .unwrap();
+        assert_eq!(deployment.verified, Some(false));
+        let deployment = deploy_verifier(&mock_node(), PRIVATE_KEY, &key, &options, None).unwrap();
+        assert_eq!(deployment.verified, None);
+    }
+
+    #[test]
+    fn invalid_private_keys_are_not_echoed() {
+        let (key, _, _) = synthetic_circuit(1);
+        let error = deploy_verifier(
+            "http://127.0.0.1:1",
+            "0xnotakey",
+            &key,
+            &VerifierOptions::default(),
+            None,
+        )
+        .unwrap_err();
+        assert!(!error.to_string().contains("notakey"));
+    }
+}
@@ -0,0 +1,118 @@
+use crate::calldata::encode_calldata;
+use crate::codegen::{Abi, MemoryLayout, PublicInputOrder};
+use crate::error::Result;
+use crate::proof::{Proof, PublicSignals};
+use crate::utils::{parse_num, render_template, Q};
+use crate::vkey::VerificationKey;
+use ibig::UBig;
+use serde::Serialize;
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The Foundry test template contract
+pub static VERIFIER_TEST_CONTRACT: &str = include_str!("contracts/VerifierTestTemplate.sol");
+
+////////////////////////////////////////////////////////////////
+//                          SCAFFOLD                          //
+////////////////////////////////////////////////////////////////
+
+/// Generates a Foundry test for the Huff verifier of `key`, which deploys it with
+/// `HuffDeployer` and checks both the accept and reject paths with `proof`.
+///
+/// With `fuzz`, the test also fuzzes the calldata of `proof`, one word at a time, and
+/// checks that every mutation is rejected, and its invariant tests check that the
+/// verifier never accepts `proof` with public inputs other than `public`.
+///
+/// `verifier_path` is the path to the generated verifier as `HuffDeployer` expects
+/// it: relative to `src/`, without the `.huff` extension.
+pub fn generate_test_scaffold(
+    key: &VerificationKey,
+    proof: &Proof,
+    public: &PublicSignals,
+    order: PublicInputOrder,
+    verifier_path: &str,
+    fuzz: bool,
+) -> Result<String> {
+    let layout = MemoryLayout::new(key.ic.len());
+
+    // Negating `proof_c` keeps it on the curve, so the pairing check fails
+    // instead of the precompile reverting.
+    let mut invalid_proof = proof.clone();
+    invalid_proof.pi_c[1] = negate_coordinate(&proof.pi_c[1])?;
+
+    // The test's own `MAIN` decodes `verify` calls, whatever the verifier's ABI
+    let valid_calldata = encode_calldata(proof, public, order, Abi::Huffv)?;
+    let invalid_calldata = encode_calldata(&invalid_proof, public, order, Abi::Huffv)?;
+
+    // The verified words follow the selector: the proof, the offset of the public
+    // inputs (which the test's `MAIN` skips), their length, and the public inputs
+    let head_bytes = 4 + 10 * 0x20;
+    let fuzz = fuzz.then(|| FuzzContext {
+        n_verified_words: 8 + 1 + public.0.len(),
+        n_inputs: public.0.len(),
+        proof_head: valid_calldata[2..2 + 2 * head_bytes].to_string(),
+        public_inputs: valid_calldata.as_bytes()[2 + 2 * head_bytes..]
+            .chunks(64)
+            .map(|word| format!("0x{}", String::from_utf8_lossy(word)))
+            .collect(),
+    });
+
+    let context = ScaffoldContext {
+        proof_ptr: format!("0x{:02x}", layout.input_ptr()),
+        pub_input_len_ptr: format!("0x{:02x}", layout.pub_input_len_ptr()),
+        vkey_hash: hex::encode(key.hash()?),
+        valid_proof: valid_calldata[2..].to_string(),
+        invalid_proof: invalid_calldata[2..].to_string(),
+        verifier_path: verifier_path.to_string(),
+        fuzz,
+    };
+    render_template(VERIFIER_TEST_CONTRACT, &context)
+}
+
+/// The values the Foundry test template is rendered with.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct ScaffoldContext {
+    /// The location of the proof in memory.
+    proof_ptr: String,
+    /// The location of the length of the public inputs in memory.
+    pub_input_len_ptr: String,
+    /// The keccak256 hash of the packed verification key, as hex without a `0x` prefix.
+    vkey_hash: String,
+    /// The calldata of the valid proof, as hex without a `0x` prefix.
+    valid_proof: String,
+    /// The calldata of the invalid proof, as hex without a `0x` prefix.
+    invalid_proof: String,
+    /// The path to the verifier, as `HuffDeployer` expects it.
+    verifier_path: String,
+    /// The fuzz and invariant tests, if the test has them.
+    fuzz: Option<FuzzContext>,
+}
+
+/// The values of the fuzz and invariant tests.
+#[derive(Serialize, Debug, Clone)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+struct FuzzContext {
+    /// The number of calldata words the test's `MAIN` passes to the verifier.
+    n_verified_words: usize,
+    /// The number of public inputs.
+    n_inputs: usize,
+    /// The calldata of the valid proof up to its public inputs, as hex without a `0x`
+    /// prefix.
+    proof_head: String,
+    /// The public inputs of the valid proof, in calldata order, as `0x` prefixed words.
+    public_inputs: Vec<String>,
+}
+
+////////////////////////////////////////////////////////////////
+//                      HELPER FUNCTIONS                      //
+////////////////////////////////////////////////////////////////
+
+/// Negates a G1 `y` coordinate in the base field, as `NEGATE` does.
+fn negate_coordinate(y: &str) -> Result<String> {
+    let q = UBig::from_str_radix(Q, 16).expect("Q is valid hex.");
+    let y = parse_num(y)?;
+    Ok(((&q - y % &q) % &q).to_string())
+}
@@ -0,0 +1,251 @@
+use crate::bench::{deploy, transact, verifier_transactions};
+use crate::codegen::{EvmVersion, VerifierOptions};
+use crate::error::Result;
+use crate::proof::{Proof, PublicSignals};
+use crate::vectors::Outcome;
+use crate::vkey::VerificationKey;
+use revm::primitives::{ExecutionResult, Output, TxKind, U256};
+use serde::Serialize;
+use std::fmt;
+
+////////////////////////////////////////////////////////////////
+//                         SIMULATION                         //
+////////////////////////////////////////////////////////////////
+
+/// The result of a call to a verifier, deployed and called under revm.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Simulation {
+    /// Whether the verifier returned true, returned false, or reverted.
+    pub outcome: Outcome,
+    /// The gas of the transaction of the call.
+    pub gas_used: u64,
+}
+
+/// Simulation implementation
+impl Simulation {
+    /// Whether the verifier verified the proof.
+    pub fn verified(&self) -> bool {
+        self.outcome == Outcome::Accept
+    }
+}
+
+impl fmt::Display for Simulation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let result = match self.outcome {
+            Outcome::Accept => "true",
+            Outcome::Reject => "false",
+            Outcome::Revert => "reverted",
+        };
+        writeln!(f, "Result:              {}", result)?;
+        write!(f, "Gas used:            {}", self.gas_used)
+    }
+}
+
+/// Deploys the verifier of `key` generated with `options` under revm, and calls it
+/// once to verify `proof` of the `public` signals.
+///
+/// Unlike [`crate::bench_verifier`], a proof that doesn't verify is a result, not an
+/// error. The verifier is deployed as `huffv bench` deploys it, so it needs a key it
+/// can be deployed with alone.
+pub fn simulate_verifier(
+    key: &VerificationKey,
+    options: &VerifierOptions,
+    proof: &Proof,
+    public: &PublicSignals,
+) -> Result<Simulation> {
+    let (initcode, calldata) = verifier_transactions(key, options, proof, public)?;
+    simulate_initcode(&initcode, &calldata, options.evm_version)
+}
+
+/// Deploys a verifier with `initcode` under revm, and calls it once with `calldata`.
+/// The verifier accepts the call if the first word of its output is 1.
+pub fn simulate_initcode(
+    initcode: &[u8],
+    calldata: &[u8],
+    evm_version: EvmVersion,
+) -> Result<Simulation> {
+    let (db, verifier, _, _) = deploy(initcode, evm_version)?;
+    let (result, _) = transact(db, evm_version, TxKind::Call(verifier), calldata.to_vec())?;
+    let outcome = match &result {
+        ExecutionResult::Success {
+            output: Output::Call(output),
+            ..
+        } if output.len() >= 0x20 && U256::from_be_slice(&output[..0x20]) == U256::from(1) => {
+            Outcome::Accept
+        }
+        ExecutionResult::Success { .. } => Outcome::Reject,
+        ExecutionResult::Revert { .. } | ExecutionResult::Halt { .. } => Outcome::Revert,
+    };
+    Ok(Simulation {
+        outcome,
+        gas_used: result.gas_used(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::{synthetic_circuit, verifier_calldata};
+    use crate::codegen::{OnFailure, Optimization, PublicInputOrder, DEFAULT_EVENT_SIGNATURE};
+    use crate::utils::{keccak256, parse_num};
+    use ark_bn254::Fr;
+    use ark_ff::PrimeField;
+    use ibig::UBig;
+    use revm::primitives::B256;
+
+    #[test]
+    fn simulation_reports_the_result_of_the_call() {
+        let (key, proof, public) = synthetic_circuit(2);
+        let mut wrong = public.clone();
+        wrong.0[1] = "1".to_string();
+        let reverting = VerifierOptions {
+            on_failure: OnFailure::Revert,
+            ..VerifierOptions::default()
+        };
+
+        let valid = simulate_verifier(&key, &VerifierOptions::default(), &proof, &public).unwrap();
+        assert_eq!(valid.outcome, Outcome::Accept);
+        assert!(valid.gas_used > 21_000);
+        let invalid = simulate_verifier(&key, &VerifierOptions::default(), &proof, &wrong).unwrap();
+        assert_eq!(invalid.outcome, Outcome::Reject);
+        let invalid = simulate_verifier(&key, &reverting, &proof, &wrong).unwrap();
+        assert_eq!(invalid.outcome, Outcome::Revert);
+    }
+
+    #[test]
+    fn public_inputs_outside_of_the_scalar_field_revert() {
+        let (key, proof, public) = synthetic_circuit(2);
+        let r = parse_num(&Fr::MODULUS.to_string()).unwrap();
+        let x = parse_num(&public.0[1]).unwrap();
+        for optimization in [Optimization::Gas, Optimization::Size] {
+            let options = VerifierOptions {
+                optimization,
+                ..VerifierOptions::default()
+            };
+            // r + x would verify as x if it were reduced rather than rejected
+            for input in [&r + UBig::from(1u8), &r + &x] {
+                let mut aliased = public.clone();
+                aliased.0[1] = input.to_string();
+                let simulation = simulate_verifier(&key, &options, &proof, &aliased).unwrap();
+                assert_eq!(
+                    simulation.outcome,
+                    Outcome::Revert,
+                    "{:?}: {}",
+                    optimization,
+                    input
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn verifiers_return_a_32_byte_abi_encoded_bool() {
+        let (key, proof, public) = synthetic_circuit(2);
+        let mut wrong = public.clone();
+        wrong.0[1] = "1".to_string();
+        let rejected = [0; 0x20];
+        let mut accepted = rejected;
+        accepted[0x1f] = 1;
+        for optimization in [Optimization::Gas, Optimization::Size] {
+            let options = VerifierOptions {
+                optimization,
+                ..VerifierOptions::default()
+            };
+            let output = |public: &PublicSignals| {
+                let (initcode, calldata) =
+                    verifier_transactions(&key, &options, &proof, public).unwrap();
+                let (db, verifier, _, _) = deploy(&initcode, options.evm_version).unwrap();
+                let (result, _) =
+                    transact(db, options.evm_version, TxKind::Call(verifier), calldata).unwrap();
+                assert!(result.is_success());
+                result.into_output().unwrap()
+            };
+
+            assert_eq!(output(&public)[..], accepted, "{:?}", optimization);
+            assert_eq!(output(&wrong)[..], rejected, "{:?}", optimization);
+        }
+    }
+
+    #[test]
+    fn verifier_decompresses_compressed_proofs() {
+        let (key, proof, public) = synthetic_circuit(2);
+        let options = VerifierOptions {
+            compressed_proof: true,
+            ..VerifierOptions::default()
+        };
+
+        let simulation = simulate_verifier(&key, &options, &proof, &public).unwrap();
+        assert_eq!(simulation.outcome, Outcome::Accept);
+    }
+
+    #[test]
+    fn reversed_verifiers_pair_the_first_input_with_the_last_ic() {
+        let (key, proof, public) = synthetic_circuit(3);
+        let reversed = PublicSignals(public.0.iter().rev().cloned().collect());
+        // Unrolled, and looping over the inputs
+        for optimization in [Optimization::Gas, Optimization::Size] {
+            let forward = VerifierOptions {
+                optimization,
+                ..VerifierOptions::default()
+            };
+            let reverse = VerifierOptions {
+                public_input_order: PublicInputOrder::Reverse,
+                ..forward.clone()
+            };
+            let (initcode, _) = verifier_transactions(&key, &reverse, &proof, &public).unwrap();
+
+            // `input[0]` is `public[n - 1]`, paired with `IC[n]`
+            let calldata = verifier_calldata(&key, &forward, &proof, &reversed).unwrap();
+            let simulation = simulate_initcode(&initcode, &calldata, forward.evm_version).unwrap();
+            assert_eq!(simulation.outcome, Outcome::Accept, "{:?}", optimization);
+            let calldata = verifier_calldata(&key, &forward, &proof, &public).unwrap();
+            let simulation = simulate_initcode(&initcode, &calldata, forward.evm_version).unwrap();
+            assert_eq!(simulation.outcome, Outcome::Reject, "{:?}", optimization);
+        }
+    }
+
+    #[test]
+    fn verified_proofs_emit_the_event_with_the_key_hash() {
+        let (key, proof, public) = synthetic_circuit(2);
+        let mut wrong = public.clone();
+        wrong.0[1] = "1".to_string();
+        let options = VerifierOptions {
+            event_signature: Some(DEFAULT_EVENT_SIGNATURE.to_string()),
+            ..VerifierOptions::default()
+        };
+        let logs = |public: &PublicSignals, options: &VerifierOptions| {
+            let (initcode, calldata) =
+                verifier_transactions(&key, options, &proof, public).unwrap();
+            let (db, verifier, _, _) = deploy(&initcode, options.evm_version).unwrap();
+            let (result, _) =
+                transact(db, options.evm_version, TxKind::Call(verifier), calldata).unwrap();
+            assert!(result.is_success());
+            result.into_logs()
+        };
+        let topics = vec![
+            B256::from(keccak256(DEFAULT_EVENT_SIGNATURE.as_bytes())),
+            B256::from(key.hash().unwrap()),
+        ];
+
+        let verified = logs(&public, &options);
+        assert_eq!(verified.len(), 1);
+        assert_eq!(verified[0].topics(), topics);
+        assert!(verified[0].data.data.is_empty());
+        assert!(logs(&wrong, &options).is_empty());
+
+        // With the hash of the public inputs as the event's data
+        let options = VerifierOptions {
+            event_signature: Some("ProofVerified(bytes32,bytes32)".to_string()),
+            event_inputs_hash: true,
+            ..VerifierOptions::default()
+        };
+        let verified = logs(&public, &options);
+        let inputs = hex::decode(public.encode(PublicInputOrder::Forward).unwrap()).unwrap();
+        assert_eq!(
+            verified[0].topics()[0],
+            B256::from(keccak256(b"ProofVerified(bytes32,bytes32)"))
+        );
+        assert_eq!(verified[0].data.data[..], keccak256(&inputs));
+    }
+}
@@ -0,0 +1,68 @@
+use crate::codegen::MemoryLayout;
+use serde::Serialize;
+use std::fmt;
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The maximum runtime bytecode size of a contract (EIP-170)
+pub const EIP170_LIMIT: usize = 24576;
+
+/// The byte size of the packed verification key without its ICs (alpha, beta, gamma, delta)
+pub const VKEY_POINTS_SIZE: usize = 0x1E0;
+
+////////////////////////////////////////////////////////////////
+//                        SIZE REPORT                         //
+////////////////////////////////////////////////////////////////
+
+/// The runtime bytecode size of a compiled verifier, and how much of it is the
+/// packed verification key.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SizeReport {
+    /// The number of public inputs.
+    pub n_inputs: usize,
+    /// The size of the runtime bytecode.
+    pub runtime: usize,
+    /// The size of the `VKEY` code table.
+    pub vkey_table: usize,
+}
+
+/// SizeReport implementation
+impl SizeReport {
+    /// The size report of the verifier with memory `layout`, whose runtime bytecode
+    /// is `runtime` bytes.
+    pub fn new(layout: &MemoryLayout, runtime: usize) -> Self {
+        Self {
+            n_inputs: layout.n_ics.saturating_sub(1),
+            runtime,
+            vkey_table: VKEY_POINTS_SIZE + layout.ic_bytes(),
+        }
+    }
+
+    /// The size of the runtime bytecode that isn't the `VKEY` table.
+    pub fn code(&self) -> usize {
+        self.runtime.saturating_sub(self.vkey_table)
+    }
+
+    /// The bytes left before the runtime bytecode reaches the EIP-170 limit, negative
+    /// if it is already over the limit.
+    pub fn headroom(&self) -> isize {
+        EIP170_LIMIT as isize - self.runtime as isize
+    }
+}
+
+impl fmt::Display for SizeReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Public inputs:       {}", self.n_inputs)?;
+        writeln!(f)?;
+        writeln!(f, "| SECTION            | BYTES    |")?;
+        writeln!(f, "| ------------------ | -------- |")?;
+        writeln!(f, "| Code               | {:>8} |", self.code())?;
+        writeln!(f, "| VKEY table         | {:>8} |", self.vkey_table)?;
+        writeln!(f, "| Runtime            | {:>8} |", self.runtime)?;
+        writeln!(f, "| EIP-170 limit      | {:>8} |", EIP170_LIMIT)?;
+        write!(f, "| Headroom           | {:>8} |", self.headroom())
+    }
+}
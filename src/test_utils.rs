@@ -0,0 +1,20 @@
+//! Fixtures shared by the unit tests of the crate's modules.
+
+use crate::vkey::VerificationKey;
+use serde_json::Value;
+
+/// The multi input sample verification key, as JSON.
+pub(crate) fn sample_json() -> Value {
+    serde_json::from_slice(include_bytes!(
+        "../test/multi-input/sample_verification_key.json"
+    ))
+    .unwrap()
+}
+
+/// The multi input sample verification key, with 3 ICs.
+pub(crate) fn sample_key() -> VerificationKey {
+    VerificationKey::from_bytes(include_bytes!(
+        "../test/multi-input/sample_verification_key.json"
+    ))
+    .unwrap()
+}
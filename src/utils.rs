@@ -0,0 +1,75 @@
+use crate::error::{HuffvError, Result};
+use handlebars::Handlebars;
+use ibig::UBig;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tiny_keccak::{Hasher, Keccak};
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The prime q of the base field F_q for G1, in hex
+pub static Q: &str = "30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47";
+
+////////////////////////////////////////////////////////////////
+//                      HELPER FUNCTIONS                      //
+////////////////////////////////////////////////////////////////
+
+/// Parses a string that contains a decimal number, or a `0x` prefixed hex number.
+pub fn parse_num(n: &str) -> Result<UBig> {
+    let parsed = match n.strip_prefix("0x").or_else(|| n.strip_prefix("0X")) {
+        Some(hex) => UBig::from_str_radix(hex, 16),
+        None => UBig::from_str_radix(n, 10),
+    };
+    parsed.map_err(|_| HuffvError::NumberParse(n.to_string()))
+}
+
+/// Encodes a string that contains a 256 bit decimal or `0x` prefixed hex number as a
+/// 32 byte hex string. Negative numbers and numbers wider than 256 bits are rejected.
+pub fn encode_num(n: &str) -> Result<String> {
+    let num = parse_num(n)?;
+    let encoded = num.in_radix(16).to_string();
+    if encoded.len() > 64 {
+        return Err(HuffvError::NumberParse(n.to_string()));
+    }
+
+    // If the encoded hex isn't 32 bytes in length, pad the beginning with
+    // zero bytes.
+    Ok(format!("{:0>64}", encoded))
+}
+
+/// Computes the keccak256 hash of `data`.
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut hash = [0u8; 32];
+    hasher.update(data);
+    hasher.finalize(&mut hash);
+    hash
+}
+
+/// Computes the 4 byte selector of a function `signature`.
+pub fn signature_selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Deserializes a JSON file at `path`.
+pub fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let contents = fs::File::open(path).map_err(|e| HuffvError::io(path, e))?;
+    Ok(serde_json::from_reader(contents)?)
+}
+
+/// Renders a handlebars `template` with `context`, failing on any tag that the
+/// context does not fill.
+pub fn render_template<T: Serialize>(template: &str, context: &T) -> Result<String> {
+    let mut handlebars = Handlebars::new();
+    handlebars.set_strict_mode(true);
+    // Templates are contracts, not HTML
+    handlebars.register_escape_fn(handlebars::no_escape);
+    handlebars
+        .render_template(template, context)
+        .map_err(|e| HuffvError::Template(e.to_string()))
+}
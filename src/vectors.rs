@@ -0,0 +1,240 @@
+use crate::calldata::{encode_calldata_with, encode_calldata_with_key};
+use crate::codegen::{OnFailure, VerifierOptions};
+use crate::error::{HuffvError, Result};
+use crate::proof::{Proof, PublicSignals};
+use crate::utils::{parse_num, Q};
+use crate::vkey::VerificationKey;
+use ark_bn254::{Fq, Fq2, Fr, G1Affine, G2Affine};
+use ark_ff::{BigInteger, PrimeField};
+use ibig::UBig;
+use serde::Serialize;
+
+////////////////////////////////////////////////////////////////
+//                        TEST VECTORS                        //
+////////////////////////////////////////////////////////////////
+
+/// What a verifier is expected to do with the calldata of a test vector.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    /// Return `true`.
+    Accept,
+    /// Return `false`, as the pairing check fails.
+    Reject,
+    /// Revert, with or without data.
+    Revert,
+}
+
+/// The calldata of a call to the verification function, and what the verifier is
+/// expected to do with it.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TestVector {
+    /// A unique, snake_case name of the vector.
+    pub name: String,
+    /// How the calldata differs from the valid proof's.
+    pub description: String,
+    /// The `0x` prefixed calldata.
+    pub calldata: String,
+    /// What the verifier is expected to do with the calldata.
+    pub expected: Outcome,
+}
+
+/// The test vectors of a proof, as written by `huffv vectors`.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TestVectors {
+    /// The number of public inputs of the proof.
+    pub n_inputs: usize,
+    /// The valid proof first, then its malformed variants.
+    pub vectors: Vec<TestVector>,
+}
+
+/// Generates test vectors for a verifier generated with `options` from the valid
+/// `proof` of the `public` signals: the calldata of the proof, which is accepted, and
+/// with `negative`, systematically malformed variants of it:
+///
+/// - `proof_b` with the limbs of its coordinates swapped, the most common encoding bug
+/// - `proof_a` and `proof_c` off the curve
+/// - each public input plus the scalar field modulus `r`, which would verify if the
+///   verifier reduced it
+/// - the calldata truncated, by a byte and to the selector alone
+/// - the calldata with a word and a byte appended, which the verifier ignores
+///
+/// The expected outcomes follow the options that change them: a proof that fails the
+/// pairing check is rejected, or reverts with `--on-failure revert`. Points off their
+/// curve fail the pairing precompile, so the verifier reverts.
+///
+/// Verifiers generated with [`crate::codegen::VkeyStorage::Calldata`] take `key` with
+/// the proof, as [`encode_calldata_with_key`] encodes it.
+pub fn generate_test_vectors(
+    proof: &Proof,
+    public: &PublicSignals,
+    options: &VerifierOptions,
+    key: Option<&VerificationKey>,
+    negative: bool,
+) -> Result<TestVectors> {
+    if options.compressed_proof {
+        return Err(HuffvError::InvalidOptions(
+            "Test vectors mutate the coordinates of the proof, which `--compressed-proof` drops."
+                .to_string(),
+        ));
+    }
+    let encode = |proof: &Proof, public: &PublicSignals| match key {
+        Some(key) => encode_calldata_with_key(key, proof, public, options),
+        None => encode_calldata_with(proof, public, options),
+    };
+    let failure = match options.on_failure {
+        OnFailure::Return => Outcome::Reject,
+        OnFailure::Revert => Outcome::Revert,
+    };
+
+    let calldata = encode(proof, public)?;
+    let mut vectors = vec![TestVector {
+        name: "valid".to_string(),
+        description: "The valid proof.".to_string(),
+        calldata: calldata.clone(),
+        expected: Outcome::Accept,
+    }];
+    if !negative {
+        return Ok(TestVectors {
+            n_inputs: public.0.len(),
+            vectors,
+        });
+    }
+    let mut push = |name: &str, description: &str, calldata: String, expected: Outcome| {
+        vectors.push(TestVector {
+            name: name.to_string(),
+            description: description.to_string(),
+            calldata,
+            expected,
+        })
+    };
+
+    // Swapped limbs are still in the base field, but almost never on the twist
+    let mut swapped = proof.clone();
+    swapped.pi_b[0].swap(0, 1);
+    swapped.pi_b[1].swap(0, 1);
+    push(
+        "proof_b_swapped_limbs",
+        "proof_b with the limbs of both of its coordinates swapped.",
+        encode(&swapped, public)?,
+        if is_g2_point(&swapped.pi_b)? {
+            failure
+        } else {
+            Outcome::Revert
+        },
+    );
+
+    let (a, c) = (off_curve(&proof.pi_a)?, off_curve(&proof.pi_c)?);
+    for (name, point, off_curve) in [
+        (
+            "proof_a",
+            &a,
+            Proof {
+                pi_a: a.clone(),
+                ..proof.clone()
+            },
+        ),
+        (
+            "proof_c",
+            &c,
+            Proof {
+                pi_c: c.clone(),
+                ..proof.clone()
+            },
+        ),
+    ] {
+        push(
+            &format!("{}_off_curve", name),
+            &format!("{} with 1 added to its y coordinate, off the curve.", name),
+            encode(&off_curve, public)?,
+            if is_g1_point(point)? {
+                failure
+            } else {
+                Outcome::Revert
+            },
+        );
+    }
+
+    let r = UBig::from_be_bytes(&Fr::MODULUS.to_bytes_be());
+    for i in 0..public.0.len() {
+        let mut aliased = public.clone();
+        aliased.0[i] = (parse_num(&public.0[i])? + &r).to_string();
+        push(
+            &format!("public_input_{}_plus_r", i),
+            &format!(
+                "public[{}] plus the scalar field modulus r, not less than r.",
+                i
+            ),
+            encode(proof, &aliased)?,
+            Outcome::Revert,
+        );
+    }
+
+    push(
+        "calldata_truncated_by_byte",
+        "The calldata without its last byte.",
+        calldata[..calldata.len() - 2].to_string(),
+        Outcome::Revert,
+    );
+    push(
+        "calldata_selector_only",
+        "The selector of the verification function alone.",
+        calldata[..10].to_string(),
+        Outcome::Revert,
+    );
+    push(
+        "calldata_extra_word",
+        "The calldata with a zero word appended.",
+        format!("{}{}", calldata, "00".repeat(0x20)),
+        Outcome::Accept,
+    );
+    push(
+        "calldata_extra_byte",
+        "The calldata with a byte appended.",
+        format!("{}ff", calldata),
+        Outcome::Accept,
+    );
+
+    Ok(TestVectors {
+        n_inputs: public.0.len(),
+        vectors,
+    })
+}
+
+////////////////////////////////////////////////////////////////
+//                      HELPER FUNCTIONS                      //
+////////////////////////////////////////////////////////////////
+
+/// The base field modulus.
+pub(crate) fn q() -> UBig {
+    UBig::from_str_radix(Q, 16).expect("Q is valid hex.")
+}
+
+/// The G1 point `[x, y + 1, ..]` of `[x, y, ..]`, which is off the curve unless
+/// `y + 1` happens to be `-y`.
+pub(crate) fn off_curve(point: &[String]) -> Result<Vec<String>> {
+    let mut point = point.to_vec();
+    point[1] = ((parse_num(&point[1])? + UBig::from(1u8)) % q()).to_string();
+    Ok(point)
+}
+
+/// An element of the base field, from a coordinate of a valid proof.
+fn fq(n: &str) -> Result<Fq> {
+    Ok(Fq::from_be_bytes_mod_order(&parse_num(n)?.to_be_bytes()))
+}
+
+/// Whether the snarkjs coordinates `[x, y, ..]` are a point on G1, which the
+/// pairing precompile accepts.
+fn is_g1_point(point: &[String]) -> Result<bool> {
+    Ok(G1Affine::new_unchecked(fq(&point[0])?, fq(&point[1])?).is_on_curve())
+}
+
+/// Whether the snarkjs coordinates `[[x.c0, x.c1], [y.c0, y.c1], ..]` are a point in
+/// the G2 subgroup, which the pairing precompile accepts.
+fn is_g2_point(point: &[Vec<String>]) -> Result<bool> {
+    let fq2 = |limbs: &[String]| -> Result<Fq2> { Ok(Fq2::new(fq(&limbs[0])?, fq(&limbs[1])?)) };
+    let point = G2Affine::new_unchecked(fq2(&point[0])?, fq2(&point[1])?);
+    Ok(point.is_on_curve() && point.is_in_correct_subgroup_assuming_on_curve())
+}
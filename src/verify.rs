@@ -0,0 +1,194 @@
+use crate::error::{HuffvError, Result};
+use crate::proof::{Proof, PublicSignals};
+use crate::utils::parse_num;
+use crate::vkey::VerificationKey;
+use ark_bn254::{Bn254, Fq, Fq12, Fq2, Fq6, Fr, G1Affine, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup};
+use ark_ff::{PrimeField, Zero};
+use ibig::UBig;
+
+/// Verifies a Groth16 `proof` of the `public` signals against `key` off-chain.
+///
+/// This runs the same pairing check as the generated verifier:
+/// `e(-proof_a, proof_b) * e(alpha, beta) * e(vk_x, gamma) * e(proof_c, delta) == 1`,
+/// where `vk_x = IC[0] + sum(public[i] * IC[i + 1])`.
+pub fn verify_proof(key: &VerificationKey, proof: &Proof, public: &PublicSignals) -> Result<bool> {
+    if public.0.len() + 1 != key.ic.len() {
+        return Err(HuffvError::InvalidProof(format!(
+            "Expected {} public signals for a key with {} ICs, found {}.",
+            key.ic.len().saturating_sub(1),
+            key.ic.len(),
+            public.0.len()
+        )));
+    }
+
+    // Compute the linear combination vk_x
+    let ic = key
+        .ic
+        .iter()
+        .enumerate()
+        .map(|(i, point)| g1(&format!("IC[{}]", i), point).map_err(HuffvError::InvalidKey))
+        .collect::<Result<Vec<_>>>()?;
+    let mut vk_x = ic[0].into_group();
+    for (i, (input, point)) in public.0.iter().zip(&ic[1..]).enumerate() {
+        let input: Fr =
+            field_element(&format!("public[{}]", i), input).map_err(HuffvError::InvalidProof)?;
+        vk_x += *point * input;
+    }
+
+    let alpha = g1("vk_alpha_1", &key.vk_alpha_1).map_err(HuffvError::InvalidKey)?;
+    let beta = g2("vk_beta_2", &key.vk_beta_2).map_err(HuffvError::InvalidKey)?;
+    let gamma = g2("vk_gamma_2", &key.vk_gamma_2).map_err(HuffvError::InvalidKey)?;
+    let delta = g2("vk_delta_2", &key.vk_delta_2).map_err(HuffvError::InvalidKey)?;
+    let a = g1("pi_a", &proof.pi_a).map_err(HuffvError::InvalidProof)?;
+    let b = g2("pi_b", &proof.pi_b).map_err(HuffvError::InvalidProof)?;
+    let c = g1("pi_c", &proof.pi_c).map_err(HuffvError::InvalidProof)?;
+
+    Ok(Bn254::multi_pairing([-a, alpha, vk_x.into_affine(), c], [b, beta, gamma, delta]).is_zero())
+}
+
+/// Checks that every point of `key` is a valid BN254 point: alpha and the ICs on
+/// G1, and beta, gamma, and delta on G2 and in its prime order subgroup. If the key
+/// includes `vk_alphabeta_12`, it is also checked to equal `e(alpha, beta)`.
+///
+/// A key that fails this check can never verify a proof, so no verifier should be
+/// generated for it.
+pub fn validate_verification_key(key: &VerificationKey) -> Result<()> {
+    if key.ic.is_empty() {
+        return Err(HuffvError::InvalidKey("IC must not be empty.".to_string()));
+    }
+    std::iter::once(("vk_alpha_1".to_string(), &key.vk_alpha_1))
+        .chain(
+            key.ic
+                .iter()
+                .enumerate()
+                .map(|(i, point)| (format!("IC[{}]", i), point)),
+        )
+        .try_for_each(|(name, point)| g1(&name, point).map(|_| ()))
+        .map_err(HuffvError::InvalidKey)?;
+    [
+        ("vk_beta_2", &key.vk_beta_2),
+        ("vk_gamma_2", &key.vk_gamma_2),
+        ("vk_delta_2", &key.vk_delta_2),
+    ]
+    .into_iter()
+    .try_for_each(|(name, point)| g2(name, point).map(|_| ()))
+    .map_err(HuffvError::InvalidKey)?;
+
+    // The verifier doesn't use alphabeta, but a mismatch means the key was corrupted
+    if let Some(alphabeta) = &key.vk_alphabeta_12 {
+        let alpha = g1("vk_alpha_1", &key.vk_alpha_1).map_err(HuffvError::InvalidKey)?;
+        let beta = g2("vk_beta_2", &key.vk_beta_2).map_err(HuffvError::InvalidKey)?;
+        let alphabeta = fq12("vk_alphabeta_12", alphabeta).map_err(HuffvError::InvalidKey)?;
+        if alphabeta != Bn254::pairing(alpha, beta).0 {
+            return Err(HuffvError::InvalidKey(
+                "vk_alphabeta_12 is not e(vk_alpha_1, vk_beta_2).".to_string(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+////////////////////////////////////////////////////////////////
+//                      HELPER FUNCTIONS                      //
+////////////////////////////////////////////////////////////////
+
+/// Parses a decimal or `0x` prefixed hex string into an element of the prime field
+/// `F`, rejecting values that are not reduced modulo the field's modulus.
+fn field_element<F: PrimeField>(name: &str, n: &str) -> std::result::Result<F, String> {
+    let value = parse_num(n).map_err(|_| format!("{} is not a number.", name))?;
+    let modulus: UBig = F::MODULUS
+        .to_string()
+        .parse()
+        .expect("Field modulus is a decimal number.");
+    if value >= modulus {
+        return Err(format!("{} is not less than the field modulus.", name));
+    }
+    Ok(F::from_be_bytes_mod_order(&value.to_be_bytes()))
+}
+
+/// Parses the affine coordinates `[x, y, ..]` of a G1 point.
+fn g1(name: &str, point: &[String]) -> std::result::Result<G1Affine, String> {
+    if point.len() < 2 {
+        return Err(format!("{} must have at least 2 coordinates.", name));
+    }
+    let x = field_element::<Fq>(&format!("{}.x", name), &point[0])?;
+    let y = field_element::<Fq>(&format!("{}.y", name), &point[1])?;
+    let point = G1Affine::new_unchecked(x, y);
+    if !point.is_on_curve() {
+        return Err(format!("{} is not on the curve.", name));
+    }
+    Ok(point)
+}
+
+/// Parses the affine coordinates `[[x.c0, x.c1], [y.c0, y.c1], ..]` of a G2 point.
+fn g2(name: &str, point: &[Vec<String>]) -> std::result::Result<G2Affine, String> {
+    if point.len() < 2 || point[..2].iter().any(|c| c.len() != 2) {
+        return Err(format!(
+            "{} must have at least 2 coordinates of 2 limbs each.",
+            name
+        ));
+    }
+    let fq2 = |coord: &str, limbs: &[String]| -> std::result::Result<Fq2, String> {
+        Ok(Fq2::new(
+            field_element(&format!("{}.{}.c0", name, coord), &limbs[0])?,
+            field_element(&format!("{}.{}.c1", name, coord), &limbs[1])?,
+        ))
+    };
+    let point = G2Affine::new_unchecked(fq2("x", &point[0])?, fq2("y", &point[1])?);
+    if !point.is_on_curve() || !point.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(format!("{} is not in the G2 subgroup.", name));
+    }
+    Ok(point)
+}
+
+/// Parses the coefficients `[[[c0, c1]; 3]; 2]` of an F_q12 element, in the
+/// F_q2 → F_q6 → F_q12 tower that snarkjs shares with arkworks.
+fn fq12(name: &str, n: &[Vec<Vec<String>>]) -> std::result::Result<Fq12, String> {
+    if n.len() != 2
+        || n.iter()
+            .any(|c| c.len() != 3 || c.iter().any(|c| c.len() != 2))
+    {
+        return Err(format!(
+            "{} must have 2 coefficients of 3 coefficients of 2 limbs each.",
+            name
+        ));
+    }
+    let fq2 = |i: usize, j: usize| -> std::result::Result<Fq2, String> {
+        Ok(Fq2::new(
+            field_element(&format!("{}[{}][{}][0]", name, i, j), &n[i][j][0])?,
+            field_element(&format!("{}[{}][{}][1]", name, i, j), &n[i][j][1])?,
+        ))
+    };
+    let fq6 = |i: usize| -> std::result::Result<Fq6, String> {
+        Ok(Fq6::new(fq2(i, 0)?, fq2(i, 1)?, fq2(i, 2)?))
+    };
+    Ok(Fq12::new(fq6(0)?, fq6(1)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::sample_key;
+
+    #[test]
+    fn out_of_field_g2_limbs_are_rejected_by_name() {
+        let q: UBig = Fq::MODULUS.to_string().parse().unwrap();
+        for (coord, limb) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+            let mut key = sample_key();
+            // Still the same point modulo q
+            let limb_value = parse_num(&key.vk_delta_2[coord][limb]).unwrap() + &q;
+            key.vk_delta_2[coord][limb] = limb_value.to_string();
+
+            let name = format!("vk_delta_2.{}.c{}", ["x", "y"][coord], limb);
+            match validate_verification_key(&key) {
+                Err(HuffvError::InvalidKey(message)) => assert_eq!(
+                    message,
+                    format!("{} is not less than the field modulus.", name)
+                ),
+                other => panic!("{} was not rejected: {:?}", name, other),
+            }
+        }
+        assert!(validate_verification_key(&sample_key()).is_ok());
+    }
+}
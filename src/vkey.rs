@@ -0,0 +1,450 @@
+use crate::arkworks::read_arkworks_vkey;
+use crate::error::{HuffvError, Result};
+use crate::utils::{encode_num, keccak256, parse_num, Q};
+use crate::zkey::{read_zkey, ZKEY_MAGIC};
+use clap::ValueEnum;
+use ibig::{modular::ModuloRing, UBig};
+use serde::{de, Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// A SNARK Verification Key.
+///
+/// Can be directly deserialized from a JSON key generated by
+/// [snarkjs](https://github.com/iden3/snarkjs).
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VerificationKey {
+    #[serde(default)]
+    pub protocol: Protocol,
+
+    #[serde(default)]
+    pub curve: Curve,
+
+    #[serde(rename(deserialize = "nPublic", serialize = "nPublic"))]
+    pub n_public: u64,
+
+    #[serde(deserialize_with = "deserialize_coordinates")]
+    pub vk_alpha_1: Vec<String>,
+
+    #[serde(deserialize_with = "deserialize_coordinate_lists")]
+    pub vk_beta_2: Vec<Vec<String>>,
+
+    #[serde(deserialize_with = "deserialize_coordinate_lists")]
+    pub vk_gamma_2: Vec<Vec<String>>,
+
+    #[serde(deserialize_with = "deserialize_coordinate_lists")]
+    pub vk_delta_2: Vec<Vec<String>>,
+
+    /// `e(vk_alpha_1, vk_beta_2)`, which the verifier doesn't use. Newer snarkjs
+    /// exports may omit it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vk_alphabeta_12: Option<Vec<Vec<Vec<String>>>>,
+
+    #[serde(
+        rename(deserialize = "IC", serialize = "IC"),
+        deserialize_with = "deserialize_coordinate_lists"
+    )]
+    pub ic: Vec<Vec<String>>,
+}
+
+/// The proof system of a verification key. Keys without a `protocol` are assumed
+/// to be Groth16 keys.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Protocol {
+    /// Groth16, the only proof system with a verifier template.
+    #[default]
+    Groth16,
+}
+
+/// The curve of a verification key. Keys without a `curve` are assumed to be
+/// BN254 keys.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Curve {
+    /// BN254, as snarkjs names it. The only curve with EVM precompiles.
+    #[default]
+    #[serde(alias = "bn254")]
+    Bn128,
+}
+
+/// The position of the constant term within a verification key's ICs.
+///
+/// The verifier always treats `IC[0]` as the constant term, so keys that place
+/// it last are remapped before packing.
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum IcConstantPosition {
+    /// The constant term is the first IC entry (snarkjs).
+    #[default]
+    First,
+    /// The constant term is the last IC entry.
+    Last,
+}
+
+/// The serialization of a verification key.
+#[derive(ValueEnum, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum InputFormat {
+    /// A JSON key as snarkjs exports it, or a snarkjs `.zkey`.
+    #[default]
+    Snarkjs,
+    /// An arkworks Groth16 verifying key in its canonical serialization, compressed
+    /// or not.
+    Arkworks,
+}
+
+/// A single coordinate as it appears in a verification key JSON.
+///
+/// Most exports store each coordinate as one decimal string, but some toolchains
+/// emit `0x` prefixed hex instead, and some limb-oriented tooling splits it into a
+/// `[hi, lo]` pair of 128 bit limbs.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Coordinate {
+    Single(String),
+    Limbs([String; 2]),
+}
+
+impl Coordinate {
+    /// Returns the coordinate as a single decimal string, reassembling limb pairs.
+    fn reassemble(self) -> std::result::Result<String, String> {
+        match self {
+            Coordinate::Single(n) if n.starts_with("0x") || n.starts_with("0X") => parse_num(&n)
+                .map(|n| n.to_string())
+                .map_err(|_| format!("Invalid coordinate {}.", n)),
+            Coordinate::Single(n) => Ok(n),
+            Coordinate::Limbs([hi, lo]) => {
+                let limb_bound = UBig::from(1u8) << 128;
+                let parse_limb = |limb: &str| match parse_num(limb) {
+                    Ok(limb) if limb < limb_bound => Ok(limb),
+                    Ok(_) => Err(format!(
+                        "Coordinate limb {} does not fit in 128 bits.",
+                        limb
+                    )),
+                    Err(_) => Err(format!("Invalid coordinate limb {}.", limb)),
+                };
+                Ok(((parse_limb(&hi)? << 128) + parse_limb(&lo)?).to_string())
+            }
+        }
+    }
+}
+
+/// Verification key implementation
+impl VerificationKey {
+    /// Reads a verification key from the contents of a JSON key or a snarkjs `.zkey`.
+    pub fn from_bytes(data: &[u8]) -> Result<VerificationKey> {
+        if data.starts_with(ZKEY_MAGIC) {
+            return read_zkey(data);
+        }
+        Self::from_value(serde_json::from_slice(data)?)
+    }
+
+    /// Reads a verification key from its contents in `format`.
+    pub fn from_bytes_as(data: &[u8], format: InputFormat) -> Result<VerificationKey> {
+        match format {
+            InputFormat::Snarkjs => Self::from_bytes(data),
+            InputFormat::Arkworks => read_arkworks_vkey(data),
+        }
+    }
+
+    /// Deserializes a verification key from a JSON string.
+    pub fn from_json(json: &str) -> Result<VerificationKey> {
+        Self::from_value(serde_json::from_str(json)?)
+    }
+
+    /// Deserializes a verification key from a JSON value, rejecting keys for an
+    /// unsupported protocol or curve before their other fields are read.
+    fn from_value(value: Value) -> Result<VerificationKey> {
+        let protocol = value.get("protocol");
+        let curve = value.get("curve");
+        let supported = protocol.is_none_or(|p| Protocol::deserialize(p).is_ok())
+            && curve.is_none_or(|c| Curve::deserialize(c).is_ok());
+        if !supported {
+            // Missing fields are assumed to be the defaults
+            let name = |field: Option<&Value>, default: &str| match field {
+                Some(Value::String(name)) => name.clone(),
+                Some(field) => field.to_string(),
+                None => default.to_string(),
+            };
+            return Err(HuffvError::UnsupportedKey(format!(
+                "Found a {} key on {}, but only groth16 keys on bn128 are supported.",
+                name(protocol, "groth16"),
+                name(curve, "bn128")
+            )));
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Produce a packed hex representation of the verification key
+    pub fn to_packed(&self) -> Result<String> {
+        self.check_shape()?;
+
+        // Add alpha, beta, gamma, and delta as the base.
+        let mut base = String::from("0x");
+        base.push_str(&encode_coordinate("vk_alpha_1.x", &self.vk_alpha_1[0])?);
+        base.push_str(&encode_coordinate("vk_alpha_1.y", &self.vk_alpha_1[1])?);
+        for (name, point) in [
+            ("vk_beta_2", &self.vk_beta_2),
+            ("vk_gamma_2", &self.vk_gamma_2),
+            ("vk_delta_2", &self.vk_delta_2),
+        ] {
+            // The limbs of G2 coordinates are swapped for the precompile
+            for (i, coord, j) in [(0, "x", 1), (0, "x", 0), (1, "y", 1), (1, "y", 0)] {
+                let name = format!("{}.{}.c{}", name, coord, j);
+                base.push_str(&encode_coordinate(&name, &point[i][j])?);
+            }
+        }
+
+        // Push ICs to base verification key
+        let n_ics = self.ic.len();
+        base.push_str(&encode_num(&n_ics.to_string())?);
+        for (i, ic) in self.ic.iter().enumerate() {
+            base.push_str(&encode_coordinate(&format!("IC[{}].x", i), &ic[0])?);
+            base.push_str(&encode_coordinate(&format!("IC[{}].y", i), &ic[1])?);
+        }
+
+        Ok(base)
+    }
+
+    /// Checks that every point of the key has enough coordinates to be packed.
+    fn check_shape(&self) -> Result<()> {
+        let check_g1 = |name: &str, point: &[String]| {
+            if point.len() < 2 {
+                return Err(HuffvError::InvalidKey(format!(
+                    "{} must have at least 2 coordinates, found {}.",
+                    name,
+                    point.len()
+                )));
+            }
+            Ok(())
+        };
+        let check_g2 = |name: &str, point: &[Vec<String>]| {
+            if point.len() < 2 || point[..2].iter().any(|c| c.len() != 2) {
+                return Err(HuffvError::InvalidKey(format!(
+                    "{} must have at least 2 coordinates of 2 limbs each.",
+                    name
+                )));
+            }
+            Ok(())
+        };
+
+        check_g1("vk_alpha_1", &self.vk_alpha_1)?;
+        check_g2("vk_beta_2", &self.vk_beta_2)?;
+        check_g2("vk_gamma_2", &self.vk_gamma_2)?;
+        check_g2("vk_delta_2", &self.vk_delta_2)?;
+        if self.ic.is_empty() {
+            return Err(HuffvError::InvalidKey("IC must not be empty.".to_string()));
+        }
+        self.ic
+            .iter()
+            .enumerate()
+            .try_for_each(|(i, ic)| check_g1(&format!("IC[{}]", i), ic))
+    }
+
+    /// The keccak256 hash of the packed verification key
+    pub fn hash(&self) -> Result<[u8; 32]> {
+        let packed = self.to_packed()?;
+        Ok(keccak256(
+            &hex::decode(&packed[2..]).expect("Packed verification key is valid hex."),
+        ))
+    }
+
+    /// Converts every G1 point of the key (alpha and the ICs) from Jacobian
+    /// `(x, y, z)` to affine `(x / z^2, y / z^3, 1)` coordinates over F_q.
+    pub fn normalize_points(&mut self) -> Result<()> {
+        std::iter::once(&mut self.vk_alpha_1)
+            .chain(self.ic.iter_mut())
+            .try_for_each(|point| affinize_g1(point))
+    }
+
+    /// Moves the constant term to `IC[0]` if the key stores it at `position`.
+    pub fn normalize_ic_constant(&mut self, position: IcConstantPosition) {
+        if position == IcConstantPosition::Last {
+            self.ic.rotate_right(1);
+        }
+    }
+}
+
+impl fmt::Display for VerificationKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            serde_json::to_string_pretty(self).expect("Failed to serialize verification key.")
+        )
+    }
+}
+
+////////////////////////////////////////////////////////////////
+//                      HELPER FUNCTIONS                      //
+////////////////////////////////////////////////////////////////
+
+/// Parses a verification key from a file path, either a JSON key or a snarkjs `.zkey`.
+pub fn parse_verification_key(path: &Path) -> Result<VerificationKey> {
+    let data = fs::read(path).map_err(|e| HuffvError::io(path, e))?;
+    VerificationKey::from_bytes(&data)
+}
+
+/// Encodes the coordinate `name` of a verification key as a 32 byte hex string,
+/// checking that it is an element of the base field F_q.
+fn encode_coordinate(name: &str, n: &str) -> Result<String> {
+    let q = UBig::from_str_radix(Q, 16).expect("Q is valid hex.");
+    if parse_num(n)? >= q {
+        return Err(HuffvError::InvalidKey(format!(
+            "{} is not less than the field modulus.",
+            name
+        )));
+    }
+    encode_num(n)
+}
+
+/// Converts a Jacobian G1 point `[x, y, z]` to affine coordinates in place.
+/// Points without a `z` coordinate, or with `z == 1`, are left untouched.
+fn affinize_g1(point: &mut [String]) -> Result<()> {
+    let (x, y, z) = match point {
+        [x, y, z] => (parse_num(x)?, parse_num(y)?, parse_num(z)?),
+        _ => return Ok(()),
+    };
+    if z == UBig::from(1u8) {
+        return Ok(());
+    }
+
+    let q = UBig::from_str_radix(Q, 16).expect("Q is valid hex.");
+    let ring = ModuloRing::new(&q);
+    let z = ring.from(&z);
+    if z == ring.from(0u8) {
+        return Err(HuffvError::InvalidKey(
+            "Cannot normalize a G1 point at infinity (z = 0).".to_string(),
+        ));
+    }
+    // z^-1 = z^(q - 2) mod q, as q is prime
+    let z_inv = z.pow(&(&q - UBig::from(2u8)));
+    let z_inv_2 = &z_inv * &z_inv;
+    let z_inv_3 = &z_inv_2 * &z_inv;
+
+    point[0] = (ring.from(&x) * z_inv_2).residue().to_string();
+    point[1] = (ring.from(&y) * z_inv_3).residue().to_string();
+    point[2] = "1".to_string();
+    Ok(())
+}
+
+/// Deserializes a list of coordinates, reassembling any `[hi, lo]` limb pairs.
+fn deserialize_coordinates<'de, D>(deserializer: D) -> std::result::Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<Coordinate>::deserialize(deserializer)?
+        .into_iter()
+        .map(|c| c.reassemble().map_err(de::Error::custom))
+        .collect()
+}
+
+/// Deserializes a list of coordinate lists, reassembling any `[hi, lo]` limb pairs.
+fn deserialize_coordinate_lists<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Vec<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Vec::<Vec<Coordinate>>::deserialize(deserializer)?
+        .into_iter()
+        .map(|cs| {
+            cs.into_iter()
+                .map(|c| c.reassemble().map_err(de::Error::custom))
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::synthetic_circuit;
+    use crate::codegen::VerifierOptions;
+    use crate::simulate::simulate_verifier;
+    use crate::test_utils::sample_json;
+    use crate::vectors::Outcome;
+    use serde_json::json;
+
+    /// `n` split into its `[hi, lo]` 128 bit limbs, as decimal strings.
+    fn limbs(n: &Value) -> Value {
+        let n = parse_num(n.as_str().unwrap()).unwrap();
+        let lo_mask = (UBig::from(1u8) << 128) - UBig::from(1u8);
+        json!([(&n >> 128).to_string(), (&n & &lo_mask).to_string()])
+    }
+
+    #[test]
+    fn limb_split_coordinates_reassemble_to_the_same_packed_key() {
+        let json = sample_json();
+        let mut split = json.clone();
+        split["vk_alpha_1"][0] = limbs(&json["vk_alpha_1"][0]);
+        split["vk_delta_2"][1][0] = limbs(&json["vk_delta_2"][1][0]);
+        split["IC"][2][1] = limbs(&json["IC"][2][1]);
+
+        let key = VerificationKey::from_value(json).unwrap();
+        let split_key = VerificationKey::from_value(split.clone()).unwrap();
+        assert_eq!(split_key.vk_alpha_1, key.vk_alpha_1);
+        assert_eq!(split_key.to_packed().unwrap(), key.to_packed().unwrap());
+
+        split["IC"][2][1][1] = Value::String((UBig::from(1u8) << 128).to_string());
+        let error = VerificationKey::from_value(split).unwrap_err().to_string();
+        assert!(error.contains("does not fit in 128 bits"), "{}", error);
+    }
+
+    #[test]
+    fn constant_last_ics_are_packed_with_the_constant_first() {
+        let (key, proof, public) = synthetic_circuit(2);
+        let mut last = key.clone();
+        last.ic.rotate_left(1);
+        let options = VerifierOptions::default();
+        let simulation = simulate_verifier(&last, &options, &proof, &public).unwrap();
+        assert_eq!(simulation.outcome, Outcome::Reject);
+
+        last.normalize_ic_constant(IcConstantPosition::Last);
+        let packed = last.to_packed().unwrap();
+        assert_eq!(packed, key.to_packed().unwrap());
+        // `IC[0]` follows alpha, beta, gamma, delta and the number of ICs
+        let ic_0 = 2 + (2 + 3 * 4 + 1) * 64;
+        assert_eq!(packed[ic_0..ic_0 + 64], encode_num(&key.ic[0][0]).unwrap());
+        let simulation = simulate_verifier(&last, &options, &proof, &public).unwrap();
+        assert_eq!(simulation.outcome, Outcome::Accept);
+
+        let mut first = key.clone();
+        first.normalize_ic_constant(IcConstantPosition::First);
+        assert_eq!(first.ic, key.ic);
+    }
+
+    #[test]
+    fn jacobian_points_normalize_to_the_affine_key() {
+        let key = VerificationKey::from_value(sample_json()).unwrap();
+        let q = UBig::from_str_radix(Q, 16).unwrap();
+        // (x, y, 1) is (x * z^2, y * z^3, z) in Jacobian coordinates
+        let jacobian = |point: &[String], z: u32| {
+            let (x, y, z) = (
+                parse_num(&point[0]).unwrap(),
+                parse_num(&point[1]).unwrap(),
+                UBig::from(z),
+            );
+            vec![
+                (x * z.pow(2) % &q).to_string(),
+                (y * z.pow(3) % &q).to_string(),
+                z.to_string(),
+            ]
+        };
+        let mut projective = key.clone();
+        projective.vk_alpha_1 = jacobian(&key.vk_alpha_1, 7);
+        projective.ic[1] = jacobian(&key.ic[1], 0xdead);
+        assert_ne!(projective.to_packed().unwrap(), key.to_packed().unwrap());
+
+        projective.normalize_points().unwrap();
+        assert_eq!(projective.vk_alpha_1[..2], key.vk_alpha_1[..2]);
+        assert_eq!(projective.vk_alpha_1[2], "1");
+        assert_eq!(projective.to_packed().unwrap(), key.to_packed().unwrap());
+
+        projective.ic[2][2] = "0".to_string();
+        assert!(projective.normalize_points().is_err());
+    }
+}
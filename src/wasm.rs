@@ -0,0 +1,73 @@
+//! `wasm-bindgen` bindings, for generating verifiers from JavaScript without a
+//! Rust toolchain. Enabled with the `wasm` feature.
+
+use crate::calldata::encode_calldata;
+use crate::codegen::{generate_verifier_with, Abi, PublicInputOrder, Target, VerifierOptions};
+use crate::proof::{Proof, PublicSignals};
+use crate::vkey::VerificationKey;
+use wasm_bindgen::prelude::*;
+
+/// Generates a Huff verifier contract from a snarkjs verification key JSON string.
+#[wasm_bindgen(js_name = generateVerifier)]
+pub fn generate_verifier(vkey_json: &str) -> Result<String, JsError> {
+    generate_verifier_for(vkey_json, "huff", false)
+}
+
+/// Generates a verifier contract in the `target` language (`huff`, `solidity`, or
+/// `yul`) from a snarkjs verification key JSON string.
+#[wasm_bindgen(js_name = generateVerifierFor)]
+pub fn generate_verifier_for(
+    vkey_json: &str,
+    target: &str,
+    reverse_inputs: bool,
+) -> Result<String, JsError> {
+    let key = VerificationKey::from_json(vkey_json)?;
+    let options = VerifierOptions {
+        target: parse_target(target)?,
+        public_input_order: input_order(reverse_inputs),
+        ..VerifierOptions::default()
+    };
+    Ok(generate_verifier_with(&key, &options)?)
+}
+
+/// Packs a snarkjs verification key JSON string as the verifier's `VKEY` table.
+#[wasm_bindgen(js_name = packVerificationKey)]
+pub fn pack_verification_key(vkey_json: &str) -> Result<String, JsError> {
+    Ok(VerificationKey::from_json(vkey_json)?.to_packed()?)
+}
+
+/// Encodes the calldata for verifying a snarkjs proof with the generated verifier.
+#[wasm_bindgen(js_name = encodeCalldata)]
+pub fn encode_proof_calldata(
+    proof_json: &str,
+    public_json: &str,
+    reverse_inputs: bool,
+) -> Result<String, JsError> {
+    let proof = Proof::from_json(proof_json)?;
+    let public = PublicSignals::from_json(public_json)?;
+    Ok(encode_calldata(
+        &proof,
+        &public,
+        input_order(reverse_inputs),
+        Abi::default(),
+    )?)
+}
+
+////////////////////////////////////////////////////////////////
+//                      HELPER FUNCTIONS                      //
+////////////////////////////////////////////////////////////////
+
+/// Parses a target language by its CLI name.
+fn parse_target(target: &str) -> Result<Target, JsError> {
+    clap::ValueEnum::from_str(target, true)
+        .map_err(|_| JsError::new(&format!("Unknown target `{}`.", target)))
+}
+
+/// The public input order for the `reverse_inputs` flag.
+fn input_order(reverse_inputs: bool) -> PublicInputOrder {
+    if reverse_inputs {
+        PublicInputOrder::Reverse
+    } else {
+        PublicInputOrder::Forward
+    }
+}
@@ -0,0 +1,220 @@
+//! A reader for the binary `.zkey` files of [snarkjs](https://github.com/iden3/snarkjs),
+//! so that verifiers can be generated without exporting the verification key first.
+//!
+//! A zkey is a [`binfile`](crate::binfile) of sections, and its field elements are
+//! stored in Montgomery form.
+
+use crate::binfile::{Reader, Sections};
+use crate::error::{HuffvError, Result};
+use crate::vkey::{Curve, Protocol, VerificationKey};
+use ark_bn254::Fq;
+use ark_ff::{BigInt, BigInteger, PrimeField};
+
+////////////////////////////////////////////////////////////////
+//                         CONSTANTS                          //
+////////////////////////////////////////////////////////////////
+
+/// The magic bytes at the start of every zkey file
+pub const ZKEY_MAGIC: &[u8; 4] = b"zkey";
+
+/// The section with the protocol of the key
+const HEADER_SECTION: u32 = 1;
+
+/// The section with the Groth16 curve, sizes, and verification key points
+const GROTH16_HEADER_SECTION: u32 = 2;
+
+/// The section with the ICs
+const IC_SECTION: u32 = 3;
+
+/// The protocol id of Groth16 in the header section
+const GROTH16_PROTOCOL_ID: u32 = 1;
+
+/// The byte size of an F_q element
+const FQ_SIZE: usize = 32;
+
+////////////////////////////////////////////////////////////////
+//                           READER                           //
+////////////////////////////////////////////////////////////////
+
+/// Reads the verification key of a Groth16 BN254 zkey.
+///
+/// The key is the same as `snarkjs zkey export verificationkey` would export,
+/// without `vk_alphabeta_12`, which the verifier doesn't use.
+pub fn read_zkey(data: &[u8]) -> Result<VerificationKey> {
+    let sections = Sections::read(data, ZKEY_MAGIC)?;
+
+    let protocol_id = sections.section(HEADER_SECTION)?.u32()?;
+    if protocol_id != GROTH16_PROTOCOL_ID {
+        return Err(HuffvError::UnsupportedKey(format!(
+            "Found a zkey with protocol id {}, but only groth16 keys on bn128 are supported.",
+            protocol_id
+        )));
+    }
+
+    // The Groth16 header starts with the base field, which must be BN254's
+    let mut header = sections.section(GROTH16_HEADER_SECTION)?;
+    let n8q = header.u32()? as usize;
+    let q = header.bytes(n8q)?;
+    if n8q != FQ_SIZE || q != Fq::MODULUS.to_bytes_le() {
+        return Err(HuffvError::UnsupportedKey(
+            "Found a zkey on another curve, but only groth16 keys on bn128 are supported."
+                .to_string(),
+        ));
+    }
+    let n8r = header.u32()? as usize;
+    let _r = header.bytes(n8r)?;
+    let _n_vars = header.u32()?;
+    let n_public = header.u32()?;
+    let _domain_size = header.u32()?;
+    let vk_alpha_1 = header.g1("vk_alpha_1")?;
+    let _vk_beta_1 = header.g1("vk_beta_1")?;
+    let vk_beta_2 = header.g2("vk_beta_2")?;
+    let vk_gamma_2 = header.g2("vk_gamma_2")?;
+    let _vk_delta_1 = header.g1("vk_delta_1")?;
+    let vk_delta_2 = header.g2("vk_delta_2")?;
+
+    let mut ic_section = sections.section(IC_SECTION)?;
+    let ic = (0..=n_public)
+        .map(|i| ic_section.g1(&format!("IC[{}]", i)))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(VerificationKey {
+        protocol: Protocol::Groth16,
+        curve: Curve::Bn128,
+        n_public: n_public.into(),
+        vk_alpha_1,
+        vk_beta_2,
+        vk_gamma_2,
+        vk_delta_2,
+        vk_alphabeta_12: None,
+        ic,
+    })
+}
+
+/// Readers of the zkey encodings of field elements and points.
+impl Reader<'_> {
+    /// Reads an F_q element in Montgomery form as a decimal string.
+    fn fq(&mut self, name: &str) -> Result<String> {
+        let mut limbs = [0u64; 4];
+        for limb in &mut limbs {
+            *limb = self.u64()?;
+        }
+        let montgomery = BigInt::new(limbs);
+        if montgomery >= Fq::MODULUS {
+            return Err(self.error(format!("{} is not less than the field modulus.", name)));
+        }
+        Ok(Fq::new_unchecked(montgomery).to_string())
+    }
+
+    /// Reads an affine G1 point as snarkjs exports it: `[x, y, "1"]`.
+    fn g1(&mut self, name: &str) -> Result<Vec<String>> {
+        let x = self.fq(&format!("{}.x", name))?;
+        let y = self.fq(&format!("{}.y", name))?;
+        Ok(vec![x, y, "1".to_string()])
+    }
+
+    /// Reads an affine G2 point as snarkjs exports it:
+    /// `[[x.c0, x.c1], [y.c0, y.c1], ["1", "0"]]`.
+    fn g2(&mut self, name: &str) -> Result<Vec<Vec<String>>> {
+        let mut coordinate = |coord: &str| -> Result<Vec<String>> {
+            Ok(vec![
+                self.fq(&format!("{}.{}.c0", name, coord))?,
+                self.fq(&format!("{}.{}.c1", name, coord))?,
+            ])
+        };
+        let x = coordinate("x")?;
+        let y = coordinate("y")?;
+        Ok(vec![x, y, vec!["1".to_string(), "0".to_string()]])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::sample_key;
+    use std::str::FromStr;
+
+    /// Encodes a decimal F_q element in Montgomery form, as a zkey stores it.
+    fn fq(n: &str) -> Vec<u8> {
+        Fq::from_str(n).unwrap().0.to_bytes_le()
+    }
+
+    /// Encodes a minimal zkey with `protocol_id`, holding the points of `key`. The
+    /// points the reader skips are `key`'s alpha.
+    fn zkey(protocol_id: u32, key: &VerificationKey) -> Vec<u8> {
+        let g1 = |point: &Vec<String>| [fq(&point[0]), fq(&point[1])].concat();
+        let g2 = |point: &Vec<Vec<String>>| {
+            [&point[0], &point[1]]
+                .iter()
+                .flat_map(|coord| [fq(&coord[0]), fq(&coord[1])])
+                .collect::<Vec<_>>()
+                .concat()
+        };
+
+        let mut header = Vec::new();
+        header.extend(32u32.to_le_bytes());
+        header.extend(Fq::MODULUS.to_bytes_le());
+        header.extend(32u32.to_le_bytes());
+        header.extend(ark_bn254::Fr::MODULUS.to_bytes_le());
+        header.extend(8u32.to_le_bytes());
+        header.extend((key.n_public as u32).to_le_bytes());
+        header.extend(4u32.to_le_bytes());
+        header.extend(g1(&key.vk_alpha_1));
+        header.extend(g1(&key.vk_alpha_1));
+        header.extend(g2(&key.vk_beta_2));
+        header.extend(g2(&key.vk_gamma_2));
+        header.extend(g1(&key.vk_alpha_1));
+        header.extend(g2(&key.vk_delta_2));
+        let ics = key.ic.iter().flat_map(g1).collect::<Vec<_>>();
+
+        let mut data = ZKEY_MAGIC.to_vec();
+        data.extend(1u32.to_le_bytes());
+        data.extend(3u32.to_le_bytes());
+        for (section_type, section) in [
+            (HEADER_SECTION, protocol_id.to_le_bytes().to_vec()),
+            (GROTH16_HEADER_SECTION, header),
+            (IC_SECTION, ics),
+        ] {
+            data.extend(section_type.to_le_bytes());
+            data.extend((section.len() as u64).to_le_bytes());
+            data.extend(section);
+        }
+        data
+    }
+
+    #[test]
+    fn zkeys_read_the_same_key_as_their_json_export() {
+        let json = sample_key();
+        let key = read_zkey(&zkey(GROTH16_PROTOCOL_ID, &json)).unwrap();
+        assert_eq!(key.n_public, json.n_public);
+        assert_eq!(key.vk_alpha_1, json.vk_alpha_1);
+        assert_eq!(key.vk_beta_2, json.vk_beta_2);
+        assert_eq!(key.vk_gamma_2, json.vk_gamma_2);
+        assert_eq!(key.vk_delta_2, json.vk_delta_2);
+        assert_eq!(key.ic, json.ic);
+        assert_eq!(key.to_packed().unwrap(), json.to_packed().unwrap());
+    }
+
+    #[test]
+    fn truncated_zkeys_are_rejected() {
+        let data = zkey(GROTH16_PROTOCOL_ID, &sample_key());
+        for len in [2, 10, 30, data.len() - 200, data.len() - 1] {
+            assert!(
+                matches!(read_zkey(&data[..len]), Err(HuffvError::BinaryFile { .. })),
+                "{} of {} bytes",
+                len,
+                data.len()
+            );
+        }
+    }
+
+    #[test]
+    fn zkeys_of_other_protocols_are_rejected() {
+        // snarkjs's protocol id of PLONK
+        let data = zkey(2, &sample_key());
+        assert!(matches!(
+            read_zkey(&data),
+            Err(HuffvError::UnsupportedKey(_))
+        ));
+    }
+}
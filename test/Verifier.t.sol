@@ -12,6 +12,7 @@ interface IHuffVerifier {
     function scalar_mul(Pairing.G1Point memory p, uint s) external view returns (Pairing.G1Point memory r);
     function pairing(Pairing.G1Point[] memory p1, Pairing.G2Point[] memory p2) external view returns (bool);
     function verify(uint[2] memory a, uint[2][2] memory b, uint[2] memory c, uint[] memory input) external view returns (bool);
+    function VKEY_HASH() external view returns (bytes32);
 }
 
 contract VerifierTest is Test {
@@ -134,6 +135,11 @@ contract VerifierTest is Test {
         assertEq(huffMultiInputVerifier.verify(a, b, c, inputs), true);
     }
 
+    function testVkeyHash() public {
+        assertEq(huffSingleInputVerifier.VKEY_HASH(), 0xf4c4b28d52a11e5193e010e550096db2cc6867bfa9fc580b0cc60b1cfb519fce);
+        assertEq(huffMultiInputVerifier.VKEY_HASH(), 0x1b68caeb5a315e52bcf00f97eb6d4564d12a1dccc0ad350099668309a6280646);
+    }
+
     ////////////////////////////////////////////////////////////////
     //                          HELPERS                           //
     ////////////////////////////////////////////////////////////////
@@ -0,0 +1,357 @@
+//! Tests of the `huffv` command line, run against the built binary.
+
+use huff_snark_verifier::{
+    assemble_runtime_code, generate_verifier_with, parse_verification_key, EvmVersion,
+    VerifierOptions,
+};
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// The multi input sample verification key.
+const MULTI_INPUT_KEY: &str = "test/multi-input/sample_verification_key.json";
+
+/// The single input sample verification key.
+const SINGLE_INPUT_KEY: &str = "test/single-input/sample_verification_key.json";
+
+/// Runs `huffv` with `args`.
+fn huffv(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_huffv"))
+        .args(args)
+        .output()
+        .expect("huffv runs")
+}
+
+#[test]
+fn out_dir_requires_input_dir() {
+    let output = huffv(&["generate", "--out-dir", "unused", SINGLE_INPUT_KEY]);
+    assert!(!output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("`--out-dir` requires `--input-dir`"));
+}
+
+#[test]
+fn json_stream_prints_a_line_per_key() {
+    let dir = std::env::temp_dir().join(format!("huffv-cli-stream-{}", std::process::id()));
+    let keys = dir.join("keys");
+    std::fs::create_dir_all(&keys).unwrap();
+    std::fs::copy(MULTI_INPUT_KEY, keys.join("multi.json")).unwrap();
+    std::fs::copy(SINGLE_INPUT_KEY, keys.join("single.json")).unwrap();
+
+    let output = huffv(&[
+        "generate",
+        "--input-dir",
+        keys.to_str().unwrap(),
+        "--out-dir",
+        dir.join("verifiers").to_str().unwrap(),
+        "--json-stream",
+    ]);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let lines = stdout.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 2);
+    for (line, name) in lines.iter().zip(["multi", "single"]) {
+        let result: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert!(result["key"]
+            .as_str()
+            .unwrap()
+            .ends_with(&format!("{}.json", name)));
+        assert!(Path::new(result["output"].as_str().unwrap()).exists());
+        assert!(result.get("error").is_none());
+    }
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn cache_keys_change_with_the_options() {
+    let dir = std::env::temp_dir().join(format!("huffv-cli-cache-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let cache_key = |flags: &[&str]| {
+        let path = dir.join("cache-key.txt");
+        let args = [
+            &["generate", SINGLE_INPUT_KEY, "--cache-key-out"][..],
+            &[path.to_str().unwrap()],
+            flags,
+        ]
+        .concat();
+        assert!(huffv(&args).status.success());
+        std::fs::read_to_string(path).unwrap()
+    };
+
+    let plain = cache_key(&[]);
+    assert_eq!(plain.trim().len(), 2 + 64);
+    assert_eq!(cache_key(&[]), plain);
+    assert_ne!(cache_key(&["--opt", "size"]), plain);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn no_flags_override_the_config_file() {
+    let dir = std::env::temp_dir().join(format!("huffv-cli-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let config = dir.join("huffv.toml");
+    std::fs::write(&config, "emit-event = true\n").unwrap();
+    let config = config.to_str().unwrap();
+
+    let with_event = huffv(&["generate", "--config", config, SINGLE_INPUT_KEY]);
+    let without_event = huffv(&[
+        "generate",
+        "--config",
+        config,
+        "--no-emit-event",
+        SINGLE_INPUT_KEY,
+    ]);
+    let plain = huffv(&["generate", SINGLE_INPUT_KEY]);
+    assert!(with_event.status.success() && without_event.status.success());
+    assert_ne!(with_event.stdout, plain.stdout);
+    assert_eq!(without_event.stdout, plain.stdout);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn simulate_generates_the_verifier_with_the_config_file() {
+    let dir = std::env::temp_dir().join(format!("huffv-cli-simulate-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let config = dir.join("huffv.toml");
+    std::fs::write(&config, "emit-event = true\n").unwrap();
+    let config = config.to_str().unwrap();
+
+    let gas_used = |flags: &[&str]| {
+        let args = [
+            &["simulate", "--json", "--vk", MULTI_INPUT_KEY][..],
+            &["--proof", "test/multi-input/sample_proof.json"],
+            &["--public", "test/multi-input/sample_public.json"],
+            flags,
+        ]
+        .concat();
+        let output = huffv(&args);
+        assert!(output.status.success());
+        let simulation: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        simulation["gasUsed"].as_u64().unwrap()
+    };
+    // The event costs gas, so the verifier simulated is the one the config generates
+    let plain = gas_used(&[]);
+    assert!(gas_used(&["--config", config]) > plain);
+    assert_eq!(gas_used(&["--config", config, "--no-emit-event"]), plain);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn size_reports_the_assembled_runtime_code() {
+    let output = huffv(&["size", "--json", MULTI_INPUT_KEY]);
+    assert!(output.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+
+    let key = parse_verification_key(Path::new(MULTI_INPUT_KEY)).unwrap();
+    let options = VerifierOptions {
+        main: true,
+        ..VerifierOptions::default()
+    };
+    let contract = generate_verifier_with(&key, &options).unwrap();
+    let runtime = assemble_runtime_code(&contract, EvmVersion::default()).unwrap();
+    assert_eq!(report["nInputs"], 2);
+    assert_eq!(report["runtime"], runtime.len());
+}
+
+#[test]
+fn size_assembles_the_verifier_with_the_test_wrappers() {
+    for (key, wrapper) in [
+        (MULTI_INPUT_KEY, "test/multi-input/MultiInputWrapper.huff"),
+        (
+            SINGLE_INPUT_KEY,
+            "test/single-input/SingleInputWrapper.huff",
+        ),
+    ] {
+        let output = huffv(&["size", "--json", key, "--wrapper", wrapper]);
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        assert!(report["runtime"].as_u64().unwrap() > report["vkeyTable"].as_u64().unwrap());
+    }
+}
+
+#[test]
+fn verify_after_generate_blocks_a_key_that_rejects_the_proof() {
+    let output = std::env::temp_dir().join(format!("huffv-cli-{}.huff", std::process::id()));
+    let output = output.to_str().unwrap();
+
+    let mismatched = huffv(&[
+        "generate",
+        SINGLE_INPUT_KEY,
+        "-o",
+        output,
+        "--verify-after-generate",
+        "test/multi-input/sample_proof.json",
+        "test/single-input/sample_public.json",
+    ]);
+    assert!(!mismatched.status.success());
+    assert!(mismatched.stdout.is_empty());
+    assert!(!Path::new(output).exists());
+
+    let matching = huffv(&[
+        "generate",
+        SINGLE_INPUT_KEY,
+        "--verify-after-generate",
+        "test/single-input/sample_proof.json",
+        "test/single-input/sample_public.json",
+    ]);
+    assert!(matching.status.success());
+    assert_eq!(
+        matching.stdout,
+        huffv(&["generate", SINGLE_INPUT_KEY]).stdout
+    );
+}
+
+#[test]
+fn batch_generates_a_batch_verifier_and_its_calldata() {
+    let output = huffv(&["generate", MULTI_INPUT_KEY, "--batch"]);
+    assert!(output.status.success());
+    let contract = String::from_utf8(output.stdout).unwrap();
+    assert!(contract.contains("verifyBatch(uint256[8][],uint256[])"));
+    assert_eq!(
+        huffv(&["generate", MULTI_INPUT_KEY, "--batch", "--format", "json"])
+            .status
+            .code(),
+        Some(1)
+    );
+
+    // The selector, the offsets, the proofs, then the public inputs of both proofs
+    let proof = [
+        "test/multi-input/sample_proof.json",
+        "test/multi-input/sample_public.json",
+    ];
+    let output = huffv(
+        &[
+            &["calldata", "--batch"],
+            &proof[..],
+            &["--batch"],
+            &proof[..],
+        ]
+        .concat(),
+    );
+    assert!(output.status.success());
+    let calldata = String::from_utf8(output.stdout).unwrap();
+    let public: Vec<String> =
+        serde_json::from_str(&std::fs::read_to_string(proof[1]).unwrap()).unwrap();
+    let words = 3 + 2 * 8 + 1 + 2 * public.len();
+    assert_eq!(calldata.trim().len(), 2 + 2 * (4 + words * 0x20));
+}
+
+#[test]
+fn disasm_out_writes_the_annotated_disassembly() {
+    let disasm = std::env::temp_dir().join(format!("huffv-cli-{}.asm", std::process::id()));
+    let disasm_path = disasm.to_str().unwrap();
+
+    let output = huffv(&[
+        "generate",
+        MULTI_INPUT_KEY,
+        "--precompile-addresses",
+        "ec-pairing=0x0108",
+        "--disasm-out",
+        disasm_path,
+    ]);
+    assert!(output.status.success());
+    let disassembly = std::fs::read_to_string(&disasm).unwrap();
+    assert!(disassembly
+        .lines()
+        .any(|line| line.contains("STATICCALL") && line.contains("; ecPairing (0x0108)")));
+    std::fs::remove_file(disasm).unwrap();
+}
+
+#[test]
+fn packages_round_trip_and_tampering_is_detected() {
+    let dir = std::env::temp_dir().join(format!("huffv-cli-package-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let package = dir.join("verifier.pkg");
+    let package_path = package.to_str().unwrap();
+
+    assert!(huffv(&["package", MULTI_INPUT_KEY, "-o", package_path])
+        .status
+        .success());
+    let verified = huffv(&["verify-package", package_path]);
+    assert!(verified.status.success());
+    assert!(String::from_utf8_lossy(&verified.stdout).contains("The package is intact."));
+
+    let extracted = dir.join("extracted");
+    let unpackaged = huffv(&["unpackage", package_path, "-o", extracted.to_str().unwrap()]);
+    assert!(unpackaged.status.success());
+    let key = parse_verification_key(&extracted.join("verification_key.json")).unwrap();
+    let expected = parse_verification_key(Path::new(MULTI_INPUT_KEY)).unwrap();
+    assert_eq!(key.ic, expected.ic);
+    assert!(extracted.join("Verifier.huff").exists() && extracted.join("manifest.json").exists());
+
+    // Edit the verifier inside the package
+    let contents = std::fs::read_to_string(&package).unwrap();
+    let edited = contents.replacen("#define macro MAIN()", "#define macro MAIN( )", 1);
+    assert_ne!(edited, contents);
+    std::fs::write(&package, edited).unwrap();
+    let tampered = huffv(&["verify-package", package_path]);
+    assert!(!tampered.status.success());
+    assert!(String::from_utf8_lossy(&tampered.stderr).contains("was modified"));
+    let tampered_extract = dir.join("tampered");
+    assert!(!huffv(&[
+        "unpackage",
+        package_path,
+        "-o",
+        tampered_extract.to_str().unwrap()
+    ])
+    .status
+    .success());
+    assert!(!tampered_extract.exists());
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn deploy_requires_a_private_key() {
+    let output = Command::new(env!("CARGO_BIN_EXE_huffv"))
+        .args(["deploy", MULTI_INPUT_KEY, "--rpc-url", "http://127.0.0.1:1"])
+        .env_remove("HUFFV_PRIVATE_KEY")
+        .output()
+        .expect("huffv runs");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("HUFFV_PRIVATE_KEY"));
+}
+
+#[test]
+fn deploy_generates_the_verifier_with_the_config_file() {
+    let dir = std::env::temp_dir().join(format!("huffv-cli-deploy-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let config = dir.join("huffv.toml");
+    std::fs::write(&config, "target = \"solidity\"\n").unwrap();
+
+    // A Solidity verifier can't be assembled, so deploying fails before any request
+    let output = Command::new(env!("CARGO_BIN_EXE_huffv"))
+        .args(["deploy", MULTI_INPUT_KEY, "--rpc-url", "http://127.0.0.1:1"])
+        .args(["--config", config.to_str().unwrap()])
+        .env(
+            "HUFFV_PRIVATE_KEY",
+            "0x0000000000000000000000000000000000000000000000000000000000000001",
+        )
+        .output()
+        .expect("huffv runs");
+    assert!(!output.status.success());
+    assert!(String::from_utf8_lossy(&output.stderr).contains("--target huff"));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn capabilities_list_the_version_and_supported_keys() {
+    let output = huffv(&["capabilities", "--json"]);
+    assert!(output.status.success());
+    let capabilities: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(capabilities["version"], env!("CARGO_PKG_VERSION"));
+    let contains = |list: &str, entry: &str| {
+        capabilities[list]
+            .as_array()
+            .unwrap()
+            .iter()
+            .any(|e| e == entry)
+    };
+    assert!(contains("inputFormats", "snarkjs"));
+    assert!(contains("protocols", "groth16"));
+    assert!(contains("curves", "bn128"));
+    assert!(contains("evmVersions", "cancun"));
+    assert_eq!(contains("features", "ffi"), cfg!(feature = "ffi"));
+}